@@ -1,15 +1,370 @@
 extern crate bindgen;
 
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// libwebp libraries linked from a `WEBP_DIR` installation, in link order.
+const LIBRARIES: &[&str] = &["webpdemux", "webpmux", "webp", "sharpyuv"];
+
+/// A libwebp release as `(major, minor)`.
+type Version = (u32, u32);
+
+/// cfgs emitted when the linked libwebp is at least the given release, so the
+/// crate still builds against older distribution packages.
+const VERSION_CFGS: &[(&str, Version)] = &[
+    // WebPAnimDecoder and WebPAnimEncoder
+    ("libwebp_0_5", (0, 5)),
+    // WebPFree
+    ("libwebp_1_0", (1, 0)),
+];
+
+/// The libwebp release the prebuilt bindings describe.
+const PREBUILT_VERSION: Version = (1, 4);
+
+/// Libraries only needed by the encoder and mux editor.
+const ENCODE_LIBRARIES: &[&str] = &["webpmux", "sharpyuv"];
+
+/// Source directories of libwebp compiled when it is built from source.
+const VENDORED_DIRS: &[&str] = &["src/dec", "src/demux", "src/dsp", "src/utils"];
+
+/// Source directories only compiled with the `encode` feature.
+const VENDORED_ENCODE_DIRS: &[&str] = &["sharpyuv", "src/enc", "src/mux"];
 
 fn main() {
-    // Use pkg-config to find libwebp and libwebpdemux
-    let webp_lib =
-        pkg_config::probe_library("libwebp").expect("libwebp not found. Ensure it is installed.");
-    let webpdemux_lib = pkg_config::probe_library("libwebpdemux")
-        .expect("libwebpdemux not found. Ensure it is installed.");
+    // `libwebp` is set whenever libwebp is linked; `libwebp_encode` when the
+    // encoder and mux editor can be built on top of it
+    println!("cargo:rustc-check-cfg=cfg(libwebp)");
+    println!("cargo:rustc-check-cfg=cfg(libwebp_encode)");
+    for (cfg, _) in VERSION_CFGS {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+    }
+
+    // wasm32 has no system libwebp. With `pure-rust` the decoder does not need
+    // it, so the libwebp-backed modules are left out instead of failing the build.
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+    let wasm = target_arch == "wasm32";
+    if wasm && env::var_os("CARGO_FEATURE_PURE_RUST").is_some() {
+        return;
+    }
+
+    // With `dynamic-loading` the decoder opens libwebpdemux at runtime and
+    // nothing is linked; the prebuilt bindings provide the types, and the
+    // encoder and mux editor, which need link-time symbols, are left out.
+    if env::var_os("CARGO_FEATURE_DYNAMIC_LOADING").is_some() {
+        emit_version_cfgs(PREBUILT_VERSION);
+        return;
+    }
+    println!("cargo:rustc-cfg=libwebp");
+
+    // Mobile toolchains ship without pkg-config or a system libwebp, so
+    // libwebp is compiled from source and linked statically there, as it is
+    // everywhere with the `vendored` feature.
+    let vendored = env::var_os("CARGO_FEATURE_VENDORED").is_some();
+    let (include_paths, version) = if vendored || target_os == "ios" || target_os == "android" {
+        build_vendored(&target_os)
+    } else {
+        // musl binaries are meant to run without shared libraries, and a
+        // libwebp compiled to wasm can only be linked statically.
+        println!("cargo:rerun-if-env-changed=WEBP_STATIC");
+        let statik = wasm || target_env == "musl" || env::var_os("WEBP_STATIC").is_some();
+        find_system(statik, &target_os, &target_env)
+    };
+
+    // An undetectable version (e.g. from vcpkg) is assumed to be current.
+    let version = version.unwrap_or(PREBUILT_VERSION);
+
+    // Checked-in bindings avoid the libclang dependency of bindgen. They
+    // declare the newest API, but the version cfgs follow the linked library
+    // so the crate never calls a function an older libwebp lacks.
+    if env::var_os("CARGO_FEATURE_PREBUILT_BINDINGS").is_some() {
+        emit_version_cfgs(version.min(PREBUILT_VERSION));
+    } else {
+        emit_version_cfgs(version);
+        generate_bindings(&include_paths);
+    }
+}
 
+/// Emits the [`VERSION_CFGS`] satisfied by `version`, and `libwebp_encode`
+/// when the encoder can be built.
+fn emit_version_cfgs(version: Version) {
+    for (cfg, required) in VERSION_CFGS {
+        if version >= *required {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+    // Dynamic loading emits the cfgs without linking anything.
+    let linked = env::var_os("CARGO_FEATURE_DYNAMIC_LOADING").is_none();
+    if linked && encode() {
+        if version >= (0, 5) {
+            println!("cargo:rustc-cfg=libwebp_encode");
+        } else {
+            println!(
+                "cargo:warning=libwebp {}.{} has no animation encoder; the encoder and mux \
+                 editor are disabled",
+                version.0, version.1
+            );
+        }
+    }
+}
+
+/// Parses the `major.minor` prefix of a version string such as `1.4.0`.
+fn parse_version(version: &str) -> Option<Version> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |minor| minor.parse().ok())?;
+    Some((major, minor))
+}
+
+/// Reads the version from the `libwebp.pc` file of an installation.
+fn pc_version(lib_dir: &Path) -> Option<Version> {
+    let pc = fs::read_to_string(lib_dir.join("pkgconfig/libwebp.pc")).ok()?;
+    pc.lines()
+        .find_map(|line| line.strip_prefix("Version:"))
+        .and_then(parse_version)
+}
+
+/// Locates an installed libwebp, emits the link flags and returns the include
+/// paths.
+///
+/// `WEBP_DIR` and `WEBP_INCLUDE_DIR`/`WEBP_LIB_DIR` take precedence, then
+/// vcpkg on MSVC, then pkg-config, then the usual install prefixes. With
+/// `statik` only the static archives are linked.
+fn find_system(statik: bool, target_os: &str, target_env: &str) -> (Vec<PathBuf>, Option<Version>) {
+    let msvc = target_env == "msvc";
+    for var in ["WEBP_DIR", "WEBP_INCLUDE_DIR", "WEBP_LIB_DIR"] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+    if let Some(dir) = env::var_os("WEBP_DIR").map(PathBuf::from) {
+        return link_webp(&dir.join("include"), &dir.join("lib"), msvc, statik);
+    }
+    match (env::var_os("WEBP_INCLUDE_DIR"), env::var_os("WEBP_LIB_DIR")) {
+        (Some(include_dir), Some(lib_dir)) => {
+            return link_webp(Path::new(&include_dir), Path::new(&lib_dir), msvc, statik);
+        }
+        (None, None) => {}
+        _ => panic!("WEBP_INCLUDE_DIR and WEBP_LIB_DIR must be set together"),
+    }
+
+    // Windows rarely has pkg-config; vcpkg is the usual source of libwebp there.
+    if target_os == "windows" && msvc {
+        match vcpkg::Config::new().find_package("libwebp") {
+            Ok(lib) => return (lib.include_paths, None),
+            Err(e) => println!("cargo:warning=vcpkg could not find libwebp: {}", e),
+        }
+    }
+
+    let pkg_config_error = match probe_system(statik) {
+        Ok(found) => return found,
+        Err(e) => e,
+    };
+
+    if let Some(prefix) = fallback_prefixes(target_os)
+        .into_iter()
+        .find(|prefix| prefix.join("include/webp/decode.h").is_file())
+    {
+        println!(
+            "cargo:warning=pkg-config could not find libwebp; using {}",
+            prefix.display()
+        );
+        return link_webp(&prefix.join("include"), &prefix.join("lib"), msvc, statik);
+    }
+
+    panic!(
+        "libwebp not found. Install it (see the README), or set WEBP_DIR, or \
+         WEBP_INCLUDE_DIR and WEBP_LIB_DIR, or enable the `vendored` feature.\n\
+         pkg-config: {}",
+        pkg_config_error
+    );
+}
+
+/// Install prefixes searched when pkg-config fails, in order: the active
+/// conda environment, Homebrew, MacPorts and the default Unix prefixes.
+fn fallback_prefixes(target_os: &str) -> Vec<PathBuf> {
+    println!("cargo:rerun-if-env-changed=CONDA_PREFIX");
+    println!("cargo:rerun-if-env-changed=HOMEBREW_PREFIX");
+    let mut prefixes = Vec::new();
+    if let Some(conda) = env::var_os("CONDA_PREFIX").map(PathBuf::from) {
+        // conda keeps native libraries under `Library` on Windows.
+        if target_os == "windows" {
+            prefixes.push(conda.join("Library"));
+        }
+        prefixes.push(conda);
+    }
+    if let Some(homebrew) = env::var_os("HOMEBREW_PREFIX") {
+        prefixes.push(PathBuf::from(homebrew));
+    }
+    for prefix in ["/opt/homebrew", "/usr/local", "/opt/local", "/usr"] {
+        prefixes.push(PathBuf::from(prefix));
+    }
+    prefixes
+}
+
+/// Links the libwebp libraries in `lib_dir` and returns `include_dir` along
+/// with the version recorded in the installation's pkg-config file, if any.
+///
+/// MSVC libraries are named `libwebp.lib` and so on, as in vcpkg and the
+/// official Windows binaries. `sharpyuv` is only linked when present, since
+/// libwebp before 1.3 does not have it. With `statik` the archives are linked
+/// along with the system libraries they depend on.
+fn link_webp(
+    include_dir: &Path,
+    lib_dir: &Path,
+    msvc: bool,
+    statik: bool,
+) -> (Vec<PathBuf>, Option<Version>) {
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    let kind = if statik { "static=" } else { "" };
+    for name in LIBRARIES {
+        if ENCODE_LIBRARIES.contains(name) && !encode() {
+            continue;
+        }
+        if *name == "sharpyuv" && !has_library(lib_dir, name) {
+            continue;
+        }
+        if msvc {
+            println!("cargo:rustc-link-lib={}lib{}", kind, name);
+        } else {
+            println!("cargo:rustc-link-lib={}{}", kind, name);
+        }
+    }
+    if statik && env::var("CARGO_CFG_TARGET_FAMILY").as_deref() == Ok("unix") {
+        println!("cargo:rustc-link-lib=m");
+        println!("cargo:rustc-link-lib=pthread");
+    }
+    (vec![include_dir.to_path_buf()], pc_version(lib_dir))
+}
+
+/// Returns whether `lib_dir` contains a library called `name`, with or
+/// without the `lib` prefix and with any extension.
+fn has_library(lib_dir: &Path, name: &str) -> bool {
+    let prefixed = format!("lib{}.", name);
+    let plain = format!("{}.", name);
+    fs::read_dir(lib_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .any(|entry| {
+            let file = entry.file_name().to_string_lossy().into_owned();
+            file.starts_with(&prefixed) || file.starts_with(&plain)
+        })
+}
+
+/// Finds libwebp, libwebpdemux and libwebpmux with pkg-config, emits the link
+/// flags and returns the include paths and the libwebp version.
+///
+/// With `statik` the archives and their private dependencies are linked.
+fn probe_system(statik: bool) -> Result<(Vec<PathBuf>, Option<Version>), pkg_config::Error> {
+    let mut config = pkg_config::Config::new();
+    config.statik(statik);
+    // Probe everything before emitting any flags, so a partial installation
+    // falls through to the next strategy cleanly.
+    config.cargo_metadata(false);
+    let mut libs = vec![config.probe("libwebp")?, config.probe("libwebpdemux")?];
+    if encode() {
+        libs.push(config.probe("libwebpmux")?);
+        // Static libwebp 1.3+ needs libsharpyuv for RGB to YUV conversion;
+        // older releases do not have it.
+        if statik {
+            libs.extend(config.probe("libsharpyuv").ok());
+        }
+    }
+
+    // Ensure all libraries are linked
+    let kind = if statik { "static=" } else { "" };
+    for lib in libs.iter().flat_map(|lib| &lib.libs) {
+        println!("cargo:rustc-link-lib={}{}", kind, lib);
+    }
+
+    // Add any library paths
+    for lib_path in libs.iter().flat_map(|lib| &lib.link_paths) {
+        println!("cargo:rustc-link-search={}", lib_path.display());
+    }
+
+    let include_paths = libs
+        .iter()
+        .flat_map(|lib| lib.include_paths.iter().cloned())
+        .collect();
+    Ok((include_paths, parse_version(&libs[0].version)))
+}
+
+/// Compiles libwebp from source with `cc`, links it statically and returns the
+/// include path of its public headers and the version of the sources.
+///
+/// The sources are taken from `LIBWEBP_SRC_DIR`, or from the copy bundled in
+/// `vendor/libwebp`.
+fn build_vendored(target_os: &str) -> (Vec<PathBuf>, Option<Version>) {
+    println!("cargo:rerun-if-env-changed=LIBWEBP_SRC_DIR");
+    let source = env::var_os("LIBWEBP_SRC_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("vendor/libwebp")
+        });
+    if !source.join("src/webp/decode.h").is_file() {
+        panic!(
+            "libwebp sources not found at {}. Point LIBWEBP_SRC_DIR at a libwebp checkout.",
+            source.display()
+        );
+    }
+
+    let mut build = cc::Build::new();
+    build
+        .include(&source)
+        .include(source.join("src"))
+        .define("WEBP_USE_THREAD", None)
+        .warnings(false);
+    for dir in VENDORED_DIRS {
+        add_c_files(&mut build, &source.join(dir));
+    }
+    if encode() {
+        for dir in VENDORED_ENCODE_DIRS {
+            add_c_files(&mut build, &source.join(dir));
+        }
+    }
+    build.compile("webp");
+
+    // Bionic keeps the math functions libwebp uses in a separate library.
+    if target_os == "android" {
+        println!("cargo:rustc-link-lib=m");
+    }
+
+    (vec![source.join("src")], source_version(&source))
+}
+
+/// Reads the version from the decoder's `DEC_MAJ_VERSION` and
+/// `DEC_MIN_VERSION` defines in a libwebp source tree.
+fn source_version(source: &Path) -> Option<Version> {
+    let header = fs::read_to_string(source.join("src/dec/vp8i_dec.h")).ok()?;
+    let define = |name: &str| {
+        header.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix("#define ")?
+                .strip_prefix(name)?
+                .trim()
+                .parse()
+                .ok()
+        })
+    };
+    Some((define("DEC_MAJ_VERSION")?, define("DEC_MIN_VERSION")?))
+}
+
+/// Adds every C file in `dir` to `build`.
+fn add_c_files(build: &mut cc::Build, dir: &Path) {
+    let entries =
+        fs::read_dir(dir).unwrap_or_else(|e| panic!("cannot read {}: {}", dir.display(), e));
+    for entry in entries {
+        let path = entry.expect("cannot read libwebp sources").path();
+        if path.extension().is_some_and(|extension| extension == "c") {
+            println!("cargo:rerun-if-changed={}", path.display());
+            build.file(path);
+        }
+    }
+}
+
+/// Runs bindgen on `wrapper.h` and writes the bindings to `$OUT_DIR/bindings.rs`.
+fn generate_bindings(include_paths: &[PathBuf]) {
     // Invalidate the built crate whenever the wrapper changes
     println!("cargo:rerun-if-changed=wrapper.h");
 
@@ -24,12 +379,13 @@ fn main() {
         .allowlist_var("WEBP.*")
         .allowlist_var("WebP.*");
 
-    // Add include paths from pkg-config to bindgen
-    for include_path in webp_lib
-        .include_paths
-        .iter()
-        .chain(webpdemux_lib.include_paths.iter())
-    {
+    // Leave out `encode.h` and `mux.h` unless the encoder is built
+    if !encode() {
+        builder = builder.clang_arg("-DNANNOU_WEBP_NO_ENCODE");
+    }
+
+    // Add include paths to bindgen
+    for include_path in include_paths {
         builder = builder.clang_arg(format!("-I{}", include_path.display()));
     }
 
@@ -41,18 +397,9 @@ fn main() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
+}
 
-    // Ensure both libraries are linked
-    for lib in webp_lib.libs.iter().chain(webpdemux_lib.libs.iter()) {
-        println!("cargo:rustc-link-lib={}", lib);
-    }
-
-    // Add any library paths
-    for lib_path in webp_lib
-        .link_paths
-        .iter()
-        .chain(webpdemux_lib.link_paths.iter())
-    {
-        println!("cargo:rustc-link-search={}", lib_path.display());
-    }
+/// Returns whether the `encode` feature is enabled.
+fn encode() -> bool {
+    env::var_os("CARGO_FEATURE_ENCODE").is_some()
 }
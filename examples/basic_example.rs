@@ -9,13 +9,9 @@ fn model(app: &App) -> Model {
     // Create a new window
     app.new_window().view(view).build().unwrap();
 
-    // Load the WEBP animation
-    let assets = app.assets_path().expect("Failed to find assets directory");
-    let webp_path = assets.join("animation.webp"); // Place 'animation.webp' in the 'assets' directory
-
-    // Initialize the animation
+    // Load the WEBP animation from 'assets/animation.webp'
     let animation =
-        WebpAnimation::from_file(&webp_path, app).expect("Failed to load WEBP animation");
+        WebpAnimation::from_assets(app, "animation.webp").expect("Failed to load WEBP animation");
 
     Model { animation }
 }
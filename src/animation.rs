@@ -1,28 +1,143 @@
-use crate::decoder::WebpDecoder;
-use crate::frame::WebpFrame;
+use crate::budget::{self, Tracker};
+use crate::builder::{PlayerSettings, WebpAnimationBuilder};
+use crate::clock::Clock;
+#[cfg(feature = "texture-compression")]
+use crate::compression::{self, TextureCompression};
+use crate::data::{decode_first_frame, MemoryReport, WebpAnimationData};
+use crate::decoder::ColorMode;
+#[cfg(all(feature = "spill-to-disk", libwebp))]
+use crate::demux::WebpDemuxer;
+use crate::drawing::DrawWebpExt;
+#[cfg(libwebp_encode)]
+use crate::encoder::EncodeJob;
+use crate::error::{Result, WebpAnimationError};
+#[cfg(feature = "ffmpeg")]
+use crate::export;
+use crate::formats::SequenceTiming;
+use crate::frame::{OpaqueBounds, WebpFrame};
+#[cfg(feature = "hot-reload")]
+use crate::hot_reload::FileWatcher;
+use crate::loader::{LoadHandle, LoadRecovery};
+use crate::playback::{self, PlaybackDirection, PlaybackSnapshot, Playhead};
+use crate::settings::{AnimationSettings, Prefetch, TextureFilter};
+#[cfg(feature = "spill-to-disk")]
+use crate::spill::{SpillFile, SpilledFrames};
+#[cfg(libwebp)]
+use crate::stream::WebpStream;
+use crate::uploader::TextureUploader;
+use nannou::draw::RendererBuilder;
+#[cfg(all(feature = "spill-to-disk", libwebp))]
+use nannou::image::RgbaImage;
+use nannou::image::{DynamicImage, GenericImageView, ImageFormat, Rgba};
 use nannou::prelude::*;
-use nannou::wgpu::Texture;
-use std::path::Path;
-use std::time::Instant;
-
-/// Represents a WebP animation, handling frame data, playback control, and rendering.
-pub struct WebpAnimation {
-    /// Collection of frames in the animation.
-    frames: Vec<WebpFrame>,
-    /// Index of the current frame in the animation sequence.
-    current_frame_index: usize,
-    /// Time when the last frame was rendered, used for timing control.
-    last_frame_time: Instant,
-    /// Indicates whether the animation should loop when it reaches the end.
-    is_looping: bool,
-    /// Textures for each frame, generated from the images in the animation.
-    textures: Vec<Texture>,
+use nannou::wgpu::{self, DeviceQueuePair, Texture};
+#[cfg(all(feature = "spill-to-disk", libwebp))]
+use std::cell::Cell;
+use std::fmt;
+use std::io;
+#[cfg(libwebp)]
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Determines how the animation transitions from one frame to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Each frame is shown unchanged for its full duration.
+    #[default]
+    Step,
+    /// Frame N is blended into frame N+1 according to the fractional playhead
+    /// position, producing smooth slow-motion instead of visible stepping.
+    ///
+    /// The blend happens on the GPU when the player is drawn with
+    /// [`DrawWebpExt::webp`], which draws frame N+1
+    /// over frame N with the fraction as its alpha.
+    Crossfade,
+}
+
+/// Plays decoded animation frames, handling textures, playback control, and rendering.
+///
+/// The frames themselves live in a [`WebpAnimationData`], which can be decoded
+/// without a GPU and turned into a player with [`WebpAnimationData::into_player`].
+///
+/// Cloning a player is cheap: the clone shares the decoded frames and their
+/// textures but keeps its own playhead, so one asset can be shown many times at
+/// different positions without re-decoding or duplicating GPU memory.
+pub struct WebpAnimationPlayer {
+    /// Decoded frames of the animation.
+    data: WebpAnimationData,
+    /// The current frame and the time spent on it. Not shared with clones.
+    playhead: Playhead,
+    /// Whether textures are uploaded on first display instead of up front.
+    lazy_textures: bool,
+    /// Textures for each frame, generated from the images in the animation and
+    /// shared between clones. With lazy uploads a texture stays empty until its
+    /// frame is first shown.
+    textures: Arc<[OnceLock<Texture>]>,
+    /// Bounding boxes of the visible pixels of each frame, computed on first use
+    /// and shared between clones.
+    opaque_bounds: Arc<[OnceLock<Option<OpaqueBounds>>]>,
+    /// Whether the bounding boxes are computed up front instead of on first use.
+    precompute_opaque_bounds: bool,
+    /// How consecutive frames are presented.
+    interpolation: Interpolation,
+    /// How frame textures are sampled when drawn.
+    filter: TextureFilter,
+    /// Device and queue used to upload lazy textures.
+    device_queue_pair: Arc<DeviceQueuePair>,
+    /// Block compression format frame textures are transcoded to.
+    #[cfg(feature = "texture-compression")]
+    texture_compression: TextureCompression,
+    /// The texture format `texture_compression` resolved to for the current
+    /// frames, or `None` for uncompressed RGBA8.
+    #[cfg(feature = "texture-compression")]
+    texture_format: Option<wgpu::TextureFormat>,
+    /// Watches the source file when hot reloading is enabled. Not shared with clones.
+    #[cfg(feature = "hot-reload")]
+    watcher: Option<FileWatcher>,
+    /// The full animation still decoding behind a progressive preview. Not
+    /// shared with clones.
+    pending: Option<LoadHandle>,
+    /// Why the progressive load failed, until taken.
+    load_error: Option<WebpAnimationError>,
+    /// The file the frames were decoded from and its pixel layout, if known.
+    source: Option<(PathBuf, ColorMode)>,
+    /// How far ahead of the playhead textures are staged in the background.
+    prefetch: Prefetch,
+    /// Stages the textures of upcoming frames, created on first use. Not
+    /// shared with clones.
+    uploader: Option<TextureUploader>,
+    /// Reports the memory of this player to the crate-wide budget. Not shared
+    /// with clones.
+    budget: Arc<Tracker>,
+    /// Canvas size once the decoded pixels were released with
+    /// [`release_cpu_frames`](Self::release_cpu_frames), or `None` while the
+    /// frames still hold them.
+    released_size: Option<(u32, u32)>,
+    /// The frames paged out to a temporary file by
+    /// [`spill_to_disk`](Self::spill_to_disk), or `None` while every frame is
+    /// in memory.
+    #[cfg(feature = "spill-to-disk")]
+    spill: Option<SpilledFrames>,
+    /// Bytes of decoded frames above which they are spilled to disk.
+    #[cfg(feature = "spill-to-disk")]
+    spill_threshold: Option<u64>,
+    /// Delivers the remaining frames of an animation played from a stream.
+    /// Not shared with clones.
+    #[cfg(libwebp)]
+    stream: Option<WebpStream>,
 }
 
-impl WebpAnimation {
+/// The name of [`WebpAnimationPlayer`] used before decoding and playback were split.
+pub type WebpAnimation = WebpAnimationPlayer;
+
+impl WebpAnimationPlayer {
     /// Creates a new `WebpAnimation` instance by loading frames from a WebP file.
     ///
     /// This function decodes the WebP file at the given path and prepares the animation for playback.
+    /// With the `gif`, `apng` and `avif` features, animated GIFs, PNGs and AVIFs
+    /// are accepted too.
     ///
     /// # Parameters
     ///
@@ -33,31 +148,378 @@ impl WebpAnimation {
     ///
     /// A `Result` containing:
     /// - `Ok(WebpAnimation)`: The animation instance if successful.
-    /// - `Err(String)`: An error message if loading fails.
+    /// - `Err(WebpAnimationError)`: The reason loading failed.
     ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be decoded or contains no frames.
-    pub fn from_file<P: AsRef<Path>>(path: P, app: &App) -> Result<Self, String> {
-        let frames = WebpDecoder::decode(path)?;
+    pub fn from_file<P: AsRef<Path>>(path: P, app: &App) -> Result<Self> {
+        Self::builder(path).build(app)
+    }
 
-        if frames.is_empty() {
-            return Err("No frames found in the animation".to_string());
+    /// Creates a new `WebpAnimation` from a file in the sketch's `assets` directory.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, used to locate the assets
+    ///   directory and to create textures.
+    /// - `path`: Path of the WebP file relative to the assets directory, e.g.
+    ///   `"subdir/animation.webp"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the resolved path if the assets directory or the
+    /// file cannot be found, or if the file cannot be decoded.
+    pub fn from_assets<P: AsRef<Path>>(app: &App, path: P) -> Result<Self> {
+        let assets = app.assets_path().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("assets directory not found: {}", e),
+            )
+        })?;
+        let path = assets.join(path);
+        if !path.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("animation not found at {}", path.display()),
+            )
+            .into());
         }
+        Self::from_file(path, app)
+    }
 
-        // Create textures from images using Nannou's Texture
-        let textures: Vec<Texture> = frames
-            .iter()
-            .map(|frame| Texture::from_image(app, &frame.image))
-            .collect();
-
-        Ok(Self {
-            frames,
-            current_frame_index: 0,
-            last_frame_time: Instant::now(),
-            is_looping: true,
+    /// Creates an animation from an encoded file held in memory; see
+    /// [`WebpAnimationData::from_bytes`].
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: The encoded animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded or contains no frames.
+    pub fn from_bytes(data: &[u8], app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::from_bytes(data)?.into_player(app))
+    }
+
+    /// Creates an animation from a directory of numbered PNG or JPEG frames; see
+    /// [`WebpAnimationData::from_image_sequence`].
+    ///
+    /// # Parameters
+    ///
+    /// - `dir`: The directory containing the frames.
+    /// - `timing`: The frame rate or the duration of every frame.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frames cannot be loaded or the timing does not
+    /// match them.
+    pub fn from_image_sequence<P: AsRef<Path>>(
+        dir: P,
+        timing: &SequenceTiming,
+        app: &App,
+    ) -> Result<Self> {
+        Ok(WebpAnimationData::from_image_sequence(dir, timing)?.into_player(app))
+    }
+
+    /// Creates an animation from frames built in code, e.g. procedurally generated
+    /// `DynamicImage`s, so they can be played and drawn like a decoded file.
+    ///
+    /// # Parameters
+    ///
+    /// - `frames`: The frames of the animation, in playback order.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `frames` is empty.
+    pub fn from_frames(frames: Vec<WebpFrame>, app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::from_frames(frames)?.into_player(app))
+    }
+
+    /// Creates an animation that shows the first frame right away while the
+    /// remaining frames decode on a background thread.
+    ///
+    /// [`update`](Self::update) swaps in the full animation once it is ready
+    /// and starts playback from its first frame, so loading never leaves the
+    /// screen blank. The first frame is decoded with
+    /// [`WebpAnimationData::from_file_first_frame`]; formats that cannot be
+    /// decoded partially are loaded synchronously instead.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the first frame cannot be decoded. Errors of the
+    /// background decode are reported by [`take_load_error`](Self::take_load_error).
+    pub fn load_progressive<P: AsRef<Path>>(path: P, app: &App) -> Result<Self> {
+        let Some(frame) = decode_first_frame(path.as_ref()) else {
+            return Self::from_file(path, app);
+        };
+        Self::with_placeholder(frame, path, app)
+    }
+
+    /// Creates an animation that shows `poster` while the file at `path`
+    /// decodes on a background thread, like
+    /// [`load_progressive`](Self::load_progressive) with a placeholder of your
+    /// choice, e.g. a branded loading card or a hand-picked still.
+    ///
+    /// The poster is drawn at its own size, so give it the dimensions of the
+    /// animation to keep the layout from jumping when the animation appears.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `poster`: The image shown until decoding has finished.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Errors of the background decode are not returned here but reported by
+    /// [`take_load_error`](Self::take_load_error); the poster stays on screen.
+    pub fn load_with_poster<P: AsRef<Path>>(
+        path: P,
+        poster: DynamicImage,
+        app: &App,
+    ) -> Result<Self> {
+        let frame = WebpFrame {
+            image: DynamicImage::ImageRgba8(poster.to_rgba8()),
+            duration: Duration::from_millis(100),
+        };
+        Self::with_placeholder(frame, path, app)
+    }
+
+    /// Shows `placeholder` until the background decode of `path` finishes.
+    fn with_placeholder<P: AsRef<Path>>(
+        placeholder: WebpFrame,
+        path: P,
+        app: &App,
+    ) -> Result<Self> {
+        let mut animation = WebpAnimationData::from_frames(vec![placeholder])?.into_player(app);
+        animation.pending = Some(WebpAnimationData::load_async(&path));
+        animation.set_source(path.as_ref().to_path_buf(), ColorMode::default());
+        Ok(animation)
+    }
+
+    /// Creates an animation from a live stream, e.g. a TCP connection, that
+    /// starts playing as soon as `min_frames` frames have arrived.
+    ///
+    /// The rest of the stream is read and decoded on a background thread
+    /// while the animation plays; [`update`](Self::update) appends the frames
+    /// as they arrive. Until the stream has ended, playback waits on the last
+    /// received frame instead of looping. See [`WebpStream`].
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: The source of the WebP file.
+    /// - `min_frames`: Number of frames to buffer before playback starts (at
+    ///   least one). This call blocks until they have arrived.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream is not a WebP file or fails before the
+    /// first frame. Later errors are reported by
+    /// [`take_load_error`](Self::take_load_error); the frames received so far
+    /// keep playing.
+    #[cfg(libwebp)]
+    pub fn from_reader<R: Read + Send + 'static>(
+        reader: R,
+        min_frames: usize,
+        app: &App,
+    ) -> Result<Self> {
+        let mut stream = WebpStream::from_reader(reader);
+        let frames = stream.wait_frames(min_frames.max(1))?;
+        let mut animation = Self::from_frames(frames, app)?;
+        animation.load_error = stream.take_error();
+        if !stream.is_finished() {
+            animation.stream = Some(stream);
+        }
+        Ok(animation)
+    }
+
+    /// Returns `true` while a progressive load is still decoding the full
+    /// animation, or frames of a stream are still arriving.
+    pub fn is_loading(&self) -> bool {
+        #[cfg(libwebp)]
+        if self.stream.is_some() {
+            return true;
+        }
+        self.pending.is_some()
+    }
+
+    /// Returns the error of a failed progressive load, once. The preview frame
+    /// stays on screen when the background decode fails.
+    ///
+    /// Also reports why a progressive load or [`reload`](Self::reload) fell
+    /// back to the [`LoadRecovery::fallback`](crate::LoadRecovery::fallback).
+    pub fn take_load_error(&mut self) -> Option<WebpAnimationError> {
+        self.load_error.take()
+    }
+
+    /// Creates a [`WebpAnimationBuilder`] for configuring load-time and playback
+    /// options before decoding the file at `path`.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    pub fn builder<P: AsRef<Path>>(path: P) -> WebpAnimationBuilder {
+        WebpAnimationBuilder::new(path)
+    }
+
+    /// Creates a new `WebpAnimation` whose frame textures are block-compressed before upload.
+    ///
+    /// Transcoding to BC7 (or BC1 for opaque content) reduces VRAM usage by 4–8x, which
+    /// matters when many animations are preloaded. If the GPU does not support BC
+    /// textures the frames are uploaded uncompressed.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    /// - `compression`: The block compression format to transcode frames to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    #[cfg(feature = "texture-compression")]
+    pub fn from_file_with_compression<P: AsRef<Path>>(
+        path: P,
+        app: &App,
+        compression: TextureCompression,
+    ) -> Result<Self> {
+        Self::builder(path)
+            .texture_compression(compression)
+            .build(app)
+    }
+
+    /// Decodes the WebP file at `path` straight into a temporary file, one
+    /// frame at a time, and plays it with only the frames near the playhead
+    /// in memory; see [`spill_to_disk`](Self::spill_to_disk).
+    ///
+    /// Unlike a [`spill_threshold`](AnimationSettings::spill_threshold), this
+    /// never holds the whole animation decoded, so files that don't fit in
+    /// memory can be played. Textures are uploaded lazily, and
+    /// [`reload`](Self::reload) and hot reloading decode into a new spill
+    /// file the same way.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded, or if the temporary
+    /// file cannot be written or mapped.
+    #[cfg(all(feature = "spill-to-disk", libwebp))]
+    pub fn from_file_spilled<P: AsRef<Path>>(path: P, app: &App) -> Result<Self> {
+        let (spill, data) = Self::decode_spilled(path.as_ref())?;
+        let settings = PlayerSettings {
+            lazy_textures: true,
+            spill_threshold: None,
+            ..PlayerSettings::default()
+        };
+        let mut animation = Self::with_settings(data, &settings, app);
+        animation.spill = Some(spill);
+        animation.page_frames();
+        animation.set_source(path.as_ref().to_path_buf(), ColorMode::default());
+        Ok(animation)
+    }
+
+    /// Decodes the WebP file at `path` frame by frame into a new spill file,
+    /// holding only the canvas being composited, and returns it together
+    /// with placeholder frames for the player.
+    #[cfg(all(feature = "spill-to-disk", libwebp))]
+    fn decode_spilled(path: &Path) -> Result<(SpilledFrames, WebpAnimationData)> {
+        let _slot = crate::loader::decode_slot();
+        let demuxer = WebpDemuxer::from_file(path)?;
+        let (width, height) = (demuxer.width(), demuxer.height());
+        let mut canvas = Vec::new();
+        let frames = (0..demuxer.frame_count()).map(|index| -> Result<WebpFrame> {
+            demuxer.composite(&mut canvas, index.checked_sub(1), index)?;
+            let image = RgbaImage::from_raw(width, height, canvas.clone()).ok_or_else(|| {
+                WebpAnimationError::Decode("Failed to create image from RGBA buffer".into())
+            })?;
+            Ok(WebpFrame {
+                image: DynamicImage::ImageRgba8(image),
+                duration: demuxer.frame_duration(index).unwrap_or_default(),
+            })
+        });
+        let (file, durations) = SpillFile::write(frames)?;
+
+        let spill = SpilledFrames::new(file);
+        let data = WebpAnimationData::from_frames(spill.placeholders(&durations))?;
+        Ok((spill, data))
+    }
+
+    /// Creates a player for decoded `data` with the given options.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(frames = data.frame_count()))
+    )]
+    pub(crate) fn with_settings(
+        data: WebpAnimationData,
+        settings: &PlayerSettings,
+        app: &App,
+    ) -> Self {
+        let device_queue_pair = app.main_window().device_queue_pair().clone();
+        let textures = data.frames.iter().map(|_| OnceLock::new()).collect();
+        let opaque_bounds = data.frames.iter().map(|_| OnceLock::new()).collect();
+
+        let mut animation = Self {
+            data,
+            playhead: Playhead::new(settings.clock.clone(), settings.looping),
+            lazy_textures: settings.lazy_textures,
             textures,
-        })
+            opaque_bounds,
+            precompute_opaque_bounds: settings.precompute_opaque_bounds,
+            interpolation: Interpolation::Step,
+            filter: settings.filter,
+            device_queue_pair,
+            #[cfg(feature = "texture-compression")]
+            texture_compression: settings.texture_compression,
+            #[cfg(feature = "texture-compression")]
+            texture_format: None,
+            #[cfg(feature = "hot-reload")]
+            watcher: None,
+            pending: None,
+            load_error: None,
+            source: None,
+            prefetch: settings.prefetch,
+            uploader: None,
+            budget: budget::register(),
+            released_size: None,
+            #[cfg(feature = "spill-to-disk")]
+            spill: None,
+            #[cfg(feature = "spill-to-disk")]
+            spill_threshold: settings.spill_threshold,
+            #[cfg(libwebp)]
+            stream: None,
+        };
+
+        // Decided while every frame still holds its pixels.
+        #[cfg(feature = "texture-compression")]
+        animation.resolve_texture_format();
+        #[cfg(feature = "spill-to-disk")]
+        if !settings.gpu_only && animation.exceeds_spill_threshold() {
+            if let Err(error) = animation.spill_to_disk() {
+                animation.load_error = Some(error);
+            }
+        }
+        animation.preload_textures();
+        animation.preload_opaque_bounds();
+        animation.set_speed(settings.speed);
+        animation.set_interpolation(settings.interpolation);
+        if settings.gpu_only {
+            animation.release_cpu_frames();
+        }
+        animation
     }
 
     /// Updates the animation's current frame based on elapsed time.
@@ -65,47 +527,1076 @@ impl WebpAnimation {
     /// This function should be called in each frame of the main loop to keep the animation
     /// in sync with its intended frame durations.
     pub fn update(&mut self) {
-        let current_frame = &self.frames[self.current_frame_index];
-        let duration = current_frame.duration;
-
-        if self.last_frame_time.elapsed() >= duration {
-            self.current_frame_index += 1;
-            if self.current_frame_index >= self.frames.len() {
-                if self.is_looping {
-                    self.current_frame_index = 0;
-                } else {
-                    self.current_frame_index = self.frames.len() - 1;
-                }
-            }
-            self.last_frame_time = Instant::now();
+        #[cfg(feature = "hot-reload")]
+        self.reload_if_changed();
+        self.finish_loading();
+        #[cfg(libwebp)]
+        self.receive_stream();
+
+        if !self.playhead.is_playing() {
+            return;
+        }
+
+        if !self.awaiting_frames() && self.playhead.advance(&self.data.frames) {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(frame = self.playhead.frame_index, "frame changed");
         }
+
+        #[cfg(feature = "spill-to-disk")]
+        self.page_frames();
+        self.schedule_uploads();
+        self.apply_budget();
     }
 
     /// Returns a reference to the current texture.
     ///
+    /// This is always the unblended current frame; in
+    /// [`Interpolation::Crossfade`] mode the next frame is blended over it
+    /// when drawn with [`DrawWebpExt::webp`].
+    ///
     /// # Returns
     ///
     /// A reference to the `Texture` of the current frame.
     pub fn texture(&self) -> &Texture {
-        &self.textures[self.current_frame_index]
+        self.budget.touch();
+        self.frame_texture(self.playhead.frame_index)
+    }
+
+    /// Returns the texture of the next frame and how far the current frame has
+    /// faded into it, from `0.0` to `1.0`, while crossfading.
+    pub(crate) fn crossfade(&self) -> Option<(&Texture, f32)> {
+        if self.interpolation != Interpolation::Crossfade {
+            return None;
+        }
+        let next = self.next_frame_index();
+        let duration = self.data.frames[self.playhead.frame_index].duration;
+        if next == self.playhead.frame_index || duration.is_zero() {
+            return None;
+        }
+        let t = self.playhead.elapsed().as_secs_f32() / duration.as_secs_f32();
+        Some((self.frame_texture(next), t.min(1.0)))
+    }
+
+    /// Draws the current frame centred at `position` with the given `size`.
+    ///
+    /// # Parameters
+    ///
+    /// - `draw`: The nannou `Draw` instance to render into.
+    /// - `position`: The centre of the animation in world coordinates.
+    /// - `size`: The width and height to draw the animation at.
+    #[deprecated(
+        note = "use `DrawWebpExt::webp`, e.g. `draw.webp(&animation).xy(position).wh(size)`"
+    )]
+    pub fn draw(&self, draw: &Draw, position: Point2, size: Vec2) {
+        draw.webp(self).xy(position).wh(size);
+    }
+
+    /// Returns the region of [`texture`](Self::texture) covered by the frame, in
+    /// normalized texture coordinates.
+    pub(crate) fn texture_area(&self) -> Rect {
+        // Block-compressed textures may be padded to a multiple of 4 pixels.
+        let [tw, th] = self.texture().size();
+        Rect::from_corners(
+            pt2(0.0, 0.0),
+            pt2(
+                self.width() as f32 / tw as f32,
+                self.height() as f32 / th as f32,
+            ),
+        )
+    }
+
+    /// Uploads every frame texture, then frees the decoded pixels on the CPU
+    /// and keeps only the frame durations.
+    ///
+    /// Roughly halves the memory of an animation that is only ever drawn. The
+    /// frames of this player become empty 0×0 images afterwards, so reading
+    /// pixels on the CPU finds nothing: [`pixel_at`](Self::pixel_at) returns
+    /// `None`, [`hit_test`](Self::hit_test) misses unless the bounding boxes
+    /// were precomputed, and saving or exporting frames has no content. Frames
+    /// swapped in later by
+    /// [`reload`](Self::reload), hot reloading or a stream are released too.
+    ///
+    /// Players cloned earlier, and other players sharing the same
+    /// [`WebpAnimationData`], keep their pixels.
+    pub fn release_cpu_frames(&mut self) {
+        self.warm_up_textures();
+        #[cfg(feature = "spill-to-disk")]
+        let _ = self.spill.take();
+        self.released_size = Some((self.width(), self.height()));
+        self.uploader = None;
+        self.data.frames = Arc::new(
+            self.data
+                .frames
+                .iter()
+                .map(|frame| WebpFrame {
+                    image: DynamicImage::new_rgba8(0, 0),
+                    duration: frame.duration,
+                })
+                .collect(),
+        );
+    }
+
+    /// Returns `false` once the decoded pixels were freed with
+    /// [`release_cpu_frames`](Self::release_cpu_frames).
+    pub fn has_cpu_frames(&self) -> bool {
+        self.released_size.is_none()
+    }
+
+    /// Moves the decoded frames to a memory-mapped temporary file and keeps
+    /// only the frames near the playhead in memory, for animations too long
+    /// to hold decoded.
+    ///
+    /// [`update`](Self::update) and seeking page in the current frame, the
+    /// next one and the frames within the [`prefetch`](Self::prefetch)
+    /// distance, and page the others out again along with their textures,
+    /// which are uploaded from the file when next shown. Frames are paged in
+    /// as RGBA8, and the first frame always stays in memory.
+    ///
+    /// Paged-out frames are empty 0×0 images in [`frames`](Self::frames) and
+    /// [`data`](Self::data), so saving, exporting and encoding see no content
+    /// for them; [`into_data`](Self::into_data), [`map_frames`](Self::map_frames)
+    /// and [`retain_frames`](Self::retain_frames) read them back first.
+    /// Textures are not staged in the background while spilled. Clones share
+    /// the file, which is deleted once the last of them is dropped.
+    ///
+    /// [`AnimationSettings::spill_threshold`] spills large animations
+    /// automatically once they are decoded;
+    /// [`from_file_spilled`](Self::from_file_spilled) decodes straight into
+    /// the file instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the decoded pixels were released with
+    /// [`release_cpu_frames`](Self::release_cpu_frames), or if the temporary
+    /// file cannot be written or mapped. The frames stay in memory on error.
+    #[cfg(feature = "spill-to-disk")]
+    pub fn spill_to_disk(&mut self) -> Result<()> {
+        if !self.has_cpu_frames() {
+            return Err(WebpAnimationError::InvalidArgument(
+                "the decoded pixels were released".into(),
+            ));
+        }
+        if self.spill.is_some() {
+            return Ok(());
+        }
+        let (file, _) = SpillFile::write(self.data.frames.iter().map(Ok))?;
+        let mut spill = SpilledFrames::new(file);
+        spill.page_out_all(&mut self.data.frames);
+        self.spill = Some(spill);
+        self.evict_textures();
+        self.page_frames();
+        Ok(())
+    }
+
+    /// Returns `true` while the frames are spilled to disk; see
+    /// [`spill_to_disk`](Self::spill_to_disk).
+    #[cfg(feature = "spill-to-disk")]
+    pub fn is_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Uploads every frame texture that is still pending, so lazily loaded
+    /// animations don't stall the first time each frame is shown.
+    pub fn warm_up_textures(&self) {
+        for index in 0..self.data.frames.len() {
+            self.frame_texture(index);
+        }
+    }
+
+    /// Prepares the animation for a performance: uploads every pending frame
+    /// texture and draws each frame once into a small offscreen target.
+    ///
+    /// The dry-run draw makes the GPU driver finish the uploads and compile the
+    /// texture pipeline, so the first real draw of the animation does not cause
+    /// a visible hitch. Call it in `model` or during a loading scene.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, whose main window's
+    ///   device renders the dry run.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn warm_up(&self, app: &App) {
+        self.warm_up_textures();
+
+        let window = app.main_window();
+        let device = window.device();
+        let target = wgpu::TextureBuilder::new()
+            .size([16, 16])
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .sample_count(1)
+            .format(wgpu::TextureFormat::Rgba16Float)
+            .build(device);
+        let mut renderer =
+            RendererBuilder::new().build_from_texture_descriptor(device, target.descriptor());
+
+        let draw = Draw::new();
+        for index in 0..self.data.frames.len() {
+            draw.texture(self.frame_texture(index)).w_h(16.0, 16.0);
+        }
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("webp warm-up"),
+        });
+        renderer.render_to_texture(device, &mut encoder, &draw, &target);
+        window.queue().submit(Some(encoder.finish()));
+    }
+
+    /// Draws a debug overlay describing the playback state.
+    ///
+    /// The overlay outlines the canvas bounds and prints the current frame index,
+    /// the playhead timestamp and the current frame's duration. Call it after
+    /// drawing the animation so that the overlay ends up on top.
+    ///
+    /// # Parameters
+    ///
+    /// - `draw`: The nannou `Draw` instance to render into.
+    /// - `position`: The centre of the animation in world coordinates.
+    pub fn draw_debug(&self, draw: &Draw, position: Point2) {
+        let bounds = Rect::from_w_h(self.width() as f32, self.height() as f32).shift(position);
+        draw.rect()
+            .xy(bounds.xy())
+            .wh(bounds.wh())
+            .no_fill()
+            .stroke(MAGENTA)
+            .stroke_weight(1.0);
+
+        let frame = &self.data.frames[self.playhead.frame_index];
+        let text = format!(
+            "frame {}/{}\nt {:.0} ms\nduration {} ms\ncanvas {}x{}",
+            self.playhead.frame_index + 1,
+            self.data.frames.len(),
+            self.timestamp().as_secs_f64() * 1000.0,
+            frame.duration.as_millis(),
+            self.width(),
+            self.height(),
+        );
+        let label = Rect::from_w_h(160.0, 64.0).top_left_of(bounds);
+        draw.rect()
+            .xy(label.xy())
+            .wh(label.wh())
+            .color(rgba(0.0, 0.0, 0.0, 0.6));
+        draw.text(&text)
+            .xy(label.pad(4.0).xy())
+            .wh(label.pad(4.0).wh())
+            .font_size(12)
+            .color(WHITE)
+            .left_justify()
+            .align_text_top();
+    }
+
+    /// Watches the WebP file at `path` and swaps in its frames whenever it changes
+    /// on disk, keeping the playhead.
+    ///
+    /// Changes are picked up by [`update`](Self::update). Clones of this player
+    /// do not watch the file and keep showing the frames they were created with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not exist or cannot be watched.
+    #[cfg(feature = "hot-reload")]
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.watch_with_color_mode(path, ColorMode::default())
+    }
+
+    /// Like [`watch`](Self::watch), decoding the file to `color_mode`.
+    #[cfg(feature = "hot-reload")]
+    pub(crate) fn watch_with_color_mode<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        color_mode: ColorMode,
+    ) -> Result<()> {
+        self.watcher = Some(FileWatcher::new(path, color_mode)?);
+        Ok(())
+    }
+
+    /// Stops watching the source file.
+    #[cfg(feature = "hot-reload")]
+    pub fn unwatch(&mut self) {
+        self.watcher = None;
+    }
+
+    /// Replaces the frames with the watched file's new contents, if it changed.
+    #[cfg(feature = "hot-reload")]
+    fn reload_if_changed(&mut self) {
+        // A spilled player decodes the new file straight into a spill file too.
+        #[cfg(all(feature = "spill-to-disk", libwebp))]
+        if self.spill.is_some() {
+            let decoded = self
+                .watcher
+                .as_ref()
+                .and_then(|watcher| watcher.poll_with(|path, _| Self::decode_spilled(path)));
+            if let Some((spill, data)) = decoded {
+                self.swap_spilled(spill, data);
+            }
+            return;
+        }
+        if let Some(data) = self.watcher.as_ref().and_then(FileWatcher::poll) {
+            self.data = data;
+            self.sync_textures();
+        }
+    }
+
+    /// Decodes the file the player was loaded from again and swaps in its
+    /// frames, e.g. after the asset was re-exported.
+    ///
+    /// Playback settings are kept, and so is the playhead: the current frame
+    /// and the time spent on it carry over, clamped to the last frame if the
+    /// new animation is shorter. Textures are re-uploaded from the new frames.
+    /// Clones of this player keep showing the frames they were created with.
+    ///
+    /// The file is decoded on the calling thread, so failed decodes are not
+    /// retried, which would stall rendering for the whole
+    /// [`retry_delay`](crate::LoadRecovery::retry_delay) schedule. If the
+    /// decode fails and [`AnimationSettings::recovery`] has a
+    /// [`fallback`](crate::LoadRecovery::fallback), its frames are swapped in
+    /// instead and the error is kept for
+    /// [`take_load_error`](Self::take_load_error); a later reload tries the
+    /// file again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player was not loaded from a file, e.g. because
+    /// it was created with [`from_frames`](Self::from_frames), or if the file
+    /// cannot be decoded and there is no fallback. The current frames stay in
+    /// place on error.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub fn reload(&mut self) -> Result<()> {
+        let Some((path, color_mode)) = &self.source else {
+            return Err(WebpAnimationError::InvalidArgument(
+                "the animation was not loaded from a file".into(),
+            ));
+        };
+        let recovery = LoadRecovery {
+            retries: 0,
+            ..AnimationSettings::global().recovery
+        };
+        // Decoding a spilled animation into memory first could run out of it.
+        #[cfg(all(feature = "spill-to-disk", libwebp))]
+        if self.spill.is_some() {
+            let spill = Cell::new(None);
+            let (data, failure) = recovery.recover(|| {
+                let (file, data) = Self::decode_spilled(path)?;
+                spill.set(Some(file));
+                Ok(data)
+            })?;
+            self.load_error = failure.or(self.load_error.take());
+            match spill.into_inner() {
+                Some(spill) => self.swap_spilled(spill, data),
+                // The fallback is in memory and spilled again like edited frames.
+                None => {
+                    self.data = data;
+                    self.sync_textures();
+                }
+            }
+            return Ok(());
+        }
+        let (data, failure) =
+            recovery.recover(|| WebpAnimationData::from_file_with_color_mode(path, *color_mode))?;
+        self.data = data;
+        self.load_error = failure.or(self.load_error.take());
+        self.sync_textures();
+        Ok(())
+    }
+
+    /// Remembers the file the frames were decoded from for [`reload`](Self::reload).
+    pub(crate) fn set_source(&mut self, path: PathBuf, color_mode: ColorMode) {
+        self.source = Some((path, color_mode));
+    }
+
+    /// Swaps in the full animation once a progressive load has finished.
+    fn finish_loading(&mut self) {
+        let Some(result) = self.pending.as_mut().and_then(LoadHandle::try_take_data) else {
+            return;
+        };
+        let failure = self
+            .pending
+            .take()
+            .and_then(|pending| pending.take_failure());
+        match result {
+            Ok(data) => {
+                self.load_error = failure.or(self.load_error.take());
+                self.data = data;
+                self.sync_textures();
+                self.seek_frame(0);
+            }
+            Err(error) => self.load_error = Some(error),
+        }
+    }
+
+    /// Appends the frames that arrived from the stream since the last update.
+    #[cfg(libwebp)]
+    fn receive_stream(&mut self) {
+        let Some(stream) = &mut self.stream else {
+            return;
+        };
+        let frames = stream.try_frames();
+        if let Some(error) = stream.take_error() {
+            self.load_error = Some(error);
+        }
+        if stream.is_finished() {
+            self.stream = None;
+        }
+        if !frames.is_empty() {
+            self.append_frames(frames);
+        }
+    }
+
+    /// Returns `true` if playback has reached the last frame received so far
+    /// while more frames are still arriving.
+    fn awaiting_frames(&self) -> bool {
+        #[cfg(libwebp)]
+        if self.stream.is_some() {
+            return self.playhead.direction == PlaybackDirection::Forward
+                && self.playhead.frame_index + 1 == self.data.frames.len();
+        }
+        false
+    }
+
+    /// Adds `frames` to the end of the animation, keeping the textures and
+    /// bounding boxes of the existing frames.
+    #[cfg(libwebp)]
+    fn append_frames(&mut self, frames: Vec<WebpFrame>) {
+        Arc::make_mut(&mut self.data.frames).extend(frames);
+        let len = self.data.frames.len();
+        extend_cells(&mut self.textures, len);
+        extend_cells(&mut self.opaque_bounds, len);
+        self.uploader = None;
+        self.preload_textures();
+        self.preload_opaque_bounds();
+        if self.released_size.is_some() {
+            self.release_cpu_frames();
+        }
+    }
+
+    /// Returns the playhead position measured from the start of the animation.
+    fn timestamp(&self) -> Duration {
+        self.playhead.timestamp(&self.data.frames)
+    }
+
+    /// Uploads the textures staged in the background and requests the
+    /// upcoming frames within the [`prefetch`](Self::prefetch) distance.
+    fn schedule_uploads(&mut self) {
+        if self.prefetch == Prefetch::default() || !self.lazy_textures {
+            return;
+        }
+        // The staging thread would read the placeholders of paged-out frames.
+        #[cfg(feature = "spill-to-disk")]
+        if self.spill.is_some() {
+            return;
+        }
+        #[cfg(feature = "texture-compression")]
+        if self.texture_compression != TextureCompression::None {
+            return;
+        }
+
+        let upcoming = self.upcoming_frames(self.prefetch);
+        let frames = self.data.frames.clone();
+        let uploader = self
+            .uploader
+            .get_or_insert_with(|| TextureUploader::new(frames));
+        uploader.upload_staged(&self.device_queue_pair, |index, texture| {
+            // The frame may have been uploaded on the draw path in the meantime.
+            let _ = self.textures[index].set(texture);
+        });
+
+        for index in upcoming {
+            if self.textures[index].get().is_none() {
+                uploader.request(index);
+            }
+        }
+    }
+
+    /// Returns the indices of the frames after the current one, in playback
+    /// order, that lie within `distance` of the playhead.
+    fn upcoming_frames(&self, distance: Prefetch) -> Vec<usize> {
+        let mut frames = Vec::new();
+        let mut index = self.playhead.frame_index;
+        let mut offset = Duration::ZERO;
+        for ahead in 1..self.data.frames.len() {
+            if !distance.includes(ahead, offset) {
+                break;
+            }
+            let next = playback::next_frame_index(
+                index,
+                self.data.frames.len(),
+                self.playhead.direction,
+                self.playhead.looping,
+            );
+            if next == index {
+                // The end of a non-looping animation.
+                break;
+            }
+            index = next;
+            frames.push(index);
+            offset += self.data.frames[index].duration;
+        }
+        frames
+    }
+
+    /// Reports the memory of this player to the
+    /// [`memory_budget`](AnimationSettings::memory_budget) and frees what the
+    /// budget asks for.
+    fn apply_budget(&mut self) {
+        let budget = AnimationSettings::global().memory_budget;
+        if budget.is_unlimited() {
+            return;
+        }
+        let usage = self.memory_usage();
+        let can_evict = self.has_cpu_frames() && self.texture_usage().1 > 0;
+        let can_release = self.has_cpu_frames()
+            && !self.is_loading()
+            && self.textures.iter().all(|texture| texture.get().is_some());
+        self.budget.report(usage, can_evict, can_release);
+        budget::enforce(budget);
+
+        let evict = self.budget.take_evict_textures();
+        let release = self.budget.take_release_pixels();
+        if !self.has_cpu_frames() {
+            return;
+        }
+        // Releasing the pixels would upload the evicted textures again.
+        if evict {
+            self.evict_textures();
+        } else if release {
+            self.release_cpu_frames();
+        }
+    }
+
+    /// Drops the frame textures of this player and uploads them again when
+    /// they are next shown.
+    fn evict_textures(&mut self) {
+        self.uploader = None;
+        self.lazy_textures = true;
+        self.textures = self.data.frames.iter().map(|_| OnceLock::new()).collect();
+    }
+
+    /// Pages the current frame, the next one and the frames within the
+    /// prefetch distance in from the spill file and the others out, dropping
+    /// the textures of the frames paged out unless clones share them.
+    #[cfg(feature = "spill-to-disk")]
+    fn page_frames(&mut self) {
+        let Some(mut spill) = self.spill.take() else {
+            return;
+        };
+        let mut wanted = self.upcoming_frames(self.prefetch);
+        wanted.push(self.playhead.frame_index);
+        wanted.push(self.next_frame_index());
+        let evicted = spill.page(&mut self.data.frames, &wanted);
+        self.spill = Some(spill);
+        if let Some(textures) = Arc::get_mut(&mut self.textures) {
+            for index in evicted {
+                textures[index].take();
+            }
+        }
+    }
+
+    /// Reads every spilled frame back into memory, e.g. before the frames
+    /// are edited; [`sync_textures`](Self::sync_textures) spills them again.
+    #[cfg(feature = "spill-to-disk")]
+    fn page_in_all(&mut self) {
+        if let Some(spill) = &mut self.spill {
+            spill.page_in_all(&mut self.data.frames);
+        }
+    }
+
+    /// Reads the frame at `index` from the spill file if it is paged out, or
+    /// returns `None` if `frame`, its copy in memory, can be used.
+    #[cfg(feature = "spill-to-disk")]
+    fn paged_out(&self, index: usize, frame: &WebpFrame) -> Option<WebpFrame> {
+        self.spill.as_ref()?.paged_out(index, frame.duration)
+    }
+
+    /// Returns `true` if the decoded frames hold more bytes than the
+    /// [`spill_threshold`](AnimationSettings::spill_threshold).
+    #[cfg(feature = "spill-to-disk")]
+    fn exceeds_spill_threshold(&self) -> bool {
+        self.spill_threshold
+            .is_some_and(|threshold| self.data.memory_usage().cpu_bytes > threshold)
+    }
+
+    /// Uploads every frame texture up front unless lazy uploads are enabled.
+    fn preload_textures(&self) {
+        if !self.lazy_textures {
+            self.warm_up_textures();
+        }
+    }
+
+    /// Computes every bounding box up front if requested.
+    fn preload_opaque_bounds(&self) {
+        if self.precompute_opaque_bounds {
+            for index in 0..self.data.frames.len() {
+                self.opaque_bounds_at(index);
+            }
+        }
+    }
+
+    /// Swaps in `data`, whose frames are paged out to `spill`, keeping the
+    /// playhead like [`sync_textures`](Self::sync_textures).
+    #[cfg(all(feature = "spill-to-disk", libwebp))]
+    fn swap_spilled(&mut self, spill: SpilledFrames, data: WebpAnimationData) {
+        self.data = data;
+        self.spill = Some(spill);
+        self.playhead.frame_index = self.playhead.frame_index.min(self.data.frames.len() - 1);
+        self.evict_textures();
+        self.page_frames();
+        self.refresh_textures(false);
+    }
+
+    /// Discards the frame textures and bounding boxes after the frames were
+    /// edited and re-uploads them, clamping the playhead to the new frame count.
+    fn sync_textures(&mut self) {
+        let released = self.released_size.take().is_some();
+        #[cfg(feature = "spill-to-disk")]
+        let spilled = self.spill.take().is_some();
+        self.playhead.frame_index = self.playhead.frame_index.min(self.data.frames.len() - 1);
+        self.uploader = None;
+        self.textures = self.data.frames.iter().map(|_| OnceLock::new()).collect();
+        // New frames are spilled again before anything is uploaded from them.
+        #[cfg(feature = "spill-to-disk")]
+        if !released && (spilled || self.exceeds_spill_threshold()) {
+            if let Err(error) = self.spill_to_disk() {
+                self.load_error = Some(error);
+            }
+        }
+        self.refresh_textures(released);
+    }
+
+    /// Uploads the frame textures unless they are lazy and recreates the
+    /// bounding boxes for new frames, freeing the pixels again if they had
+    /// been `released`.
+    fn refresh_textures(&mut self, released: bool) {
+        #[cfg(feature = "texture-compression")]
+        self.resolve_texture_format();
+        self.preload_textures();
+        self.opaque_bounds = self.data.frames.iter().map(|_| OnceLock::new()).collect();
+        self.preload_opaque_bounds();
+        if released {
+            self.release_cpu_frames();
+        }
+    }
+
+    /// Returns the texture of the frame at `index`, uploading it on first use.
+    fn frame_texture(&self, index: usize) -> &Texture {
+        self.textures[index].get_or_init(|| {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("upload_texture", index).entered();
+            let frame = &self.data.frames[index];
+            #[cfg(feature = "spill-to-disk")]
+            if let Some(frame) = self.paged_out(index, frame) {
+                return self.create_texture(&frame);
+            }
+            self.create_texture(frame)
+        })
+    }
+
+    /// Returns how many frame textures have been uploaded so far.
+    #[cfg(feature = "nannou_egui")]
+    pub(crate) fn uploaded_texture_count(&self) -> usize {
+        self.textures
+            .iter()
+            .filter(|texture| texture.get().is_some())
+            .count()
+    }
+
+    /// Returns an identifier of the frame textures, equal for clones that
+    /// share them, and the bytes of those uploaded so far.
+    pub(crate) fn texture_usage(&self) -> (*const (), u64) {
+        let bytes = self
+            .textures
+            .iter()
+            .filter_map(OnceLock::get)
+            .map(texture_bytes)
+            .sum();
+        (Arc::as_ptr(&self.textures) as *const (), bytes)
+    }
+
+    /// Picks the compressed texture format once for all current frames, so
+    /// e.g. [`TextureCompression::Auto`] does not mix formats between frames.
+    #[cfg(feature = "texture-compression")]
+    fn resolve_texture_format(&mut self) {
+        self.texture_format = self
+            .texture_compression
+            .resolve(&self.data.frames, self.device_queue_pair.device());
+    }
+
+    /// Uploads `frame`, transcoding it to the resolved compression format.
+    #[cfg(feature = "texture-compression")]
+    fn create_texture(&self, frame: &WebpFrame) -> Texture {
+        compression::create_texture(&self.device_queue_pair, frame, self.texture_format)
+    }
+
+    /// Uploads `frame` as an uncompressed texture.
+    #[cfg(not(feature = "texture-compression"))]
+    fn create_texture(&self, frame: &WebpFrame) -> Texture {
+        let (device, queue) = (
+            self.device_queue_pair.device(),
+            self.device_queue_pair.queue(),
+        );
+        Texture::from_image((device, queue), &frame.image)
     }
 
     /// Returns the width of the current frame.
     ///
     /// # Returns
     ///
-    /// The width (in pixels) of the current frame's texture.
+    /// The width (in pixels) of the current frame's image.
     pub fn width(&self) -> u32 {
-        self.texture().size()[0]
+        match self.released_size {
+            Some((width, _)) => width,
+            None => self.data.frames[self.playhead.frame_index].image.width(),
+        }
     }
 
     /// Returns the height of the current frame.
     ///
     /// # Returns
     ///
-    /// The height (in pixels) of the current frame's texture.
+    /// The height (in pixels) of the current frame's image.
     pub fn height(&self) -> u32 {
-        self.texture().size()[1]
+        match self.released_size {
+            Some((_, height)) => height,
+            None => self.data.frames[self.playhead.frame_index].image.height(),
+        }
+    }
+
+    /// Iterates over the frames together with their start time; see
+    /// [`WebpAnimationData::iter_timed`].
+    pub fn iter_timed(&self) -> impl Iterator<Item = (Duration, &WebpFrame)> + '_ {
+        self.data.iter_timed()
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn frame_count(&self) -> usize {
+        self.data.frame_count()
+    }
+
+    /// Returns the total duration of one playthrough at normal speed.
+    pub fn duration(&self) -> Duration {
+        self.data.duration()
+    }
+
+    /// Returns the average frame rate of the animation at normal speed.
+    ///
+    /// # Returns
+    ///
+    /// The number of frames per second, or `0.0` if every frame has a zero duration.
+    pub fn average_fps(&self) -> f64 {
+        self.data.average_fps()
+    }
+
+    /// Saves the current frame as a still image, e.g. for thumbnails.
+    ///
+    /// The image format is inferred from the file extension, e.g. `.png`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the format is unsupported or the file cannot be written.
+    pub fn save_current_frame<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.data.frames[self.playhead.frame_index].save(path)
+    }
+
+    /// Creates another player for the same frames, starting from the first frame.
+    ///
+    /// Like [`clone`](Clone::clone), the new player shares the decoded frames and
+    /// textures, but its playhead is reset instead of copied.
+    pub fn new_instance(&self) -> Self {
+        let mut instance = self.clone();
+        instance.playhead.seek_frame(0, instance.data.frames.len());
+        instance.playhead.loop_count = 0;
+        instance
+    }
+
+    /// Returns all frames in playback order, e.g. to analyse their pixels on the CPU.
+    pub fn frames(&self) -> &[WebpFrame] {
+        self.data.frames()
+    }
+
+    /// Returns the frame at `index`, or `None` if it is out of range.
+    pub fn frame_at(&self, index: usize) -> Option<&WebpFrame> {
+        self.data.frame_at(index)
+    }
+
+    /// Returns the frame currently being shown.
+    pub fn current_frame(&self) -> &WebpFrame {
+        &self.data.frames[self.playhead.frame_index]
+    }
+
+    /// Returns the RGBA color of the current frame at (`x`, `y`), measured in
+    /// pixels from the top-left corner of the animation, or `None` if the
+    /// position lies outside the canvas.
+    ///
+    /// The color is read from the decoded CPU image, so this works for color
+    /// picking regardless of how the texture is drawn. In
+    /// [`Interpolation::Crossfade`] mode the unblended current frame is sampled.
+    pub fn pixel_at(&self, x: u32, y: u32) -> Option<Rgba<u8>> {
+        self.current_frame().pixel_at(x, y)
+    }
+
+    /// Returns `true` if `point` lands on a mostly opaque pixel (alpha above 127)
+    /// of the current frame; see [`hit_test_with_threshold`](Self::hit_test_with_threshold).
+    pub fn hit_test(&self, point: Point2, transform: Mat4) -> bool {
+        self.hit_test_with_threshold(point, transform, 127)
+    }
+
+    /// Returns `true` if `point` lands on a pixel of the current frame whose
+    /// alpha is above `threshold`.
+    ///
+    /// `transform` maps animation-local space, where the frame is centred at the
+    /// origin at its native size with y pointing up, into the space of `point`.
+    /// It is inverted to find the pixel under `point`, which makes animated
+    /// sprites clickable:
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # use nannou_webp_animation::{DrawWebpExt, WebpAnimation};
+    /// # fn view(app: &App, animation: &WebpAnimation) {
+    /// let (xy, scale, rotation) = (pt2(100.0, 0.0), 2.0, PI / 8.0);
+    /// app.draw()
+    ///     .webp(animation)
+    ///     .xy(xy)
+    ///     .w_h(animation.width() as f32 * scale, animation.height() as f32 * scale)
+    ///     .rotate(rotation);
+    ///
+    /// let transform = Mat4::from_scale_rotation_translation(
+    ///     vec3(scale, scale, 1.0),
+    ///     Quat::from_rotation_z(rotation),
+    ///     xy.extend(0.0),
+    /// );
+    /// if animation.hit_test(app.mouse.position(), transform) {
+    ///     // The cursor is over a visible part of the animation.
+    /// }
+    /// # }
+    /// ```
+    pub fn hit_test_with_threshold(&self, point: Point2, transform: Mat4, threshold: u8) -> bool {
+        let local = transform.inverse().transform_point3(point.extend(0.0));
+        let x = local.x + self.width() as f32 / 2.0;
+        let y = self.height() as f32 / 2.0 - local.y;
+        if !(x >= 0.0 && y >= 0.0) {
+            return false;
+        }
+        self.pixel_at(x as u32, y as u32)
+            .is_some_and(|pixel| pixel[3] > threshold)
+    }
+
+    /// Returns the bounding box of the current frame's visible pixels, or `None`
+    /// if the frame is fully transparent; see [`WebpFrame::opaque_bounds`].
+    ///
+    /// The box is computed the first time each frame is queried, unless
+    /// [`WebpAnimationBuilder::precompute_opaque_bounds`] is enabled.
+    pub fn opaque_bounds(&self) -> Option<OpaqueBounds> {
+        self.opaque_bounds_at(self.playhead.frame_index)
+    }
+
+    /// Returns the bounding box of the visible pixels of the frame at `index`,
+    /// or `None` if it is out of range or fully transparent.
+    pub fn opaque_bounds_at(&self, index: usize) -> Option<OpaqueBounds> {
+        let frame = self.data.frames.get(index)?;
+        *self.opaque_bounds[index].get_or_init(|| {
+            #[cfg(feature = "spill-to-disk")]
+            if let Some(frame) = self.paged_out(index, frame) {
+                return frame.opaque_bounds();
+            }
+            frame.opaque_bounds()
+        })
+    }
+
+    /// Returns the current frame's visible region in animation-local space, where
+    /// the frame is centred at the origin at its native size with y pointing up,
+    /// as used by [`hit_test`](Self::hit_test).
+    ///
+    /// Shift and scale the rectangle like the drawn animation to use it for
+    /// collisions or layout.
+    pub fn opaque_rect(&self) -> Option<Rect> {
+        let bounds = self.opaque_bounds()?;
+        let left = bounds.x as f32 - self.width() as f32 / 2.0;
+        let top = self.height() as f32 / 2.0 - bounds.y as f32;
+        Some(Rect::from_corners(
+            pt2(left, top),
+            pt2(left + bounds.width as f32, top - bounds.height as f32),
+        ))
+    }
+
+    /// Returns the index of the frame currently being shown.
+    pub fn current_frame_index(&self) -> usize {
+        self.playhead.frame_index
+    }
+
+    /// Jumps to the start of the frame at `index`, clamped to the last frame.
+    ///
+    /// Playback continues from there unless the animation is paused.
+    pub fn seek_frame(&mut self, index: usize) {
+        self.playhead.seek_frame(index, self.data.frames.len());
+        #[cfg(feature = "spill-to-disk")]
+        self.page_frames();
+    }
+
+    /// Jumps to the playhead position `time`, measured from the start of the
+    /// animation at normal speed and clamped to its duration.
+    ///
+    /// Playback continues from there unless the animation is paused.
+    pub fn seek_time(&mut self, time: Duration) {
+        self.playhead.seek_time(&self.data.frames, time);
+        #[cfg(feature = "spill-to-disk")]
+        self.page_frames();
+    }
+
+    /// Replaces the image of every frame with the result of `f` and re-uploads
+    /// the textures; see [`WebpAnimationData::map_frames`].
+    ///
+    /// Clones of this player keep showing the original frames. Does nothing
+    /// once the decoded pixels were released with
+    /// [`release_cpu_frames`](Self::release_cpu_frames), as there is nothing
+    /// left to map.
+    pub fn map_frames<F>(&mut self, f: F)
+    where
+        F: FnMut(&DynamicImage) -> DynamicImage,
+    {
+        if !self.has_cpu_frames() {
+            return;
+        }
+        #[cfg(feature = "spill-to-disk")]
+        self.page_in_all();
+        self.data.map_frames(f);
+        self.sync_textures();
+    }
+
+    /// Keeps only the frames for which `f` returns `true` and re-uploads the
+    /// textures; see [`WebpAnimationData::retain_frames`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the animation unchanged, if no frame would
+    /// remain or if the decoded pixels were released with
+    /// [`release_cpu_frames`](Self::release_cpu_frames).
+    pub fn retain_frames<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnMut(usize, &WebpFrame) -> bool,
+    {
+        if !self.has_cpu_frames() {
+            return Err(WebpAnimationError::InvalidArgument(
+                "the decoded pixels were released".into(),
+            ));
+        }
+        #[cfg(feature = "spill-to-disk")]
+        self.page_in_all();
+        self.data.retain_frames(f)?;
+        self.sync_textures();
+        Ok(())
+    }
+
+    /// Replaces all frames and re-uploads the textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the animation unchanged, if `frames` is empty.
+    pub fn set_frames(&mut self, frames: Vec<WebpFrame>) -> Result<()> {
+        self.data.set_frames(frames)?;
+        self.sync_textures();
+        Ok(())
+    }
+
+    /// Returns the decoded frames backing this player.
+    pub fn data(&self) -> &WebpAnimationData {
+        &self.data
+    }
+
+    /// Returns the memory this player holds, e.g. to show a budget or to pick
+    /// the assets worth downscaling or loading with
+    /// [`lazy_textures`](WebpAnimationBuilder::lazy_textures).
+    ///
+    /// The CPU side counts the decoded frames; the GPU side counts the frame
+    /// textures uploaded so far.
+    pub fn memory_usage(&self) -> MemoryReport {
+        let data = self.data.memory_usage();
+        let (_, frame_texture_bytes) = self.texture_usage();
+        MemoryReport {
+            cpu_bytes: data.cpu_bytes,
+            gpu_bytes: frame_texture_bytes,
+            frame_count: data.frame_count,
+        }
+    }
+
+    /// Consumes the player, releasing its GPU resources and returning the decoded frames.
+    ///
+    /// Frames spilled to disk are read back into memory first.
+    pub fn into_data(self) -> WebpAnimationData {
+        #[cfg(feature = "spill-to-disk")]
+        if let Some(mut spill) = self.spill {
+            let mut data = self.data;
+            spill.page_in_all(&mut data.frames);
+            return data;
+        }
+        self.data
+    }
+
+    /// See [`WebpAnimationData::export_frames`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or any file cannot be written.
+    pub fn export_frames<P: AsRef<Path>>(&self, dir: P, format: ImageFormat) -> Result<()> {
+        self.data.export_frames(dir, format)
+    }
+
+    /// See [`WebpAnimationData::export_sprite_sheet`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be written.
+    pub fn export_sprite_sheet<P: AsRef<Path>>(&self, path: P, columns: Option<u32>) -> Result<()> {
+        self.data.export_sprite_sheet(path, columns)
+    }
+
+    /// See [`WebpAnimationData::export_gif`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the canvas is too large for GIF or the file cannot be written.
+    #[cfg(feature = "gif")]
+    pub fn export_gif<P: AsRef<Path>>(&self, path: P, speed: i32) -> Result<()> {
+        self.data.export_gif(path, speed)
+    }
+
+    /// See [`WebpAnimationData::export_apng`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    #[cfg(feature = "apng")]
+    pub fn export_apng<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.data.export_apng(path)
+    }
+
+    /// See [`WebpAnimationData::export_video`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ffmpeg` cannot be started or fails.
+    #[cfg(feature = "ffmpeg")]
+    pub fn export_video<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: export::VideoFormat,
+        fps: f64,
+    ) -> Result<()> {
+        self.data.export_video(path, format, fps)
+    }
+
+    /// See [`WebpAnimationData::encode_job`].
+    #[cfg(libwebp_encode)]
+    pub fn encode_job(&self) -> EncodeJob<'_> {
+        self.data.encode_job()
+    }
+
+    /// See [`WebpAnimationData::trim`].
+    #[cfg(libwebp_encode)]
+    pub fn trim(&self, start: usize, end: usize) -> EncodeJob<'_> {
+        self.data.trim(start, end)
+    }
+
+    /// See [`WebpAnimationData::trim_time`].
+    #[cfg(libwebp_encode)]
+    pub fn trim_time(&self, start: Duration, end: Duration) -> EncodeJob<'_> {
+        self.data.trim_time(start, end)
     }
 
     /// Sets whether the animation should loop after reaching the final frame.
@@ -114,6 +1605,210 @@ impl WebpAnimation {
     ///
     /// - `looping`: If `true`, the animation will loop indefinitely. If `false`, it will stop at the last frame.
     pub fn set_looping(&mut self, looping: bool) {
-        self.is_looping = looping;
+        self.playhead.looping = looping;
     }
+
+    /// Sets the playback speed multiplier.
+    ///
+    /// # Parameters
+    ///
+    /// - `speed`: `1.0` plays at the encoded frame rate, `2.0` twice as fast and
+    ///   `0.5` at half speed. Negative or non-finite values are treated as `0.0`,
+    ///   which pauses playback.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.playhead.set_speed(speed);
+    }
+
+    /// Returns the playback speed multiplier.
+    pub fn speed(&self) -> f32 {
+        self.playhead.speed()
+    }
+
+    /// Sets the order in which frames are played.
+    pub fn set_direction(&mut self, direction: PlaybackDirection) {
+        self.playhead.direction = direction;
+    }
+
+    /// Returns the order in which frames are played.
+    pub fn direction(&self) -> PlaybackDirection {
+        self.playhead.direction
+    }
+
+    /// Returns how many times playback has wrapped around since the player was
+    /// created or restored.
+    pub fn loop_count(&self) -> u64 {
+        self.playhead.loop_count
+    }
+
+    /// Captures the playhead so it can be resumed later with [`restore`](Self::restore).
+    pub fn playback_state(&self) -> PlaybackSnapshot {
+        self.playhead.snapshot()
+    }
+
+    /// Moves the playhead to a state captured with [`playback_state`](Self::playback_state).
+    ///
+    /// The frame index is clamped to the last frame, so snapshots stay usable
+    /// after the animation was edited.
+    pub fn restore(&mut self, snapshot: PlaybackSnapshot) {
+        self.playhead.restore(snapshot, self.data.frames.len());
+        #[cfg(feature = "spill-to-disk")]
+        self.page_frames();
+    }
+
+    /// Freezes the playhead until [`play`](Self::play) is called.
+    pub fn pause(&mut self) {
+        self.playhead.pause();
+    }
+
+    /// Resumes playback after [`pause`](Self::pause) from where it stopped.
+    pub fn play(&mut self) {
+        self.playhead.play();
+    }
+
+    /// Returns `true` unless playback is paused.
+    pub fn is_playing(&self) -> bool {
+        self.playhead.is_playing()
+    }
+
+    /// Replaces the clock frame durations are measured against, keeping the
+    /// playhead; see [`Clock`].
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.playhead.set_clock(Arc::new(clock));
+    }
+
+    /// Sets how the animation transitions between consecutive frames.
+    ///
+    /// # Parameters
+    ///
+    /// - `interpolation`: [`Interpolation::Step`] shows each frame as is, while
+    ///   [`Interpolation::Crossfade`] blends adjacent frames over time.
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Returns the current interpolation mode.
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Sets how frame textures are sampled when drawn at a different size.
+    pub fn set_filter(&mut self, filter: TextureFilter) {
+        self.filter = filter;
+    }
+
+    /// Returns how frame textures are sampled when drawn.
+    pub fn filter(&self) -> TextureFilter {
+        self.filter
+    }
+
+    /// Sets how far ahead of the playhead textures are prepared with lazy
+    /// uploads; see [`WebpAnimationBuilder::prefetch`].
+    pub fn set_prefetch(&mut self, distance: impl Into<Prefetch>) {
+        self.prefetch = distance.into();
+    }
+
+    /// Returns how far ahead of the playhead textures are prepared.
+    pub fn prefetch(&self) -> Prefetch {
+        self.prefetch
+    }
+
+    /// Returns the index of the frame that follows the current one, honouring
+    /// the playback direction and the looping setting.
+    fn next_frame_index(&self) -> usize {
+        self.playhead.next_frame_index(self.data.frames.len())
+    }
+}
+
+impl Clone for WebpAnimationPlayer {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            playhead: self.playhead.clone(),
+            lazy_textures: self.lazy_textures,
+            textures: self.textures.clone(),
+            opaque_bounds: self.opaque_bounds.clone(),
+            precompute_opaque_bounds: self.precompute_opaque_bounds,
+            interpolation: self.interpolation,
+            filter: self.filter,
+            device_queue_pair: self.device_queue_pair.clone(),
+            #[cfg(feature = "texture-compression")]
+            texture_compression: self.texture_compression,
+            #[cfg(feature = "texture-compression")]
+            texture_format: self.texture_format,
+            #[cfg(feature = "hot-reload")]
+            watcher: None,
+            pending: None,
+            load_error: None,
+            source: self.source.clone(),
+            prefetch: self.prefetch,
+            uploader: None,
+            budget: budget::register(),
+            released_size: self.released_size,
+            #[cfg(feature = "spill-to-disk")]
+            spill: self.spill.clone(),
+            #[cfg(feature = "spill-to-disk")]
+            spill_threshold: self.spill_threshold,
+            #[cfg(libwebp)]
+            stream: None,
+        }
+    }
+}
+
+impl fmt::Debug for WebpAnimationPlayer {
+    /// Summarizes the animation and playhead instead of dumping frames and GPU handles.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebpAnimationPlayer")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("frame_count", &self.frame_count())
+            .field("current_frame_index", &self.playhead.frame_index)
+            .field("timestamp", &self.timestamp())
+            .field("is_playing", &self.is_playing())
+            .field("is_looping", &self.playhead.looping)
+            .field("direction", &self.playhead.direction)
+            .field("loop_count", &self.playhead.loop_count)
+            .field("speed", &self.playhead.speed())
+            .field("interpolation", &self.interpolation)
+            .field("filter", &self.filter)
+            .field("lazy_textures", &self.lazy_textures)
+            .finish()
+    }
+}
+
+impl fmt::Display for WebpAnimationPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} animation, frame {}/{}",
+            self.width(),
+            self.height(),
+            self.playhead.frame_index + 1,
+            self.frame_count()
+        )
+    }
+}
+
+/// Returns the size of `texture` in bytes from the block size of its format,
+/// e.g. 8 bytes per 4×4 block for BC1 and 16 for BC7.
+fn texture_bytes(texture: &Texture) -> u64 {
+    let format = texture.format();
+    let (block_width, block_height) = format.block_dimensions();
+    let block_size = format.block_size(None).unwrap_or(4);
+    let [width, height] = texture.size();
+    width.div_ceil(block_width) as u64 * height.div_ceil(block_height) as u64 * block_size as u64
+}
+
+/// Grows `cells` to `len` entries, moving the computed values over unless
+/// the cells are shared with clones.
+#[cfg(libwebp)]
+fn extend_cells<T>(cells: &mut Arc<[OnceLock<T>]>, len: usize) {
+    let mut extended: Vec<OnceLock<T>> = match Arc::get_mut(cells) {
+        Some(cells) => cells
+            .iter_mut()
+            .map(|cell| cell.take().map_or_else(OnceLock::new, OnceLock::from))
+            .collect(),
+        None => cells.iter().map(|_| OnceLock::new()).collect(),
+    };
+    extended.resize_with(len, OnceLock::new);
+    *cells = extended.into();
 }
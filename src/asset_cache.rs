@@ -0,0 +1,98 @@
+use crate::data::WebpAnimationData;
+use crate::decoder::ColorMode;
+use crate::error::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+/// A decoded file together with the modification time it was decoded at.
+struct Entry {
+    /// Modification time of the file when it was decoded.
+    modified: Option<SystemTime>,
+    /// The shared frames.
+    data: WebpAnimationData,
+}
+
+/// One slot per file and color mode. Each slot has its own lock, so
+/// concurrent loads of the same file decode it once while loads of other
+/// files proceed in parallel.
+type Slot = Arc<Mutex<Option<Entry>>>;
+
+/// The decoded animations shared by [`AnimationCache`].
+fn entries() -> MutexGuard<'static, HashMap<(PathBuf, ColorMode), Slot>> {
+    static ENTRIES: OnceLock<Mutex<HashMap<(PathBuf, ColorMode), Slot>>> = OnceLock::new();
+    ENTRIES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The crate-wide cache of decoded animations, keyed by path.
+///
+/// The cache is opt-in: enable it with
+/// [`AnimationSettings::cache_decoded`](crate::AnimationSettings::cache_decoded).
+/// Loading a file that is already cached then returns the same frames
+/// instead of decoding them again, so several entities showing one asset
+/// hold a single copy in memory while each gets its own playhead. A file
+/// that changed on disk since it was cached is decoded again.
+///
+/// Entries live until they are removed, even when no player uses them.
+#[derive(Debug)]
+pub struct AnimationCache;
+
+impl AnimationCache {
+    /// Returns the number of cached files.
+    pub fn len() -> usize {
+        entries().len()
+    }
+
+    /// Returns `true` if nothing is cached.
+    pub fn is_empty() -> bool {
+        Self::len() == 0
+    }
+
+    /// Drops the cached frames of the file at `path` in every color mode.
+    ///
+    /// Players created from them keep their frames.
+    pub fn remove<P: AsRef<Path>>(path: P) {
+        let Ok(path) = path.as_ref().canonicalize() else {
+            return;
+        };
+        entries().retain(|(cached, _), _| *cached != path);
+    }
+
+    /// Drops all cached frames.
+    pub fn clear() {
+        entries().clear();
+    }
+}
+
+/// Returns the cached frames of `path` in `color_mode`, calling `decode` to
+/// fill the cache when they are missing or outdated.
+pub(crate) fn get_or_decode<F>(
+    path: &Path,
+    color_mode: ColorMode,
+    decode: F,
+) -> Result<WebpAnimationData>
+where
+    F: FnOnce() -> Result<WebpAnimationData>,
+{
+    let path = path.canonicalize()?;
+    let modified = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let slot = entries().entry((path, color_mode)).or_default().clone();
+    let mut slot = slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(entry) = slot.as_ref().filter(|entry| entry.modified == modified) {
+        return Ok(entry.data.clone());
+    }
+    let data = decode()?;
+    *slot = Some(Entry {
+        modified,
+        data: data.clone(),
+    });
+    Ok(data)
+}
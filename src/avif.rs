@@ -0,0 +1,119 @@
+use crate::decoder::ColorMode;
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use crate::loader;
+use image::RgbaImage;
+use libavif_sys::*;
+use std::path::Path;
+use std::time::Duration;
+use std::{fs, slice};
+
+/// Frees the libavif decoder when dropped, including on early returns.
+struct Decoder(*mut avifDecoder);
+
+impl Drop for Decoder {
+    fn drop(&mut self) {
+        unsafe { avifDecoderDestroy(self.0) };
+    }
+}
+
+/// Decodes the animated (or still) AVIF file at `path` with libavif.
+pub(crate) fn decode_file(path: &Path, color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    decode_bytes(&fs::read(path)?, color_mode)
+}
+
+/// Decodes an in-memory AVIF with libavif.
+///
+/// Every frame of an AVIF image sequence is a full canvas, so no compositing
+/// is needed; the YUV planes are converted to 8-bit RGBA and then to
+/// `color_mode`.
+pub(crate) fn decode_bytes(data: &[u8], color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    //-----------------------------------------------------------------
+    // 1. Parse the container
+    //-----------------------------------------------------------------
+    let decoder = Decoder(unsafe { avifDecoderCreate() });
+    if decoder.0.is_null() {
+        return Err(WebpAnimationError::Decode(
+            "avifDecoderCreate failed".into(),
+        ));
+    }
+    check(
+        unsafe { avifDecoderSetIOMemory(decoder.0, data.as_ptr(), data.len()) },
+        "avifDecoderSetIOMemory failed",
+    )?;
+    check(
+        unsafe { avifDecoderParse(decoder.0) },
+        "avifDecoderParse failed",
+    )?;
+
+    //-----------------------------------------------------------------
+    // 2. Decode every frame and convert it to RGBA
+    //-----------------------------------------------------------------
+    let count = unsafe { (*decoder.0).imageCount }.max(0) as usize;
+    let mut frames = Vec::with_capacity(count);
+    while unsafe { avifDecoderNextImage(decoder.0) } == AVIF_RESULT_OK {
+        let image = unsafe { (*decoder.0).image };
+        let seconds = unsafe { (*decoder.0).imageTiming.duration };
+
+        let mut rgb: avifRGBImage = unsafe { std::mem::zeroed() };
+        unsafe { avifRGBImageSetDefaults(&mut rgb, image) };
+        rgb.format = AVIF_RGB_FORMAT_RGBA;
+        rgb.depth = 8;
+        check(
+            unsafe { avifRGBImageAllocatePixels(&mut rgb) },
+            "avifRGBImageAllocatePixels failed",
+        )?;
+        let converted = unsafe { avifImageYUVToRGB(image, &mut rgb) };
+        let rgba = if converted == AVIF_RESULT_OK {
+            copy_rows(&rgb)
+        } else {
+            None
+        };
+        unsafe { avifRGBImageFreePixels(&mut rgb) };
+        check(converted, "avifImageYUVToRGB failed")?;
+
+        let image = rgba
+            .and_then(|rgba| color_mode.convert_rgba(rgba))
+            .ok_or_else(|| {
+                WebpAnimationError::Decode("failed to create image from AVIF frame".into())
+            })?;
+        // Like the other decoders, fall back to 100 ms for missing timing.
+        let duration = if seconds > 0.0 {
+            Duration::from_secs_f64(seconds)
+        } else {
+            Duration::from_millis(100)
+        };
+        frames.push(WebpFrame { image, duration });
+        loader::frame_decoded(frames.len(), Some(count).filter(|&count| count > 0))?;
+    }
+
+    if frames.is_empty() {
+        return Err(WebpAnimationError::NoFrames);
+    }
+    Ok(frames)
+}
+
+/// Copies the converted pixels into Rust-owned memory, dropping row padding.
+fn copy_rows(rgb: &avifRGBImage) -> Option<RgbaImage> {
+    let row = rgb.width as usize * 4;
+    let stride = rgb.rowBytes as usize;
+    let pixels = unsafe { slice::from_raw_parts(rgb.pixels, stride * rgb.height as usize) };
+    let raw = pixels
+        .chunks_exact(stride)
+        .flat_map(|line| &line[..row])
+        .copied()
+        .collect();
+    RgbaImage::from_raw(rgb.width, rgb.height, raw)
+}
+
+/// Turns an `avifResult` into a `Result`, using `context` as the message.
+fn check(result: avifResult, context: &str) -> Result<()> {
+    if result == AVIF_RESULT_OK {
+        Ok(())
+    } else {
+        Err(WebpAnimationError::Decode(format!(
+            "{} (error code {})",
+            context, result
+        )))
+    }
+}
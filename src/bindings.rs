@@ -0,0 +1,21 @@
+//! Bindgen‑generated FFI layer for libwebp, libwebpdemux and libwebpmux.
+
+#![allow(
+    dead_code,
+    non_snake_case,
+    non_camel_case_types,
+    non_upper_case_globals,
+    improper_ctypes
+)]
+
+#[cfg(not(feature = "prebuilt-bindings"))]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(feature = "prebuilt-bindings")]
+include!("bindings/prebuilt.rs");
+
+/// Buffers returned by libwebp before 1.0 are released with the C allocator.
+#[cfg(not(libwebp_1_0))]
+pub unsafe fn WebPFree(ptr: *mut libc::c_void) {
+    libc::free(ptr)
+}
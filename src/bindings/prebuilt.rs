@@ -0,0 +1,743 @@
+// Bindings for the libwebp 1.4.0 headers (`decode.h`, `demux.h`, `encode.h`
+// and `mux.h`), in the form bindgen emits for `wrapper.h`. Used by the
+// `prebuilt-bindings` feature so builds do not need libclang.
+//
+// Regenerate against a new libwebp release with:
+//
+//     bindgen wrapper.h --allowlist-function 'WebP.*' --allowlist-type 'WebP.*' \
+//         --allowlist-var 'WEBP.*' --allowlist-var 'WebP.*' --with-derive-default
+//
+// and bump the version above. Older libwebp releases are ABI-compatible as long
+// as their `*_ABI_VERSION` major bytes match.
+
+pub const WEBP_DECODER_ABI_VERSION: u32 = 521;
+pub const WEBP_ENCODER_ABI_VERSION: u32 = 527;
+pub const WEBP_DEMUX_ABI_VERSION: u32 = 263;
+pub const WEBP_MUX_ABI_VERSION: u32 = 265;
+pub const WEBP_MAX_DIMENSION: u32 = 16383;
+
+// ---------------------------------------------------------------------------
+// types.h
+
+extern "C" {
+    pub fn WebPMalloc(size: usize) -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn WebPFree(ptr: *mut ::std::os::raw::c_void);
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPData {
+    pub bytes: *const u8,
+    pub size: usize,
+}
+impl Default for WebPData {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// decode.h
+
+pub const WEBP_CSP_MODE_MODE_RGB: WEBP_CSP_MODE = 0;
+pub const WEBP_CSP_MODE_MODE_RGBA: WEBP_CSP_MODE = 1;
+pub const WEBP_CSP_MODE_MODE_BGR: WEBP_CSP_MODE = 2;
+pub const WEBP_CSP_MODE_MODE_BGRA: WEBP_CSP_MODE = 3;
+pub const WEBP_CSP_MODE_MODE_ARGB: WEBP_CSP_MODE = 4;
+pub const WEBP_CSP_MODE_MODE_RGBA_4444: WEBP_CSP_MODE = 5;
+pub const WEBP_CSP_MODE_MODE_RGB_565: WEBP_CSP_MODE = 6;
+pub const WEBP_CSP_MODE_MODE_rgbA: WEBP_CSP_MODE = 7;
+pub const WEBP_CSP_MODE_MODE_bgrA: WEBP_CSP_MODE = 8;
+pub const WEBP_CSP_MODE_MODE_Argb: WEBP_CSP_MODE = 9;
+pub const WEBP_CSP_MODE_MODE_rgbA_4444: WEBP_CSP_MODE = 10;
+pub const WEBP_CSP_MODE_MODE_YUV: WEBP_CSP_MODE = 11;
+pub const WEBP_CSP_MODE_MODE_YUVA: WEBP_CSP_MODE = 12;
+pub const WEBP_CSP_MODE_MODE_LAST: WEBP_CSP_MODE = 13;
+pub type WEBP_CSP_MODE = ::std::os::raw::c_uint;
+
+extern "C" {
+    pub fn WebPGetDecoderVersion() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPGetInfo(
+        data: *const u8,
+        data_size: usize,
+        width: *mut ::std::os::raw::c_int,
+        height: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPDecodeRGBA(
+        data: *const u8,
+        data_size: usize,
+        width: *mut ::std::os::raw::c_int,
+        height: *mut ::std::os::raw::c_int,
+    ) -> *mut u8;
+}
+extern "C" {
+    pub fn WebPDecodeBGRA(
+        data: *const u8,
+        data_size: usize,
+        width: *mut ::std::os::raw::c_int,
+        height: *mut ::std::os::raw::c_int,
+    ) -> *mut u8;
+}
+extern "C" {
+    pub fn WebPDecodeRGBAInto(
+        data: *const u8,
+        data_size: usize,
+        output_buffer: *mut u8,
+        output_buffer_size: usize,
+        output_stride: ::std::os::raw::c_int,
+    ) -> *mut u8;
+}
+
+// ---------------------------------------------------------------------------
+// demux.h
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPDemuxer {
+    _unused: [u8; 0],
+}
+
+pub const WebPDemuxState_WEBP_DEMUX_PARSE_ERROR: WebPDemuxState = -1;
+pub const WebPDemuxState_WEBP_DEMUX_PARSING_HEADER: WebPDemuxState = 0;
+pub const WebPDemuxState_WEBP_DEMUX_PARSED_HEADER: WebPDemuxState = 1;
+pub const WebPDemuxState_WEBP_DEMUX_DONE: WebPDemuxState = 2;
+pub type WebPDemuxState = ::std::os::raw::c_int;
+
+pub const WebPFormatFeature_WEBP_FF_FORMAT_FLAGS: WebPFormatFeature = 0;
+pub const WebPFormatFeature_WEBP_FF_CANVAS_WIDTH: WebPFormatFeature = 1;
+pub const WebPFormatFeature_WEBP_FF_CANVAS_HEIGHT: WebPFormatFeature = 2;
+pub const WebPFormatFeature_WEBP_FF_LOOP_COUNT: WebPFormatFeature = 3;
+pub const WebPFormatFeature_WEBP_FF_BACKGROUND_COLOR: WebPFormatFeature = 4;
+pub const WebPFormatFeature_WEBP_FF_FRAME_COUNT: WebPFormatFeature = 5;
+pub type WebPFormatFeature = ::std::os::raw::c_uint;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPIterator {
+    pub frame_num: ::std::os::raw::c_int,
+    pub num_frames: ::std::os::raw::c_int,
+    pub x_offset: ::std::os::raw::c_int,
+    pub y_offset: ::std::os::raw::c_int,
+    pub width: ::std::os::raw::c_int,
+    pub height: ::std::os::raw::c_int,
+    pub duration: ::std::os::raw::c_int,
+    pub dispose_method: WebPMuxAnimDispose,
+    pub complete: ::std::os::raw::c_int,
+    pub fragment: WebPData,
+    pub has_alpha: ::std::os::raw::c_int,
+    pub blend_method: WebPMuxAnimBlend,
+    pub pad: [u32; 2usize],
+    pub private_: *mut ::std::os::raw::c_void,
+}
+impl Default for WebPIterator {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPChunkIterator {
+    pub chunk_num: ::std::os::raw::c_int,
+    pub num_chunks: ::std::os::raw::c_int,
+    pub chunk: WebPData,
+    pub pad: [u32; 6usize],
+    pub private_: *mut ::std::os::raw::c_void,
+}
+impl Default for WebPChunkIterator {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPAnimDecoder {
+    _unused: [u8; 0],
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebPAnimDecoderOptions {
+    pub color_mode: WEBP_CSP_MODE,
+    pub use_threads: ::std::os::raw::c_int,
+    pub padding: [u32; 7usize],
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebPAnimInfo {
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    pub loop_count: u32,
+    pub bgcolor: u32,
+    pub frame_count: u32,
+    pub pad: [u32; 4usize],
+}
+
+extern "C" {
+    pub fn WebPGetDemuxVersion() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPDemuxInternal(
+        arg1: *const WebPData,
+        arg2: ::std::os::raw::c_int,
+        arg3: *mut WebPDemuxState,
+        arg4: ::std::os::raw::c_int,
+    ) -> *mut WebPDemuxer;
+}
+extern "C" {
+    pub fn WebPDemuxDelete(dmux: *mut WebPDemuxer);
+}
+extern "C" {
+    pub fn WebPDemuxGetI(dmux: *const WebPDemuxer, feature: WebPFormatFeature) -> u32;
+}
+extern "C" {
+    pub fn WebPDemuxGetFrame(
+        dmux: *const WebPDemuxer,
+        frame_number: ::std::os::raw::c_int,
+        iter: *mut WebPIterator,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPDemuxNextFrame(iter: *mut WebPIterator) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPDemuxPrevFrame(iter: *mut WebPIterator) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPDemuxReleaseIterator(iter: *mut WebPIterator);
+}
+extern "C" {
+    pub fn WebPDemuxGetChunk(
+        dmux: *const WebPDemuxer,
+        fourcc: *const ::std::os::raw::c_char,
+        chunk_number: ::std::os::raw::c_int,
+        iter: *mut WebPChunkIterator,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPDemuxReleaseChunkIterator(iter: *mut WebPChunkIterator);
+}
+extern "C" {
+    pub fn WebPAnimDecoderOptionsInitInternal(
+        arg1: *mut WebPAnimDecoderOptions,
+        arg2: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimDecoderNewInternal(
+        arg1: *const WebPData,
+        arg2: *const WebPAnimDecoderOptions,
+        arg3: ::std::os::raw::c_int,
+    ) -> *mut WebPAnimDecoder;
+}
+extern "C" {
+    pub fn WebPAnimDecoderGetInfo(
+        dec: *const WebPAnimDecoder,
+        info: *mut WebPAnimInfo,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimDecoderGetNext(
+        dec: *mut WebPAnimDecoder,
+        buf: *mut *mut u8,
+        timestamp: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimDecoderHasMoreFrames(dec: *const WebPAnimDecoder) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimDecoderReset(dec: *mut WebPAnimDecoder);
+}
+extern "C" {
+    pub fn WebPAnimDecoderGetDemuxer(dec: *const WebPAnimDecoder) -> *const WebPDemuxer;
+}
+extern "C" {
+    pub fn WebPAnimDecoderDelete(dec: *mut WebPAnimDecoder);
+}
+
+// ---------------------------------------------------------------------------
+// encode.h
+
+pub const WebPImageHint_WEBP_HINT_DEFAULT: WebPImageHint = 0;
+pub const WebPImageHint_WEBP_HINT_PICTURE: WebPImageHint = 1;
+pub const WebPImageHint_WEBP_HINT_PHOTO: WebPImageHint = 2;
+pub const WebPImageHint_WEBP_HINT_GRAPH: WebPImageHint = 3;
+pub const WebPImageHint_WEBP_HINT_LAST: WebPImageHint = 4;
+pub type WebPImageHint = ::std::os::raw::c_uint;
+
+pub const WebPPreset_WEBP_PRESET_DEFAULT: WebPPreset = 0;
+pub const WebPPreset_WEBP_PRESET_PICTURE: WebPPreset = 1;
+pub const WebPPreset_WEBP_PRESET_PHOTO: WebPPreset = 2;
+pub const WebPPreset_WEBP_PRESET_DRAWING: WebPPreset = 3;
+pub const WebPPreset_WEBP_PRESET_ICON: WebPPreset = 4;
+pub const WebPPreset_WEBP_PRESET_TEXT: WebPPreset = 5;
+pub type WebPPreset = ::std::os::raw::c_uint;
+
+pub const WebPEncCSP_WEBP_YUV420: WebPEncCSP = 0;
+pub const WebPEncCSP_WEBP_YUV420A: WebPEncCSP = 4;
+pub const WebPEncCSP_WEBP_CSP_UV_MASK: WebPEncCSP = 3;
+pub const WebPEncCSP_WEBP_CSP_ALPHA_BIT: WebPEncCSP = 4;
+pub type WebPEncCSP = ::std::os::raw::c_uint;
+
+pub const WebPEncodingError_VP8_ENC_OK: WebPEncodingError = 0;
+pub const WebPEncodingError_VP8_ENC_ERROR_OUT_OF_MEMORY: WebPEncodingError = 1;
+pub const WebPEncodingError_VP8_ENC_ERROR_BITSTREAM_OUT_OF_MEMORY: WebPEncodingError = 2;
+pub const WebPEncodingError_VP8_ENC_ERROR_NULL_PARAMETER: WebPEncodingError = 3;
+pub const WebPEncodingError_VP8_ENC_ERROR_INVALID_CONFIGURATION: WebPEncodingError = 4;
+pub const WebPEncodingError_VP8_ENC_ERROR_BAD_DIMENSION: WebPEncodingError = 5;
+pub const WebPEncodingError_VP8_ENC_ERROR_PARTITION0_OVERFLOW: WebPEncodingError = 6;
+pub const WebPEncodingError_VP8_ENC_ERROR_PARTITION_OVERFLOW: WebPEncodingError = 7;
+pub const WebPEncodingError_VP8_ENC_ERROR_BAD_WRITE: WebPEncodingError = 8;
+pub const WebPEncodingError_VP8_ENC_ERROR_FILE_TOO_BIG: WebPEncodingError = 9;
+pub const WebPEncodingError_VP8_ENC_ERROR_USER_ABORT: WebPEncodingError = 10;
+pub const WebPEncodingError_VP8_ENC_ERROR_LAST: WebPEncodingError = 11;
+pub type WebPEncodingError = ::std::os::raw::c_uint;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebPConfig {
+    pub lossless: ::std::os::raw::c_int,
+    pub quality: f32,
+    pub method: ::std::os::raw::c_int,
+    pub image_hint: WebPImageHint,
+    pub target_size: ::std::os::raw::c_int,
+    pub target_PSNR: f32,
+    pub segments: ::std::os::raw::c_int,
+    pub sns_strength: ::std::os::raw::c_int,
+    pub filter_strength: ::std::os::raw::c_int,
+    pub filter_sharpness: ::std::os::raw::c_int,
+    pub filter_type: ::std::os::raw::c_int,
+    pub autofilter: ::std::os::raw::c_int,
+    pub alpha_compression: ::std::os::raw::c_int,
+    pub alpha_filtering: ::std::os::raw::c_int,
+    pub alpha_quality: ::std::os::raw::c_int,
+    pub pass: ::std::os::raw::c_int,
+    pub show_compressed: ::std::os::raw::c_int,
+    pub preprocessing: ::std::os::raw::c_int,
+    pub partitions: ::std::os::raw::c_int,
+    pub partition_limit: ::std::os::raw::c_int,
+    pub emulate_jpeg_size: ::std::os::raw::c_int,
+    pub thread_level: ::std::os::raw::c_int,
+    pub low_memory: ::std::os::raw::c_int,
+    pub near_lossless: ::std::os::raw::c_int,
+    pub exact: ::std::os::raw::c_int,
+    pub use_delta_palette: ::std::os::raw::c_int,
+    pub use_sharp_yuv: ::std::os::raw::c_int,
+    pub qmin: ::std::os::raw::c_int,
+    pub qmax: ::std::os::raw::c_int,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebPAuxStats {
+    pub coded_size: ::std::os::raw::c_int,
+    pub PSNR: [f32; 5usize],
+    pub block_count: [::std::os::raw::c_int; 3usize],
+    pub header_bytes: [::std::os::raw::c_int; 2usize],
+    pub residual_bytes: [[::std::os::raw::c_int; 4usize]; 3usize],
+    pub segment_size: [::std::os::raw::c_int; 4usize],
+    pub segment_quant: [::std::os::raw::c_int; 4usize],
+    pub segment_level: [::std::os::raw::c_int; 4usize],
+    pub alpha_data_size: ::std::os::raw::c_int,
+    pub layer_data_size: ::std::os::raw::c_int,
+    pub lossless_features: u32,
+    pub histogram_bits: ::std::os::raw::c_int,
+    pub transform_bits: ::std::os::raw::c_int,
+    pub cache_bits: ::std::os::raw::c_int,
+    pub palette_size: ::std::os::raw::c_int,
+    pub lossless_size: ::std::os::raw::c_int,
+    pub lossless_hdr_size: ::std::os::raw::c_int,
+    pub lossless_data_size: ::std::os::raw::c_int,
+    pub pad: [u32; 2usize],
+}
+
+pub type WebPWriterFunction = ::std::option::Option<
+    unsafe extern "C" fn(
+        data: *const u8,
+        data_size: usize,
+        picture: *const WebPPicture,
+    ) -> ::std::os::raw::c_int,
+>;
+
+pub type WebPProgressHook = ::std::option::Option<
+    unsafe extern "C" fn(
+        percent: ::std::os::raw::c_int,
+        picture: *const WebPPicture,
+    ) -> ::std::os::raw::c_int,
+>;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPMemoryWriter {
+    pub mem: *mut u8,
+    pub size: usize,
+    pub max_size: usize,
+    pub pad: [u32; 1usize],
+}
+impl Default for WebPMemoryWriter {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPPicture {
+    pub use_argb: ::std::os::raw::c_int,
+    pub colorspace: WebPEncCSP,
+    pub width: ::std::os::raw::c_int,
+    pub height: ::std::os::raw::c_int,
+    pub y: *mut u8,
+    pub u: *mut u8,
+    pub v: *mut u8,
+    pub y_stride: ::std::os::raw::c_int,
+    pub uv_stride: ::std::os::raw::c_int,
+    pub a: *mut u8,
+    pub a_stride: ::std::os::raw::c_int,
+    pub pad1: [u32; 2usize],
+    pub argb: *mut u32,
+    pub argb_stride: ::std::os::raw::c_int,
+    pub pad2: [u32; 3usize],
+    pub writer: WebPWriterFunction,
+    pub custom_ptr: *mut ::std::os::raw::c_void,
+    pub extra_info_type: ::std::os::raw::c_int,
+    pub extra_info: *mut u8,
+    pub stats: *mut WebPAuxStats,
+    pub error_code: WebPEncodingError,
+    pub progress_hook: WebPProgressHook,
+    pub user_data: *mut ::std::os::raw::c_void,
+    pub pad3: [u32; 3usize],
+    pub pad4: *mut u8,
+    pub pad5: *mut u8,
+    pub pad6: [u32; 8usize],
+    pub memory_: *mut ::std::os::raw::c_void,
+    pub memory_argb_: *mut ::std::os::raw::c_void,
+    pub pad7: [*mut ::std::os::raw::c_void; 2usize],
+}
+impl Default for WebPPicture {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+
+extern "C" {
+    pub fn WebPGetEncoderVersion() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPConfigInitInternal(
+        arg1: *mut WebPConfig,
+        arg2: WebPPreset,
+        arg3: f32,
+        arg4: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPConfigLosslessPreset(
+        config: *mut WebPConfig,
+        level: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPValidateConfig(config: *const WebPConfig) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPMemoryWriterInit(writer: *mut WebPMemoryWriter);
+}
+extern "C" {
+    pub fn WebPMemoryWriterClear(writer: *mut WebPMemoryWriter);
+}
+extern "C" {
+    pub fn WebPMemoryWrite(
+        data: *const u8,
+        data_size: usize,
+        picture: *const WebPPicture,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPPictureInitInternal(
+        arg1: *mut WebPPicture,
+        arg2: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPPictureAlloc(picture: *mut WebPPicture) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPPictureFree(picture: *mut WebPPicture);
+}
+extern "C" {
+    pub fn WebPPictureImportRGB(
+        picture: *mut WebPPicture,
+        rgb: *const u8,
+        rgb_stride: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPPictureImportRGBA(
+        picture: *mut WebPPicture,
+        rgba: *const u8,
+        rgba_stride: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPPictureImportBGRA(
+        picture: *mut WebPPicture,
+        bgra: *const u8,
+        bgra_stride: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPEncode(
+        config: *const WebPConfig,
+        picture: *mut WebPPicture,
+    ) -> ::std::os::raw::c_int;
+}
+
+// ---------------------------------------------------------------------------
+// mux.h
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPMux {
+    _unused: [u8; 0],
+}
+
+pub const WebPMuxError_WEBP_MUX_OK: WebPMuxError = 1;
+pub const WebPMuxError_WEBP_MUX_NOT_FOUND: WebPMuxError = 0;
+pub const WebPMuxError_WEBP_MUX_INVALID_ARGUMENT: WebPMuxError = -1;
+pub const WebPMuxError_WEBP_MUX_BAD_DATA: WebPMuxError = -2;
+pub const WebPMuxError_WEBP_MUX_MEMORY_ERROR: WebPMuxError = -3;
+pub const WebPMuxError_WEBP_MUX_NOT_ENOUGH_DATA: WebPMuxError = -4;
+pub type WebPMuxError = ::std::os::raw::c_int;
+
+pub const WebPChunkId_WEBP_CHUNK_VP8X: WebPChunkId = 0;
+pub const WebPChunkId_WEBP_CHUNK_ICCP: WebPChunkId = 1;
+pub const WebPChunkId_WEBP_CHUNK_ANIM: WebPChunkId = 2;
+pub const WebPChunkId_WEBP_CHUNK_ANMF: WebPChunkId = 3;
+pub const WebPChunkId_WEBP_CHUNK_DEPRECATED: WebPChunkId = 4;
+pub const WebPChunkId_WEBP_CHUNK_ALPHA: WebPChunkId = 5;
+pub const WebPChunkId_WEBP_CHUNK_IMAGE: WebPChunkId = 6;
+pub const WebPChunkId_WEBP_CHUNK_EXIF: WebPChunkId = 7;
+pub const WebPChunkId_WEBP_CHUNK_XMP: WebPChunkId = 8;
+pub const WebPChunkId_WEBP_CHUNK_UNKNOWN: WebPChunkId = 9;
+pub const WebPChunkId_WEBP_CHUNK_NIL: WebPChunkId = 10;
+pub type WebPChunkId = ::std::os::raw::c_uint;
+
+pub const WebPMuxAnimDispose_WEBP_MUX_DISPOSE_NONE: WebPMuxAnimDispose = 0;
+pub const WebPMuxAnimDispose_WEBP_MUX_DISPOSE_BACKGROUND: WebPMuxAnimDispose = 1;
+pub type WebPMuxAnimDispose = ::std::os::raw::c_uint;
+
+pub const WebPMuxAnimBlend_WEBP_MUX_BLEND: WebPMuxAnimBlend = 0;
+pub const WebPMuxAnimBlend_WEBP_MUX_NO_BLEND: WebPMuxAnimBlend = 1;
+pub type WebPMuxAnimBlend = ::std::os::raw::c_uint;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPMuxFrameInfo {
+    pub bitstream: WebPData,
+    pub x_offset: ::std::os::raw::c_int,
+    pub y_offset: ::std::os::raw::c_int,
+    pub duration: ::std::os::raw::c_int,
+    pub id: WebPChunkId,
+    pub dispose_method: WebPMuxAnimDispose,
+    pub blend_method: WebPMuxAnimBlend,
+    pub pad: [u32; 1usize],
+}
+impl Default for WebPMuxFrameInfo {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebPMuxAnimParams {
+    pub bgcolor: u32,
+    pub loop_count: ::std::os::raw::c_int,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct WebPAnimEncoder {
+    _unused: [u8; 0],
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebPAnimEncoderOptions {
+    pub anim_params: WebPMuxAnimParams,
+    pub minimize_size: ::std::os::raw::c_int,
+    pub kmin: ::std::os::raw::c_int,
+    pub kmax: ::std::os::raw::c_int,
+    pub allow_mixed: ::std::os::raw::c_int,
+    pub verbose: ::std::os::raw::c_int,
+    pub padding: [u32; 4usize],
+}
+
+extern "C" {
+    pub fn WebPGetMuxVersion() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPNewInternal(arg1: ::std::os::raw::c_int) -> *mut WebPMux;
+}
+extern "C" {
+    pub fn WebPMuxDelete(mux: *mut WebPMux);
+}
+extern "C" {
+    pub fn WebPMuxCreateInternal(
+        arg1: *const WebPData,
+        arg2: ::std::os::raw::c_int,
+        arg3: ::std::os::raw::c_int,
+    ) -> *mut WebPMux;
+}
+extern "C" {
+    pub fn WebPMuxSetChunk(
+        mux: *mut WebPMux,
+        fourcc: *const ::std::os::raw::c_char,
+        chunk_data: *const WebPData,
+        copy_data: ::std::os::raw::c_int,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxGetChunk(
+        mux: *const WebPMux,
+        fourcc: *const ::std::os::raw::c_char,
+        chunk_data: *mut WebPData,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxDeleteChunk(
+        mux: *mut WebPMux,
+        fourcc: *const ::std::os::raw::c_char,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxSetImage(
+        mux: *mut WebPMux,
+        bitstream: *const WebPData,
+        copy_data: ::std::os::raw::c_int,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxPushFrame(
+        mux: *mut WebPMux,
+        frame: *const WebPMuxFrameInfo,
+        copy_data: ::std::os::raw::c_int,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxGetFrame(
+        mux: *const WebPMux,
+        nth: u32,
+        frame: *mut WebPMuxFrameInfo,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxDeleteFrame(mux: *mut WebPMux, nth: u32) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxSetAnimationParams(
+        mux: *mut WebPMux,
+        params: *const WebPMuxAnimParams,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxGetAnimationParams(
+        mux: *const WebPMux,
+        params: *mut WebPMuxAnimParams,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxSetCanvasSize(
+        mux: *mut WebPMux,
+        width: ::std::os::raw::c_int,
+        height: ::std::os::raw::c_int,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxGetCanvasSize(
+        mux: *const WebPMux,
+        width: *mut ::std::os::raw::c_int,
+        height: *mut ::std::os::raw::c_int,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxGetFeatures(mux: *const WebPMux, flags: *mut u32) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxNumChunks(
+        mux: *const WebPMux,
+        id: WebPChunkId,
+        num_elements: *mut ::std::os::raw::c_int,
+    ) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPMuxAssemble(mux: *mut WebPMux, assembled_data: *mut WebPData) -> WebPMuxError;
+}
+extern "C" {
+    pub fn WebPAnimEncoderOptionsInitInternal(
+        arg1: *mut WebPAnimEncoderOptions,
+        arg2: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimEncoderNewInternal(
+        arg1: ::std::os::raw::c_int,
+        arg2: ::std::os::raw::c_int,
+        arg3: *const WebPAnimEncoderOptions,
+        arg4: ::std::os::raw::c_int,
+    ) -> *mut WebPAnimEncoder;
+}
+extern "C" {
+    pub fn WebPAnimEncoderAdd(
+        enc: *mut WebPAnimEncoder,
+        frame: *mut WebPPicture,
+        timestamp_ms: ::std::os::raw::c_int,
+        config: *const WebPConfig,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimEncoderAssemble(
+        enc: *mut WebPAnimEncoder,
+        webp_data: *mut WebPData,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn WebPAnimEncoderGetError(enc: *mut WebPAnimEncoder) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn WebPAnimEncoderDelete(enc: *mut WebPAnimEncoder);
+}
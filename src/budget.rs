@@ -0,0 +1,224 @@
+use crate::clock::Instant;
+use crate::data::MemoryReport;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+
+/// How long a player must go undrawn before the budget frees its memory.
+const IDLE_AFTER: Duration = Duration::from_secs(1);
+
+/// How often the totals are compared against the budget.
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Crate-wide limits on the memory held by all players together.
+///
+/// Set it with [`AnimationSettings::memory_budget`](crate::AnimationSettings::memory_budget).
+/// Every [`WebpAnimationPlayer`](crate::WebpAnimationPlayer) reports its
+/// [`memory_usage`](crate::WebpAnimationPlayer::memory_usage) from `update()`,
+/// and while the total is over a limit the players that have not been drawn
+/// for a second free memory, least recently drawn first:
+///
+/// - Over the GPU limit, a player drops its frame textures and switches to
+///   lazy uploads, so they are uploaded again when it is next drawn.
+/// - Over the CPU limit, a player whose textures are all uploaded frees its
+///   decoded pixels like [`release_cpu_frames`](crate::WebpAnimationPlayer::release_cpu_frames).
+///   A player without pixels keeps its textures, as they can't be uploaded
+///   again.
+///
+/// Players on screen are never touched, so the total can stay over the limit
+/// if the visible animations alone exceed it. Frames and textures shared
+/// between clones are counted by each clone.
+///
+/// ```no_run
+/// # use nannou_webp_animation::{AnimationSettings, MemoryBudget};
+/// AnimationSettings {
+///     memory_budget: MemoryBudget {
+///         cpu_bytes: Some(512 << 20),
+///         gpu_bytes: Some(1 << 30),
+///     },
+///     ..AnimationSettings::global()
+/// }
+/// .set_global();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Maximum bytes of decoded frames on the CPU, or `None` for no limit.
+    pub cpu_bytes: Option<u64>,
+    /// Maximum bytes of frame textures on the GPU, or `None` for no limit.
+    pub gpu_bytes: Option<u64>,
+}
+
+impl MemoryBudget {
+    /// No limits; players manage their memory on their own.
+    pub(crate) const UNLIMITED: Self = Self {
+        cpu_bytes: None,
+        gpu_bytes: None,
+    };
+
+    /// Returns the memory held by all players together, as last reported
+    /// from their `update()`.
+    pub fn usage() -> MemoryReport {
+        live_trackers()
+            .iter()
+            .fold(MemoryReport::default(), |total, tracker| MemoryReport {
+                cpu_bytes: total.cpu_bytes + tracker.cpu_bytes.load(Ordering::Relaxed),
+                gpu_bytes: total.gpu_bytes + tracker.gpu_bytes.load(Ordering::Relaxed),
+                frame_count: total.frame_count + tracker.frame_count.load(Ordering::Relaxed),
+            })
+    }
+
+    /// Returns `true` if neither limit is set.
+    pub(crate) fn is_unlimited(&self) -> bool {
+        *self == Self::UNLIMITED
+    }
+}
+
+/// The memory use of one player, shared between the player and the budget.
+#[derive(Debug, Default)]
+pub(crate) struct Tracker {
+    /// Bytes held on the CPU, as last reported.
+    cpu_bytes: AtomicU64,
+    /// Bytes held on the GPU, as last reported.
+    gpu_bytes: AtomicU64,
+    /// Number of frames, as last reported.
+    frame_count: AtomicUsize,
+    /// Value of [`now`] when the player was last drawn.
+    last_drawn: AtomicU64,
+    /// Whether dropping the textures would free GPU memory.
+    can_evict_textures: AtomicBool,
+    /// Whether freeing the decoded pixels would not need new uploads.
+    can_release_pixels: AtomicBool,
+    /// Set by the budget to ask the player to drop its textures.
+    evict_textures: AtomicBool,
+    /// Set by the budget to ask the player to free its decoded pixels.
+    release_pixels: AtomicBool,
+}
+
+impl Tracker {
+    /// Records that the player was drawn just now.
+    pub(crate) fn touch(&self) {
+        self.last_drawn.store(now(), Ordering::Relaxed);
+    }
+
+    /// Records the memory the player holds and what it could free.
+    pub(crate) fn report(&self, usage: MemoryReport, can_evict: bool, can_release: bool) {
+        self.cpu_bytes.store(usage.cpu_bytes, Ordering::Relaxed);
+        self.gpu_bytes.store(usage.gpu_bytes, Ordering::Relaxed);
+        self.frame_count.store(usage.frame_count, Ordering::Relaxed);
+        self.can_evict_textures.store(can_evict, Ordering::Relaxed);
+        self.can_release_pixels
+            .store(can_release, Ordering::Relaxed);
+    }
+
+    /// Returns whether the budget asked the player to drop its textures, once.
+    pub(crate) fn take_evict_textures(&self) -> bool {
+        self.evict_textures.swap(false, Ordering::Relaxed)
+    }
+
+    /// Returns whether the budget asked the player to free its pixels, once.
+    pub(crate) fn take_release_pixels(&self) -> bool {
+        self.release_pixels.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Every player created so far; dropped players leave dangling entries
+/// behind until the next check.
+static TRACKERS: Mutex<Vec<Weak<Tracker>>> = Mutex::new(Vec::new());
+
+/// Value of [`now`] when the totals were last checked.
+static LAST_CHECK: AtomicU64 = AtomicU64::new(0);
+
+/// Creates the tracker of a new player.
+pub(crate) fn register() -> Arc<Tracker> {
+    let tracker = Arc::new(Tracker::default());
+    tracker.touch();
+    TRACKERS.lock().unwrap().push(Arc::downgrade(&tracker));
+    tracker
+}
+
+/// Compares the reported totals against `budget` and asks idle players to
+/// free memory until they fit; does nothing if it checked recently.
+pub(crate) fn enforce(budget: MemoryBudget) {
+    let now = now();
+    let last = LAST_CHECK.load(Ordering::Relaxed);
+    if now < last + CHECK_INTERVAL.as_millis() as u64
+        || LAST_CHECK
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+    {
+        return;
+    }
+
+    let mut idle: Vec<Arc<Tracker>> = live_trackers()
+        .into_iter()
+        .filter(|tracker| {
+            now.saturating_sub(tracker.last_drawn.load(Ordering::Relaxed))
+                >= IDLE_AFTER.as_millis() as u64
+        })
+        .collect();
+    idle.sort_by_key(|tracker| tracker.last_drawn.load(Ordering::Relaxed));
+
+    if let Some(limit) = budget.gpu_bytes {
+        free_until(limit, &idle, |tracker| {
+            (
+                &tracker.gpu_bytes,
+                &tracker.can_evict_textures,
+                &tracker.evict_textures,
+            )
+        });
+    }
+    if let Some(limit) = budget.cpu_bytes {
+        free_until(limit, &idle, |tracker| {
+            (
+                &tracker.cpu_bytes,
+                &tracker.can_release_pixels,
+                &tracker.release_pixels,
+            )
+        });
+    }
+}
+
+/// Flags `idle` players, in order, until the bytes left fit in `limit`.
+///
+/// `fields` selects the reported bytes, whether the player can free them and
+/// the request flag. Bytes of players already asked to free them are counted
+/// as freed.
+fn free_until<F>(limit: u64, idle: &[Arc<Tracker>], fields: F)
+where
+    F: Fn(&Tracker) -> (&AtomicU64, &AtomicBool, &AtomicBool),
+{
+    let mut total: u64 = live_trackers()
+        .iter()
+        .map(|tracker| {
+            let (bytes, _, requested) = fields(tracker);
+            if requested.load(Ordering::Relaxed) {
+                0
+            } else {
+                bytes.load(Ordering::Relaxed)
+            }
+        })
+        .sum();
+    for tracker in idle {
+        if total <= limit {
+            break;
+        }
+        let (bytes, can_free, requested) = fields(tracker);
+        if can_free.load(Ordering::Relaxed) && !requested.swap(true, Ordering::Relaxed) {
+            total = total.saturating_sub(bytes.load(Ordering::Relaxed));
+        }
+    }
+}
+
+/// Returns the trackers of the players still alive, forgetting the others.
+fn live_trackers() -> Vec<Arc<Tracker>> {
+    let mut trackers = TRACKERS.lock().unwrap();
+    trackers.retain(|tracker| tracker.strong_count() > 0);
+    trackers.iter().filter_map(Weak::upgrade).collect()
+}
+
+/// Returns the milliseconds since the first call, as a common time base for
+/// the trackers.
+fn now() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
@@ -0,0 +1,311 @@
+use crate::animation::{Interpolation, WebpAnimation, WebpAnimationPlayer};
+use crate::clock::{Clock, SystemClock};
+#[cfg(feature = "texture-compression")]
+use crate::compression::TextureCompression;
+use crate::data::WebpAnimationData;
+use crate::decoder::ColorMode;
+use crate::error::Result;
+use crate::settings::{AnimationSettings, Prefetch, TextureFilter};
+use nannou::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Configures how a [`WebpAnimation`] is loaded and played back.
+///
+/// Created with [`WebpAnimation::builder`]. Every option has a default matching
+/// [`WebpAnimation::from_file`], so only the settings that differ need to be given:
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use nannou_webp_animation::{ColorMode, WebpAnimation};
+/// # fn model(app: &App) -> nannou_webp_animation::Result<WebpAnimation> {
+/// let animation = WebpAnimation::builder("assets/sample.webp")
+///     .looping(false)
+///     .speed(2.0)
+///     .color_mode(ColorMode::Rgba)
+///     .lazy_textures(true)
+///     .build(app)?;
+/// # Ok(animation)
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct WebpAnimationBuilder {
+    /// Path to the WebP file containing the animation.
+    path: PathBuf,
+    /// Pixel layout the frames are decoded to.
+    color_mode: ColorMode,
+    /// Options applied when the player is created.
+    settings: PlayerSettings,
+    /// Whether the player reloads the file when it changes on disk.
+    #[cfg(feature = "hot-reload")]
+    hot_reload: bool,
+}
+
+/// Playback and texture options used to create a [`WebpAnimationPlayer`].
+#[derive(Clone, Debug)]
+pub(crate) struct PlayerSettings {
+    /// Whether playback restarts after the final frame.
+    pub(crate) looping: bool,
+    /// Playback speed multiplier.
+    pub(crate) speed: f32,
+    /// Whether frame textures are uploaded on first display instead of up front.
+    pub(crate) lazy_textures: bool,
+    /// How consecutive frames are presented.
+    pub(crate) interpolation: Interpolation,
+    /// Whether the opaque bounding boxes are computed at load time.
+    pub(crate) precompute_opaque_bounds: bool,
+    /// How frame textures are sampled when drawn.
+    pub(crate) filter: TextureFilter,
+    /// Block compression format frame textures are transcoded to.
+    #[cfg(feature = "texture-compression")]
+    pub(crate) texture_compression: TextureCompression,
+    /// Source of time for playback.
+    pub(crate) clock: Arc<dyn Clock>,
+    /// How far ahead of the playhead textures are staged in the background.
+    pub(crate) prefetch: Prefetch,
+    /// Whether the decoded pixels are freed once the textures exist.
+    pub(crate) gpu_only: bool,
+    /// Bytes of decoded frames above which they are spilled to disk.
+    #[cfg(feature = "spill-to-disk")]
+    pub(crate) spill_threshold: Option<u64>,
+}
+
+impl Default for PlayerSettings {
+    /// Starts from the crate-wide [`AnimationSettings`].
+    fn default() -> Self {
+        let global = AnimationSettings::global();
+        Self {
+            looping: global.looping,
+            speed: 1.0,
+            lazy_textures: global.lazy_textures,
+            interpolation: Interpolation::default(),
+            precompute_opaque_bounds: false,
+            filter: global.filter,
+            #[cfg(feature = "texture-compression")]
+            texture_compression: global.texture_compression,
+            clock: Arc::new(SystemClock),
+            prefetch: Prefetch::default(),
+            gpu_only: false,
+            #[cfg(feature = "spill-to-disk")]
+            spill_threshold: global.spill_threshold,
+        }
+    }
+}
+
+impl WebpAnimationBuilder {
+    /// Creates a builder for the animation at `path` with the crate-wide default
+    /// options; see [`AnimationSettings`].
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            color_mode: ColorMode::default(),
+            settings: PlayerSettings::default(),
+            #[cfg(feature = "hot-reload")]
+            hot_reload: false,
+        }
+    }
+
+    /// Sets whether the animation loops after reaching the final frame.
+    /// Defaults to [`AnimationSettings::looping`].
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.settings.looping = looping;
+        self
+    }
+
+    /// Sets the playback speed multiplier, e.g. `2.0` plays twice as fast. Defaults to `1.0`.
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.settings.speed = speed;
+        self
+    }
+
+    /// Sets the pixel layout the frames are decoded to. Defaults to [`ColorMode::Rgba`].
+    ///
+    /// Note that nannou uploads textures as RGBA, so the other layouts are mainly
+    /// useful when the frames are processed on the CPU.
+    pub fn color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    /// Sets whether frame textures are uploaded the first time each frame is shown
+    /// instead of all at load time. Defaults to [`AnimationSettings::lazy_textures`].
+    ///
+    /// Lazy uploads make loading long animations much faster at the cost of a small
+    /// stall the first time every frame is displayed.
+    pub fn lazy_textures(mut self, lazy_textures: bool) -> Self {
+        self.settings.lazy_textures = lazy_textures;
+        self
+    }
+
+    /// Sets how many upcoming frames have their textures prepared ahead of
+    /// time with lazy uploads. Defaults to `0`, which uploads each frame when
+    /// it is first shown.
+    ///
+    /// A staging thread converts the next `frames` frames to GPU-ready pixels,
+    /// and [`WebpAnimationPlayer::update`] uploads whatever is ready, so
+    /// neither loading nor drawing waits for the conversion. Has no effect
+    /// unless [`lazy_textures`](Self::lazy_textures) is enabled, or with
+    /// texture compression, whose frames are transcoded on first display.
+    ///
+    /// Shorthand for [`prefetch`](Self::prefetch) with [`Prefetch::Frames`].
+    pub fn upload_ahead(mut self, frames: usize) -> Self {
+        self.settings.prefetch = Prefetch::Frames(frames);
+        self
+    }
+
+    /// Sets how far ahead of the playhead textures are prepared with lazy
+    /// uploads, in frames or in time. Defaults to nothing.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # use std::time::Duration;
+    /// # use nannou_webp_animation::{Prefetch, WebpAnimation};
+    /// # fn model(app: &App) -> nannou_webp_animation::Result<()> {
+    /// let animation = WebpAnimation::builder("assets/sample.webp")
+    ///     .lazy_textures(true)
+    ///     .prefetch(Prefetch::Time(Duration::from_millis(250)))
+    ///     .build(app)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See [`upload_ahead`](Self::upload_ahead) for how frames are prepared.
+    pub fn prefetch(mut self, distance: impl Into<Prefetch>) -> Self {
+        self.settings.prefetch = distance.into();
+        self
+    }
+
+    /// Sets how the animation transitions between frames. Defaults to [`Interpolation::Step`].
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.settings.interpolation = interpolation;
+        self
+    }
+
+    /// Sets how frame textures are sampled when drawn at a different size.
+    /// Defaults to [`AnimationSettings::filter`].
+    pub fn filter(mut self, filter: TextureFilter) -> Self {
+        self.settings.filter = filter;
+        self
+    }
+
+    /// Sets whether the bounding box of every frame's visible pixels is computed
+    /// at load time instead of the first time it is queried. Defaults to `false`.
+    ///
+    /// See [`WebpAnimationPlayer::opaque_bounds`].
+    pub fn precompute_opaque_bounds(mut self, precompute: bool) -> Self {
+        self.settings.precompute_opaque_bounds = precompute;
+        self
+    }
+
+    /// Sets whether the decoded pixels are freed on the CPU as soon as every
+    /// frame texture has been uploaded, keeping only the frame durations.
+    /// Defaults to `false`.
+    ///
+    /// Roughly halves the memory of animations that are only drawn; see
+    /// [`WebpAnimationPlayer::release_cpu_frames`] for what stops working.
+    /// Textures are uploaded at load time even if
+    /// [`lazy_textures`](Self::lazy_textures) is enabled.
+    pub fn gpu_only(mut self, gpu_only: bool) -> Self {
+        self.settings.gpu_only = gpu_only;
+        self
+    }
+
+    /// Sets the clock frame durations are measured against. Defaults to
+    /// [`SystemClock`]; pass a [`ManualClock`](crate::ManualClock) to test
+    /// playback deterministically.
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.settings.clock = Arc::new(clock);
+        self
+    }
+
+    /// Sets the bytes of decoded frames above which they are moved to a
+    /// temporary file, keeping only the frames near the playhead in memory,
+    /// or `None` to keep every frame. Defaults to
+    /// [`AnimationSettings::spill_threshold`].
+    ///
+    /// Ignored with [`gpu_only`](Self::gpu_only); see
+    /// [`WebpAnimationPlayer::spill_to_disk`].
+    #[cfg(feature = "spill-to-disk")]
+    pub fn spill_threshold(mut self, bytes: Option<u64>) -> Self {
+        self.settings.spill_threshold = bytes;
+        self
+    }
+
+    /// Sets the block compression format frame textures are transcoded to.
+    /// Defaults to [`AnimationSettings::texture_compression`].
+    #[cfg(feature = "texture-compression")]
+    pub fn texture_compression(mut self, texture_compression: TextureCompression) -> Self {
+        self.settings.texture_compression = texture_compression;
+        self
+    }
+
+    /// Sets whether the animation is reloaded when the file changes on disk,
+    /// keeping the playhead. Defaults to `false`.
+    ///
+    /// See [`WebpAnimation::watch`].
+    #[cfg(feature = "hot-reload")]
+    pub fn hot_reload(mut self, hot_reload: bool) -> Self {
+        self.hot_reload = hot_reload;
+        self
+    }
+
+    /// Decodes the file and creates the animation.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames, or
+    /// if hot reloading is enabled and the file cannot be watched.
+    pub fn build(self, app: &App) -> Result<WebpAnimation> {
+        let data = self.decode()?;
+        #[cfg(feature = "hot-reload")]
+        if self.hot_reload {
+            let (path, color_mode) = (self.path.clone(), self.color_mode);
+            let mut animation = self.build_from_file_data(data, app);
+            animation.watch_with_color_mode(path, color_mode)?;
+            return Ok(animation);
+        }
+        Ok(self.build_from_file_data(data, app))
+    }
+
+    /// Decodes the file without touching the GPU.
+    ///
+    /// Unlike [`build`](Self::build) this does not need the `App`, so it can run
+    /// on a worker thread. Pass the result to [`build_from_data`](Self::build_from_data)
+    /// on the main thread to apply the playback options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    pub fn decode(&self) -> Result<WebpAnimationData> {
+        WebpAnimationData::from_file_with_color_mode(&self.path, self.color_mode)
+    }
+
+    /// Creates a player for already decoded `data` using this builder's playback
+    /// and texture options. The path and color mode are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: The decoded frames to play.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    pub fn build_from_data(self, data: WebpAnimationData, app: &App) -> WebpAnimationPlayer {
+        WebpAnimationPlayer::with_settings(data, &self.settings, app)
+    }
+
+    /// Like [`build_from_data`](Self::build_from_data) for `data` decoded from
+    /// this builder's file, which the player remembers for
+    /// [`WebpAnimationPlayer::reload`].
+    pub(crate) fn build_from_file_data(
+        self,
+        data: WebpAnimationData,
+        app: &App,
+    ) -> WebpAnimationPlayer {
+        let (path, color_mode) = (self.path.clone(), self.color_mode);
+        let mut animation = self.build_from_data(data, app);
+        animation.set_source(path, color_mode);
+        animation
+    }
+}
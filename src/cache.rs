@@ -0,0 +1,147 @@
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use crate::utils::create_image_from_raw;
+use image::GenericImageView;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Identifies a cache file.
+const MAGIC: &[u8; 8] = b"NWEBPCAC";
+/// Bumped whenever the layout changes; older caches are rejected.
+const VERSION: u8 = 1;
+/// Header flag marking an LZ4-compressed payload.
+const FLAG_LZ4: u8 = 1;
+
+/// Writes `frames` to `path` in the cache format.
+///
+/// The file starts with a header (magic, version, flags, frame count) followed
+/// by one record per frame: width, height and duration in nanoseconds as
+/// little-endian integers, then the raw RGBA pixels. With `compress` the
+/// records are wrapped in an LZ4 frame; callers only pass `true` when the
+/// `cache-compression` feature is enabled.
+pub(crate) fn save<P: AsRef<Path>>(frames: &[WebpFrame], path: P, compress: bool) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    let flags = if compress { FLAG_LZ4 } else { 0 };
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION, flags])?;
+    writer.write_all(&(frames.len() as u32).to_le_bytes())?;
+
+    #[cfg(feature = "cache-compression")]
+    if compress {
+        return write_compressed(frames, writer);
+    }
+
+    write_frames(frames, &mut writer)?;
+    Ok(writer.flush()?)
+}
+
+/// Reads frames written by [`save`] from `path`.
+pub(crate) fn load<P: AsRef<Path>>(path: P) -> Result<Vec<WebpFrame>> {
+    let file = File::open(path)?;
+    let mut reader = BufReader::new(file);
+
+    let mut header = [0u8; 14];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(WebpAnimationError::Cache(
+            "not an animation cache file".to_string(),
+        ));
+    }
+    if header[8] != VERSION {
+        return Err(WebpAnimationError::Cache(format!(
+            "unsupported cache version {}",
+            header[8]
+        )));
+    }
+    let flags = header[9];
+    let count = u32::from_le_bytes([header[10], header[11], header[12], header[13]]) as usize;
+
+    if flags & FLAG_LZ4 != 0 {
+        read_compressed(reader, count)
+    } else {
+        read_frames(&mut reader, count)
+    }
+}
+
+/// Writes the frame records to `writer`.
+fn write_frames<W: Write>(frames: &[WebpFrame], writer: &mut W) -> Result<()> {
+    for frame in frames {
+        let (width, height) = frame.image.dimensions();
+        let mut record = [0u8; 16];
+        record[0..4].copy_from_slice(&width.to_le_bytes());
+        record[4..8].copy_from_slice(&height.to_le_bytes());
+        record[8..16].copy_from_slice(&(frame.duration.as_nanos() as u64).to_le_bytes());
+        writer.write_all(&record)?;
+
+        match frame.image.as_rgba8() {
+            Some(buffer) => writer.write_all(buffer.as_raw()),
+            None => writer.write_all(frame.image.to_rgba8().as_raw()),
+        }?;
+    }
+    Ok(())
+}
+
+/// Reads `count` frame records from `reader`.
+///
+/// The counts and sizes in the file are not trusted for allocations: buffers
+/// grow only as far as data is actually read, so a corrupt or truncated cache
+/// fails with [`WebpAnimationError::Cache`] instead of aborting.
+fn read_frames<R: Read>(reader: &mut R, count: usize) -> Result<Vec<WebpFrame>> {
+    let truncated = || WebpAnimationError::Cache("truncated frame record".to_string());
+    let mut frames = Vec::new();
+    for _ in 0..count {
+        let mut record = [0u8; 16];
+        reader
+            .read_exact(&mut record)
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::UnexpectedEof => truncated(),
+                _ => error.into(),
+            })?;
+        let width = u32::from_le_bytes(record[0..4].try_into().unwrap());
+        let height = u32::from_le_bytes(record[4..8].try_into().unwrap());
+        let nanos = u64::from_le_bytes(record[8..16].try_into().unwrap());
+
+        let len = width as u64 * height as u64 * 4;
+        let mut rgba = Vec::new();
+        reader.by_ref().take(len).read_to_end(&mut rgba)?;
+        if rgba.len() as u64 != len {
+            return Err(truncated());
+        }
+        let image = create_image_from_raw(width, height, rgba).ok_or_else(truncated)?;
+
+        frames.push(WebpFrame {
+            image,
+            duration: Duration::from_nanos(nanos),
+        });
+    }
+    Ok(frames)
+}
+
+/// Writes the frame records to `writer` through an LZ4 frame encoder.
+#[cfg(feature = "cache-compression")]
+fn write_compressed<W: Write>(frames: &[WebpFrame], writer: W) -> Result<()> {
+    let mut encoder = lz4_flex::frame::FrameEncoder::new(writer);
+    write_frames(frames, &mut encoder)?;
+    let mut writer = encoder
+        .finish()
+        .map_err(|e| WebpAnimationError::Cache(e.to_string()))?;
+    Ok(writer.flush()?)
+}
+
+/// Reads `count` LZ4-compressed frame records from `reader`.
+#[cfg(feature = "cache-compression")]
+fn read_compressed<R: Read>(reader: R, count: usize) -> Result<Vec<WebpFrame>> {
+    read_frames(&mut lz4_flex::frame::FrameDecoder::new(reader), count)
+}
+
+/// Compressed caches cannot be read without the LZ4 decoder.
+#[cfg(not(feature = "cache-compression"))]
+fn read_compressed<R: Read>(_reader: R, _count: usize) -> Result<Vec<WebpFrame>> {
+    Err(WebpAnimationError::Cache(
+        "compressed caches require the `cache-compression` feature".to_string(),
+    ))
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// `std::time::Instant` panics on wasm32-unknown-unknown, so the crate takes
+// its `Instant` from here.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// The source of time a player measures frame durations against.
+///
+/// Players use [`SystemClock`] unless another clock is given with
+/// [`WebpAnimationBuilder::clock`](crate::WebpAnimationBuilder::clock) or
+/// [`WebpAnimationPlayer::set_clock`](crate::WebpAnimationPlayer::set_clock).
+/// Inject a [`ManualClock`] to test `update()` deterministically, or implement
+/// the trait to follow an external timeline such as a show controller.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current time. Must never go backwards.
+    fn now(&self) -> Instant;
+}
+
+/// The real monotonic clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to, for deterministic tests.
+///
+/// Clones share the same time, so keep one clone to drive the clock and hand
+/// another to the player:
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use std::time::Duration;
+/// # use nannou_webp_animation::{ManualClock, WebpAnimation};
+/// # fn model(app: &App) -> nannou_webp_animation::Result<()> {
+/// let clock = ManualClock::new();
+/// let mut animation = WebpAnimation::builder("assets/sample.webp")
+///     .clock(clock.clone())
+///     .build(app)?;
+/// clock.advance(animation.current_frame().duration);
+/// animation.update();
+/// assert_eq!(animation.current_frame_index(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    /// The time the clock was created at.
+    start: Instant,
+    /// Time advanced since `start`, shared between clones.
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    /// Creates a clock standing at the current time.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            offset: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves the clock forward by `delta`.
+    pub fn advance(&self, delta: Duration) {
+        *self.offset.lock().unwrap() += delta;
+    }
+
+    /// Returns how far the clock has been advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        *self.offset.lock().unwrap()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::WebpFrame;
+    use crate::playback::Playhead;
+    use image::DynamicImage;
+
+    /// Returns three 1×1 frames shown for 100 ms each.
+    fn frames() -> Vec<WebpFrame> {
+        (0..3)
+            .map(|_| WebpFrame {
+                image: DynamicImage::new_rgba8(1, 1),
+                duration: Duration::from_millis(100),
+            })
+            .collect()
+    }
+
+    /// Returns a playhead timed against `clock`.
+    fn playhead(clock: &ManualClock, looping: bool) -> Playhead {
+        Playhead::new(Arc::new(clock.clone()), looping)
+    }
+
+    #[test]
+    fn looping_playback_wraps_around() {
+        let (clock, frames) = (ManualClock::new(), frames());
+        let mut playhead = playhead(&clock, true);
+        for expected in [1, 2, 0, 1] {
+            clock.advance(Duration::from_millis(100));
+            assert!(playhead.advance(&frames));
+            assert_eq!(playhead.frame_index, expected);
+        }
+        assert_eq!(playhead.loop_count, 1);
+    }
+
+    #[test]
+    fn playback_without_looping_finishes_on_the_last_frame() {
+        let (clock, frames) = (ManualClock::new(), frames());
+        let mut playhead = playhead(&clock, false);
+        for _ in 0..10 {
+            clock.advance(Duration::from_millis(100));
+            playhead.advance(&frames);
+        }
+        assert_eq!(playhead.frame_index, 2);
+        assert_eq!(playhead.loop_count, 0);
+        assert_eq!(playhead.timestamp(&frames), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn speed_change_mid_frame_keeps_the_time_already_played() {
+        let (clock, frames) = (ManualClock::new(), frames());
+        let mut playhead = playhead(&clock, true);
+        clock.advance(Duration::from_millis(50));
+        playhead.set_speed(2.0);
+        assert_eq!(playhead.elapsed(), Duration::from_millis(50));
+
+        // The remaining 50 ms of the frame pass in 25 ms at double speed.
+        clock.advance(Duration::from_millis(24));
+        assert!(!playhead.advance(&frames));
+        clock.advance(Duration::from_millis(1));
+        assert!(playhead.advance(&frames));
+        assert_eq!(playhead.frame_index, 1);
+    }
+
+    #[test]
+    fn manual_clock_clones_share_their_time() {
+        let clock = ManualClock::new();
+        let other = clock.clone();
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(other.elapsed(), Duration::from_secs(1));
+        assert_eq!(other.now(), clock.now());
+    }
+}
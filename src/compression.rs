@@ -0,0 +1,143 @@
+use crate::frame::WebpFrame;
+use intel_tex_2::{bc1, bc7, RgbaSurface};
+use nannou::image::{DynamicImage, GenericImageView};
+use nannou::wgpu::{self, DeviceQueuePair, Texture};
+
+/// Block-compressed texture formats that decoded frames can be transcoded to before upload.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextureCompression {
+    /// Upload frames as uncompressed RGBA8.
+    #[default]
+    None,
+    /// BC7: high quality RGBA at one byte per pixel (4x smaller than RGBA8).
+    Bc7,
+    /// BC1: opaque RGB at half a byte per pixel (8x smaller than RGBA8).
+    Bc1,
+    /// BC1 if every frame is fully opaque, BC7 otherwise.
+    Auto,
+}
+
+impl TextureCompression {
+    /// Picks the texture format for `frames`, or `None` if they should stay uncompressed.
+    ///
+    /// Decided once per animation, so every frame is uploaded in the same format.
+    pub(crate) fn resolve(
+        self,
+        frames: &[WebpFrame],
+        device: &wgpu::Device,
+    ) -> Option<wgpu::TextureFormat> {
+        if !device
+            .features()
+            .contains(wgpu::Features::TEXTURE_COMPRESSION_BC)
+        {
+            return None;
+        }
+
+        match self {
+            TextureCompression::None => None,
+            TextureCompression::Bc7 => Some(wgpu::TextureFormat::Bc7RgbaUnormSrgb),
+            TextureCompression::Bc1 => Some(wgpu::TextureFormat::Bc1RgbaUnormSrgb),
+            TextureCompression::Auto => {
+                // Paged-out or released frames have no pixels to check, so
+                // they may be transparent.
+                let opaque = frames
+                    .iter()
+                    .all(|frame| frame.image.width() > 0 && is_opaque(&frame.image));
+                if opaque {
+                    Some(wgpu::TextureFormat::Bc1RgbaUnormSrgb)
+                } else {
+                    Some(wgpu::TextureFormat::Bc7RgbaUnormSrgb)
+                }
+            }
+        }
+    }
+}
+
+/// Uploads `frame` into a new texture, block-compressed to `format` as picked
+/// by [`TextureCompression::resolve`], or as uncompressed RGBA8 if `None`.
+pub(crate) fn create_texture(
+    device_queue_pair: &DeviceQueuePair,
+    frame: &WebpFrame,
+    format: Option<wgpu::TextureFormat>,
+) -> Texture {
+    match format {
+        Some(format) => create_compressed_texture(device_queue_pair, &frame.image, format),
+        None => Texture::from_image(
+            (device_queue_pair.device(), device_queue_pair.queue()),
+            &frame.image,
+        ),
+    }
+}
+
+/// Compresses `image` to `format` and uploads it into a new texture.
+///
+/// The texture is padded to a multiple of 4 pixels as required by block formats.
+fn create_compressed_texture(
+    device_queue_pair: &DeviceQueuePair,
+    image: &DynamicImage,
+    format: wgpu::TextureFormat,
+) -> Texture {
+    let (data, width, height) = padded_rgba(image);
+    let surface = RgbaSurface {
+        data: &data,
+        width,
+        height,
+        stride: width * 4,
+    };
+    let (blocks, block_size) = match format {
+        wgpu::TextureFormat::Bc1RgbaUnormSrgb => (bc1::compress_blocks(&surface), 8),
+        _ => (
+            bc7::compress_blocks(&bc7::alpha_basic_settings(), &surface),
+            16,
+        ),
+    };
+
+    let texture = wgpu::TextureBuilder::new()
+        .size([width, height])
+        .format(format)
+        .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+        .build(device_queue_pair.device());
+
+    device_queue_pair.queue().write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &blocks,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width / 4 * block_size),
+            rows_per_image: Some(height / 4),
+        },
+        texture.extent(),
+    );
+
+    texture
+}
+
+/// Returns the RGBA8 pixels of `image` padded with transparent pixels to a multiple of 4
+/// in both dimensions, along with the padded width and height.
+fn padded_rgba(image: &DynamicImage) -> (Vec<u8>, u32, u32) {
+    let rgba = image.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let padded_width = width.div_ceil(4) * 4;
+    let padded_height = height.div_ceil(4) * 4;
+
+    if padded_width == width && padded_height == height {
+        return (rgba.into_raw(), width, height);
+    }
+
+    let mut data = vec![0; (padded_width * padded_height * 4) as usize];
+    for (y, row) in rgba.as_raw().chunks_exact((width * 4) as usize).enumerate() {
+        let start = y * (padded_width * 4) as usize;
+        data[start..start + row.len()].copy_from_slice(row);
+    }
+    (data, padded_width, padded_height)
+}
+
+/// Returns `true` if every pixel of `image` is fully opaque.
+fn is_opaque(image: &DynamicImage) -> bool {
+    image.pixels().all(|(_, _, pixel)| pixel[3] == u8::MAX)
+}
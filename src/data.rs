@@ -0,0 +1,584 @@
+#[cfg(feature = "nannou")]
+use crate::animation::WebpAnimationPlayer;
+#[cfg(feature = "nannou")]
+use crate::builder::PlayerSettings;
+use crate::decoder::ColorMode;
+#[cfg(libwebp)]
+use crate::demux::WebpDemuxer;
+#[cfg(libwebp_encode)]
+use crate::encoder::EncodeJob;
+use crate::error::{Result, WebpAnimationError};
+use crate::export;
+use crate::formats::{self, SequenceTiming};
+use crate::frame::WebpFrame;
+use crate::settings::AnimationSettings;
+use crate::{asset_cache, cache};
+use image::{DynamicImage, Frame, Frames, GenericImageView, ImageFormat};
+#[cfg(feature = "nannou")]
+use nannou::App;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Memory held by a single animation, as returned by `memory_usage()`.
+///
+/// Frames and textures shared with other players (clones, or loads through
+/// the [`AnimationCache`](crate::AnimationCache)) are counted in full by each
+/// of them; [`AnimationManager::memory_stats`](crate::AnimationManager::memory_stats)
+/// counts them once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Bytes of decoded pixels and working buffers held on the CPU.
+    pub cpu_bytes: u64,
+    /// Bytes of the textures uploaded so far, counted from their actual format,
+    /// e.g. by block size for block-compressed textures.
+    pub gpu_bytes: u64,
+    /// Number of frames in the animation.
+    pub frame_count: usize,
+}
+
+/// The decoded frames of an animation, independent of any GPU resources.
+///
+/// Decoding does not need the nannou `App`, so `WebpAnimationData` can be
+/// created on any thread and sent to the main thread, where
+/// [`into_player`](Self::into_player) uploads the textures for playback.
+///
+/// The frames are reference-counted, so cloning is cheap and the clones share
+/// their pixels. Use this to back several players with the same frames.
+///
+/// The type is `Send + Sync`, like every CPU-side type of the crate, so it
+/// can be stored in shared app state such as an `Arc<Mutex<_>>`.
+#[derive(Clone, PartialEq)]
+pub struct WebpAnimationData {
+    /// Collection of frames in the animation. Never empty.
+    pub(crate) frames: Arc<Vec<WebpFrame>>,
+}
+
+impl WebpAnimationData {
+    /// Decodes the WebP file at `path` into RGBA frames.
+    ///
+    /// With the `gif`, `apng` and `avif` features, animated GIFs, PNGs and AVIFs
+    /// are decoded as well. The format is detected from the file contents, not the extension.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_file_with_color_mode(path, ColorMode::Rgba)
+    }
+
+    /// Decodes the WebP file at `path` into frames with the given pixel layout.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `color_mode`: The pixel layout the frames are decoded to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    pub fn from_file_with_color_mode<P: AsRef<Path>>(
+        path: P,
+        color_mode: ColorMode,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let decode = || Self::from_frames(formats::decode_file(path, color_mode)?);
+        if AnimationSettings::global().cache_decoded {
+            asset_cache::get_or_decode(path, color_mode, decode)
+        } else {
+            decode()
+        }
+    }
+
+    /// Decodes only the first frame of the file at `path` as a one-frame
+    /// animation, e.g. to show a placeholder while the rest is loading.
+    ///
+    /// WebP files are read with [`WebpDemuxer`](crate::WebpDemuxer), so this
+    /// takes about as long as decoding a still image. Other formats, and every
+    /// format without libwebp, are decoded in full and trimmed.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the file containing the animation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    pub fn from_file_first_frame<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match decode_first_frame(path.as_ref()) {
+            Some(frame) => Self::from_frames(vec![frame]),
+            None => Self::from_frames(vec![Self::from_file(path)?.frames[0].clone()]),
+        }
+    }
+
+    /// Decodes an animation held in memory, e.g. embedded with `include_bytes!`
+    /// or downloaded by the caller.
+    ///
+    /// The format is detected from the leading bytes like in [`from_file`](Self::from_file).
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: The encoded animation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded or contains no frames.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_frames(formats::decode_bytes(data, ColorMode::Rgba)?)
+    }
+
+    /// Loads a directory of numbered PNG or JPEG frames, e.g. a frame sequence
+    /// exported from a design tool.
+    ///
+    /// Files are ordered by the number at the end of their name, so
+    /// `frame_2.png` comes before `frame_10.png`. Other files are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `dir`: The directory containing the frames.
+    /// - `timing`: The frame rate or the duration of every frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory contains no frames, a frame cannot be
+    /// read or differs in size from the first, or the timing does not match the
+    /// number of frames.
+    pub fn from_image_sequence<P: AsRef<Path>>(dir: P, timing: &SequenceTiming) -> Result<Self> {
+        let frames = formats::decode_sequence(dir, timing)?;
+        Self::from_frames(frames)
+    }
+
+    /// Wraps frames created in code, e.g. procedurally generated images.
+    ///
+    /// # Parameters
+    ///
+    /// - `frames`: The frames of the animation, in playback order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `frames` is empty.
+    pub fn from_frames(frames: Vec<WebpFrame>) -> Result<Self> {
+        if frames.is_empty() {
+            return Err(WebpAnimationError::NoFrames);
+        }
+
+        Ok(Self {
+            frames: Arc::new(frames),
+        })
+    }
+
+    /// Collects the frames of an `image` animation decoder, e.g.
+    /// `GifDecoder::into_frames`, so image-rs pipelines can feed the player.
+    ///
+    /// # Parameters
+    ///
+    /// - `frames`: The frames to collect, with their delays.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding a frame fails or there are no frames.
+    pub fn from_image_frames(frames: Frames<'_>) -> Result<Self> {
+        let frames = frames
+            .map(|frame| Ok(WebpFrame::from(frame?)))
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_frames(frames)
+    }
+
+    /// Returns the frames as `image` crate frames with matching delays, e.g. to
+    /// pass them to `image`'s GIF encoder.
+    pub fn image_frames(&self) -> Frames<'_> {
+        Frames::new(Box::new(
+            self.frames.iter().map(|frame| Ok(Frame::from(frame))),
+        ))
+    }
+
+    /// Uploads the frames to the GPU and creates a player with default options.
+    ///
+    /// Use [`WebpAnimationBuilder::build_from_data`](crate::WebpAnimationBuilder::build_from_data)
+    /// to configure the player instead.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    #[cfg(feature = "nannou")]
+    pub fn into_player(self, app: &App) -> WebpAnimationPlayer {
+        WebpAnimationPlayer::with_settings(self, &PlayerSettings::default(), app)
+    }
+
+    /// Returns all frames in playback order.
+    pub fn frames(&self) -> &[WebpFrame] {
+        &self.frames
+    }
+
+    /// Returns the frame at `index`, or `None` if it is out of range.
+    pub fn frame_at(&self, index: usize) -> Option<&WebpFrame> {
+        self.frames.get(index)
+    }
+
+    /// Replaces the image of every frame with the result of `f`, keeping the durations.
+    ///
+    /// Frames shared with clones of this data are copied first, so the clones are
+    /// not affected.
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: Maps a frame's image to its replacement.
+    pub fn map_frames<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&DynamicImage) -> DynamicImage,
+    {
+        for frame in Arc::make_mut(&mut self.frames) {
+            frame.image = f(&frame.image);
+        }
+    }
+
+    /// Keeps only the frames for which `f` returns `true`, e.g. `|i, _| i % 2 == 0`
+    /// to drop every other frame.
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: Called with the index and the frame; returns whether to keep it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the frames unchanged, if no frame would remain.
+    pub fn retain_frames<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(usize, &WebpFrame) -> bool,
+    {
+        let frames: Vec<WebpFrame> = self
+            .frames
+            .iter()
+            .enumerate()
+            .filter(|(i, frame)| f(*i, frame))
+            .map(|(_, frame)| frame.clone())
+            .collect();
+        self.set_frames(frames)
+    }
+
+    /// Replaces all frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the frames unchanged, if `frames` is empty.
+    pub fn set_frames(&mut self, frames: Vec<WebpFrame>) -> Result<()> {
+        *self = Self::from_frames(frames)?;
+        Ok(())
+    }
+
+    /// Reverses the order of the frames, so the animation plays backwards.
+    pub fn reverse(&mut self) {
+        Arc::make_mut(&mut self.frames).reverse();
+    }
+
+    /// Appends copies of the frames of `other` after the frames of this animation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the frames unchanged, if the canvas size of
+    /// `other` differs from this animation's.
+    pub fn append(&mut self, other: &WebpAnimationData) -> Result<()> {
+        if (other.width(), other.height()) != (self.width(), self.height()) {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "canvas size {}x{} does not match canvas size {}x{}",
+                other.width(),
+                other.height(),
+                self.width(),
+                self.height()
+            )));
+        }
+        Arc::make_mut(&mut self.frames).extend_from_slice(&other.frames);
+        Ok(())
+    }
+
+    /// Splits the animation into the frames before `index` and the frames from
+    /// `index` onwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either half would be empty, i.e. if `index` is `0` or
+    /// not less than the frame count.
+    pub fn split_at(&self, index: usize) -> Result<(Self, Self)> {
+        if index == 0 || index >= self.frames.len() {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "split index {} out of range for {} frames",
+                index,
+                self.frames.len()
+            )));
+        }
+        let (head, tail) = self.frames.split_at(index);
+        Ok((
+            Self::from_frames(head.to_vec())?,
+            Self::from_frames(tail.to_vec())?,
+        ))
+    }
+
+    /// Iterates over the frames together with the time each frame starts,
+    /// measured from the beginning of the animation.
+    ///
+    /// The first frame starts at `Duration::ZERO` and every following timestamp is
+    /// the sum of the durations of the frames before it.
+    pub fn iter_timed(&self) -> impl Iterator<Item = (Duration, &WebpFrame)> + '_ {
+        self.frames.iter().scan(Duration::ZERO, |timestamp, frame| {
+            let start = *timestamp;
+            *timestamp += frame.duration;
+            Some((start, frame))
+        })
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the memory held by the decoded frames; nothing is on the GPU
+    /// until the data is turned into a player.
+    pub fn memory_usage(&self) -> MemoryReport {
+        MemoryReport {
+            cpu_bytes: self
+                .frames
+                .iter()
+                .map(|frame| frame.image.as_bytes().len() as u64)
+                .sum(),
+            gpu_bytes: 0,
+            frame_count: self.frames.len(),
+        }
+    }
+
+    /// Returns the width (in pixels) of the animation canvas.
+    pub fn width(&self) -> u32 {
+        self.frames[0].image.width()
+    }
+
+    /// Returns the height (in pixels) of the animation canvas.
+    pub fn height(&self) -> u32 {
+        self.frames[0].image.height()
+    }
+
+    /// Returns the total duration of one playthrough.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    /// Returns the average frame rate over one playthrough.
+    ///
+    /// # Returns
+    ///
+    /// The number of frames per second, or `0.0` if every frame has a zero duration.
+    pub fn average_fps(&self) -> f64 {
+        let seconds = self.duration().as_secs_f64();
+        if seconds > 0.0 {
+            self.frames.len() as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// Writes the decoded frames to `path` in a binary cache format that loads
+    /// much faster than decoding the WebP file again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save_cache<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        cache::save(&self.frames, path, false)
+    }
+
+    /// Writes the decoded frames to `path` as an LZ4-compressed cache, trading a
+    /// little load time for a much smaller file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    #[cfg(feature = "cache-compression")]
+    pub fn save_cache_compressed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        cache::save(&self.frames, path, true)
+    }
+
+    /// Loads frames written by [`save_cache`](Self::save_cache) or
+    /// `save_cache_compressed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a cache of a supported
+    /// version, is compressed while the `cache-compression` feature is disabled,
+    /// or contains no frames.
+    pub fn load_cache<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_frames(cache::load(path)?)
+    }
+
+    /// Writes every frame as a numbered image file plus a `manifest.json` with the
+    /// frame durations, for post-processing in other tools.
+    ///
+    /// See [`export::export_frames`] for the naming scheme and manifest layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or any file cannot be written.
+    pub fn export_frames<P: AsRef<Path>>(&self, dir: P, format: ImageFormat) -> Result<()> {
+        export::export_frames(&self.frames, dir, format)
+    }
+
+    /// Packs every frame into a sprite-sheet PNG at `path` plus a JSON manifest
+    /// with the frame rectangles and durations.
+    ///
+    /// See [`export::export_sprite_sheet`] for the layout and manifest format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be written.
+    pub fn export_sprite_sheet<P: AsRef<Path>>(&self, path: P, columns: Option<u32>) -> Result<()> {
+        export::export_sprite_sheet(&self.frames, path, columns)
+    }
+
+    /// Writes the animation to `path` as an animated GIF.
+    ///
+    /// See [`export::export_gif`] for details on quantization and timing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the canvas is too large for GIF or the file cannot be written.
+    #[cfg(feature = "gif")]
+    pub fn export_gif<P: AsRef<Path>>(&self, path: P, speed: i32) -> Result<()> {
+        export::export_gif(&self.frames, path, speed)
+    }
+
+    /// Writes the animation to `path` as an animated PNG (APNG).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    #[cfg(feature = "apng")]
+    pub fn export_apng<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        export::export_apng(&self.frames, path)
+    }
+
+    /// Writes the animation to `path` as an MP4 or WebM video using `ffmpeg`.
+    ///
+    /// See [`export::export_video`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ffmpeg` cannot be started or fails.
+    #[cfg(feature = "ffmpeg")]
+    pub fn export_video<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: export::VideoFormat,
+        fps: f64,
+    ) -> Result<()> {
+        export::export_video(&self.frames, path, format, fps)
+    }
+
+    /// Creates an [`EncodeJob`] for the whole animation, e.g. to concatenate it
+    /// with other animations via [`EncodeJob::concat`].
+    #[cfg(libwebp_encode)]
+    pub fn encode_job(&self) -> EncodeJob<'_> {
+        EncodeJob::new(&self.frames)
+    }
+
+    /// Creates an [`EncodeJob`] for the frames in `start..end`, e.g. to write a
+    /// sub-clip to a new animated WebP file.
+    ///
+    /// Out-of-range indices are clamped to the available frames.
+    ///
+    /// # Parameters
+    ///
+    /// - `start`: Index of the first frame to include.
+    /// - `end`: Index one past the last frame to include.
+    #[cfg(libwebp_encode)]
+    pub fn trim(&self, start: usize, end: usize) -> EncodeJob<'_> {
+        let end = end.min(self.frames.len());
+        let start = start.min(end);
+        EncodeJob::new(&self.frames[start..end])
+    }
+
+    /// Creates an [`EncodeJob`] for the frames starting within the time range
+    /// `start..end`, measured from the beginning of the animation.
+    ///
+    /// # Parameters
+    ///
+    /// - `start`: Start of the time range.
+    /// - `end`: End of the time range (exclusive).
+    #[cfg(libwebp_encode)]
+    pub fn trim_time(&self, start: Duration, end: Duration) -> EncodeJob<'_> {
+        let mut first = self.frames.len();
+        let mut last = self.frames.len();
+        for (i, (timestamp, _)) in self.iter_timed().enumerate() {
+            if timestamp >= start && first == self.frames.len() {
+                first = i;
+            }
+            if timestamp >= end {
+                last = i;
+                break;
+            }
+        }
+        self.trim(first, last)
+    }
+}
+
+/// Decodes the first frame of a WebP file without decoding the others, or
+/// returns `None` if the file cannot be demuxed.
+#[cfg(libwebp)]
+pub(crate) fn decode_first_frame(path: &Path) -> Option<WebpFrame> {
+    WebpDemuxer::from_file(path)
+        .and_then(|demuxer| demuxer.decode_frame(0))
+        .ok()
+}
+
+/// Without libwebp there is no demuxer to decode a single frame with.
+#[cfg(not(libwebp))]
+pub(crate) fn decode_first_frame(_path: &Path) -> Option<WebpFrame> {
+    None
+}
+
+impl fmt::Debug for WebpAnimationData {
+    /// Summarizes the animation instead of dumping every frame.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebpAnimationData")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("frame_count", &self.frame_count())
+            .field("duration", &self.duration())
+            .finish()
+    }
+}
+
+impl fmt::Display for WebpAnimationData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} animation, {} frames, {} ms",
+            self.width(),
+            self.height(),
+            self.frame_count(),
+            self.duration().as_millis()
+        )
+    }
+}
+
+/// Serialized as the sequence of frames.
+#[cfg(feature = "serde")]
+impl serde::Serialize for WebpAnimationData {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(self.frames.as_slice(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WebpAnimationData {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let frames: Vec<WebpFrame> = serde::Deserialize::deserialize(deserializer)?;
+        Self::from_frames(frames).map_err(serde::de::Error::custom)
+    }
+}
@@ -1,29 +1,93 @@
+use crate::error::{Result, WebpAnimationError};
 use crate::frame::WebpFrame;
+use crate::loader;
+#[cfg(all(libwebp_0_5, not(feature = "pure-rust")))]
+use crate::settings::AnimationSettings;
 use crate::utils::create_image_from_raw;
 
+use image::{Bgra, DynamicImage, ImageBuffer, RgbaImage};
+#[cfg(feature = "pure-rust")]
+use image::RgbImage;
+
 use std::fs::File;
 use std::io::Read;
+#[cfg(feature = "pure-rust")]
+use std::io::Cursor;
 use std::path::Path;
 use std::time::Duration;
 
+#[cfg(all(libwebp_0_5, not(feature = "pure-rust")))]
 use std::slice;
+#[cfg(all(libwebp_0_5, not(feature = "pure-rust")))]
 use libc::c_int;
 
+#[cfg(all(libwebp_0_5, not(feature = "pure-rust")))]
+use crate::bindings::*;
+// Shadow the linked decoder functions with the runtime-loaded ones.
+#[cfg(all(feature = "dynamic-loading", not(feature = "pure-rust")))]
+use crate::dynload::{
+    self, WebPAnimDecoderDelete, WebPAnimDecoderGetInfo, WebPAnimDecoderGetNext,
+    WebPAnimDecoderHasMoreFrames, WebPAnimDecoderNewInternal, WebPAnimDecoderOptionsInitInternal,
+};
+
 //---------------------------------------------------------------------
-// Bindgen‑generated FFI layer
+// Color modes
 //---------------------------------------------------------------------
 
-#[allow(
-    dead_code,
-    non_snake_case,
-    non_camel_case_types,
-    non_upper_case_globals,
-    improper_ctypes
-)]
-mod bindings {
-    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+/// Pixel layout requested from libwebp’s animated decoder.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorMode {
+    /// Straight (non-premultiplied) RGBA.
+    #[default]
+    Rgba,
+    /// Straight (non-premultiplied) BGRA.
+    Bgra,
+    /// RGBA with premultiplied alpha.
+    PremultipliedRgba,
+    /// BGRA with premultiplied alpha.
+    PremultipliedBgra,
+}
+
+impl ColorMode {
+    /// The matching libwebp colorspace constant.
+    #[cfg(all(libwebp_0_5, not(feature = "pure-rust")))]
+    fn csp_mode(self) -> WEBP_CSP_MODE {
+        match self {
+            ColorMode::Rgba => WEBP_CSP_MODE_MODE_RGBA,
+            ColorMode::Bgra => WEBP_CSP_MODE_MODE_BGRA,
+            ColorMode::PremultipliedRgba => WEBP_CSP_MODE_MODE_rgbA,
+            ColorMode::PremultipliedBgra => WEBP_CSP_MODE_MODE_bgrA,
+        }
+    }
+
+    /// Convert a straight RGBA image decoded from another format to this layout.
+    pub(crate) fn convert_rgba(self, image: RgbaImage) -> Option<DynamicImage> {
+        let (w, h) = image.dimensions();
+        let mut raw = image.into_raw();
+        for px in raw.chunks_exact_mut(4) {
+            if matches!(self, ColorMode::PremultipliedRgba | ColorMode::PremultipliedBgra) {
+                let alpha = px[3] as u16;
+                for channel in &mut px[..3] {
+                    *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+                }
+            }
+            if matches!(self, ColorMode::Bgra | ColorMode::PremultipliedBgra) {
+                px.swap(0, 2);
+            }
+        }
+        self.create_image(w, h, raw)
+    }
+
+    /// Wrap a raw canvas of this layout in a `DynamicImage`.
+    fn create_image(self, w: u32, h: u32, raw: Vec<u8>) -> Option<DynamicImage> {
+        match self {
+            ColorMode::Rgba | ColorMode::PremultipliedRgba => create_image_from_raw(w, h, raw),
+            ColorMode::Bgra | ColorMode::PremultipliedBgra => {
+                ImageBuffer::<Bgra<u8>, _>::from_raw(w, h, raw).map(DynamicImage::ImageBgra8)
+            }
+        }
+    }
 }
-use bindings::*;
 
 //---------------------------------------------------------------------
 // Public decoder
@@ -39,10 +103,11 @@ use bindings::*;
 /// 1.  Reading the file into memory.
 /// 2.  Calling the C API.
 /// 3.  Copying the returned raw RGBA canvas into a `Vec<u8>`.
-/// 4.  Wrapping the bytes in `nannou::image::DynamicImage`.
+/// 4.  Wrapping the bytes in `image::DynamicImage`.
 ///
 /// All memory management stays on the Rust side except for the lifetime of the
 /// decoder object itself, which is freed immediately after use.
+#[derive(Debug)]
 pub struct WebpDecoder;
 
 impl WebpDecoder {
@@ -51,14 +116,60 @@ impl WebpDecoder {
     /// # Errors
     /// * I/O failures (file not found, no read permission, …)
     /// * Corrupted / unsupported WebP payloads
-    pub fn decode<P: AsRef<Path>>(path: P) -> Result<Vec<WebpFrame>, String> {
+    pub fn decode<P: AsRef<Path>>(path: P) -> Result<Vec<WebpFrame>> {
+        Self::decode_with_color_mode(path, ColorMode::Rgba)
+    }
+
+    /// Decode `path` into frames with the requested pixel layout.
+    ///
+    /// # Errors
+    /// * I/O failures (file not found, no read permission, …)
+    /// * Corrupted / unsupported WebP payloads
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(path), fields(path = %path.as_ref().display()))
+    )]
+    pub fn decode_with_color_mode<P: AsRef<Path>>(
+        path: P,
+        color_mode: ColorMode,
+    ) -> Result<Vec<WebpFrame>> {
         //-----------------------------------------------------------------
         // 1. Read file into a Vec<u8>
         //-----------------------------------------------------------------
+        #[cfg(feature = "tracing")]
+        let read_span = tracing::debug_span!("read_file").entered();
         let mut data = Vec::new();
-        File::open(path)
-            .and_then(|mut f| f.read_to_end(&mut data))
-            .map_err(|e| e.to_string())?;
+        File::open(path).and_then(|mut f| f.read_to_end(&mut data))?;
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!(bytes = data.len(), "read file");
+            drop(read_span);
+        }
+
+        Self::decode_bytes(&data, color_mode)
+    }
+
+    /// Returns whether animated WebP can be decoded.
+    ///
+    /// Always `true` unless the linked libwebp predates the animated decoder
+    /// (0.5), or the `dynamic-loading` feature is enabled and libwebpdemux
+    /// could not be loaded. In both cases only the first frame of lossy WebP
+    /// files is decoded.
+    pub fn supports_animation() -> bool {
+        #[cfg(all(feature = "dynamic-loading", not(feature = "pure-rust")))]
+        if dynload::load().is_err() {
+            return false;
+        }
+        cfg!(any(libwebp_0_5, feature = "pure-rust"))
+    }
+
+    /// Decode an in‑memory WebP with libwebp’s animated decoder.
+    #[cfg(all(libwebp_0_5, not(feature = "pure-rust")))]
+    pub(crate) fn decode_bytes(data: &[u8], color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+        #[cfg(feature = "dynamic-loading")]
+        if let Err(reason) = dynload::load() {
+            return Self::decode_still(data, color_mode, &reason);
+        }
 
         // libwebp keeps *pointers* into this buffer, so it must stay alive for
         // the decoder’s lifetime.
@@ -78,10 +189,10 @@ impl WebpDecoder {
             )
         };
         if ok == 0 {
-            return Err("WebPAnimDecoderOptionsInitInternal failed".into());
+            return Err(WebpAnimationError::Decode("WebPAnimDecoderOptionsInitInternal failed".into()));
         }
-        dec_opts.color_mode = WEBP_CSP_MODE_MODE_RGBA; // RGBA output
-        dec_opts.use_threads = 1;
+        dec_opts.color_mode = color_mode.csp_mode();
+        dec_opts.use_threads = AnimationSettings::global().decode_threads as c_int;
 
         //-----------------------------------------------------------------
         // 3. Create decoder
@@ -94,7 +205,7 @@ impl WebpDecoder {
             )
         };
         if dec.is_null() {
-            return Err("WebPAnimDecoderNewInternal failed".into());
+            return Err(WebpAnimationError::Decode("WebPAnimDecoderNewInternal failed".into()));
         }
 
         //-----------------------------------------------------------------
@@ -104,13 +215,17 @@ impl WebpDecoder {
         let ok = unsafe { WebPAnimDecoderGetInfo(dec, &mut info) };
         if ok == 0 {
             unsafe { WebPAnimDecoderDelete(dec) };
-            return Err("WebPAnimDecoderGetInfo failed".into());
+            return Err(WebpAnimationError::Decode("WebPAnimDecoderGetInfo failed".into()));
         }
         let (w, h) = (info.canvas_width, info.canvas_height);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(width = w, height = h, frame_count = info.frame_count, "parsed animation info");
 
         //-----------------------------------------------------------------
         // 5. Decode all frames
         //-----------------------------------------------------------------
+        #[cfg(feature = "tracing")]
+        let decode_span = tracing::debug_span!("decode_frames").entered();
         let mut rgba_ptr: *mut u8 = std::ptr::null_mut();
         let mut timestamp_ms: c_int = 0;
 
@@ -122,7 +237,7 @@ impl WebpDecoder {
                 unsafe { WebPAnimDecoderGetNext(dec, &mut rgba_ptr, &mut timestamp_ms) };
             if ok == 0 {
                 unsafe { WebPAnimDecoderDelete(dec) };
-                return Err("WebPAnimDecoderGetNext failed".into());
+                return Err(WebpAnimationError::Decode("WebPAnimDecoderGetNext failed".into()));
             }
 
             // Copy the RGBA canvas into Rust‑owned memory
@@ -131,17 +246,26 @@ impl WebpDecoder {
             };
             raws.push(slice.to_vec());
             timestamps.push(timestamp_ms);
+            let total = Some(info.frame_count as usize);
+            if let Err(error) = loader::frame_decoded(raws.len(), total) {
+                unsafe { WebPAnimDecoderDelete(dec) };
+                return Err(error);
+            }
         }
 
         unsafe { WebPAnimDecoderDelete(dec) };
+        #[cfg(feature = "tracing")]
+        drop(decode_span);
 
         if raws.is_empty() {
-            return Err("No frames decoded".into());
+            return Err(WebpAnimationError::NoFrames);
         }
 
         //-----------------------------------------------------------------
         // 6. Convert raw bytes → DynamicImage → WebpFrame
         //-----------------------------------------------------------------
+        #[cfg(feature = "tracing")]
+        let _convert_span = tracing::debug_span!("convert_frames", frames = raws.len()).entered();
         let mut frames = Vec::with_capacity(raws.len());
         for i in 0..raws.len() {
             // libwebp gives us “display_timestamp”; frame duration is the
@@ -155,8 +279,9 @@ impl WebpDecoder {
                 100
             };
 
-            let img = create_image_from_raw(w, h, raws[i].clone())
-                .ok_or("Failed to create image from RGBA buffer")?;
+            let img = color_mode
+                .create_image(w, h, raws[i].clone())
+                .ok_or_else(|| WebpAnimationError::Decode("Failed to create image from RGBA buffer".into()))?;
 
             frames.push(WebpFrame {
                 image: img,
@@ -166,4 +291,95 @@ impl WebpDecoder {
 
         Ok(frames)
     }
+
+    /// Decode the first frame only, as libwebp older than 0.5 has no animated
+    /// decoder.
+    #[cfg(all(not(libwebp_0_5), not(feature = "pure-rust")))]
+    pub(crate) fn decode_bytes(data: &[u8], color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+        Self::decode_still(data, color_mode, "libwebp is older than 0.5")
+    }
+
+    /// Decode a still lossy WebP with the `image` crate when libwebp’s animated
+    /// decoder is unavailable.
+    #[cfg(all(
+        any(feature = "dynamic-loading", not(libwebp_0_5)),
+        not(feature = "pure-rust")
+    ))]
+    fn decode_still(data: &[u8], color_mode: ColorMode, reason: &str) -> Result<Vec<WebpFrame>> {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(reason, "animated decoder unavailable, decoding a still image");
+        let image = image::load_from_memory_with_format(data, image::ImageFormat::WebP)
+            .map_err(|e| {
+                WebpAnimationError::Decode(format!("{} (animated decoder unavailable: {})", e, reason))
+            })?;
+        let image = color_mode.convert_rgba(image.to_rgba8()).ok_or_else(|| {
+            WebpAnimationError::Decode("Failed to create image from RGBA buffer".into())
+        })?;
+        Ok(vec![WebpFrame {
+            image,
+            duration: Duration::from_millis(100),
+        }])
+    }
+
+    /// Decode an in‑memory WebP with the pure‑Rust `image-webp` decoder.
+    ///
+    /// `image-webp` composites animation frames onto the canvas itself, so
+    /// every frame it returns is already a full RGB(A) canvas.
+    #[cfg(feature = "pure-rust")]
+    pub(crate) fn decode_bytes(data: &[u8], color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+        let decode_error = |e: image_webp::DecodingError| WebpAnimationError::Decode(e.to_string());
+
+        let mut decoder = image_webp::WebPDecoder::new(Cursor::new(data)).map_err(decode_error)?;
+        let (w, h) = decoder.dimensions();
+        let has_alpha = decoder.has_alpha();
+        let size = decoder
+            .output_buffer_size()
+            .ok_or_else(|| WebpAnimationError::Decode("Canvas is too large".into()))?;
+        let mut buffer = vec![0u8; size];
+
+        let mut frames = Vec::new();
+        if decoder.is_animated() {
+            let total = decoder.num_frames() as usize;
+            for _ in 0..total {
+                let dur_ms = decoder.read_frame(&mut buffer).map_err(decode_error)?;
+                let image = Self::canvas_image(w, h, &buffer, has_alpha, color_mode)?;
+                frames.push(WebpFrame {
+                    image,
+                    duration: Duration::from_millis(dur_ms as u64),
+                });
+                loader::frame_decoded(frames.len(), Some(total))?;
+            }
+        } else {
+            // A still image becomes a single frame with the default duration.
+            decoder.read_image(&mut buffer).map_err(decode_error)?;
+            let image = Self::canvas_image(w, h, &buffer, has_alpha, color_mode)?;
+            frames.push(WebpFrame {
+                image,
+                duration: Duration::from_millis(100),
+            });
+        }
+
+        if frames.is_empty() {
+            return Err(WebpAnimationError::NoFrames);
+        }
+        Ok(frames)
+    }
+
+    /// Wrap a canvas returned by `image-webp` (RGBA, or RGB without alpha).
+    #[cfg(feature = "pure-rust")]
+    fn canvas_image(
+        w: u32,
+        h: u32,
+        buffer: &[u8],
+        has_alpha: bool,
+        color_mode: ColorMode,
+    ) -> Result<DynamicImage> {
+        let rgba = if has_alpha {
+            RgbaImage::from_raw(w, h, buffer.to_vec())
+        } else {
+            RgbImage::from_raw(w, h, buffer.to_vec()).map(|rgb| DynamicImage::ImageRgb8(rgb).to_rgba8())
+        };
+        rgba.and_then(|rgba| color_mode.convert_rgba(rgba))
+            .ok_or_else(|| WebpAnimationError::Decode("Failed to create image from RGBA buffer".into()))
+    }
 }
@@ -0,0 +1,389 @@
+use crate::bindings::*;
+use crate::decoder::{ColorMode, WebpDecoder};
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use crate::loader;
+
+use image::RgbaImage;
+use std::fs;
+use std::path::Path;
+use std::slice;
+use std::time::Duration;
+
+use libc::{c_int, c_void};
+
+//---------------------------------------------------------------------
+// Frame headers
+//---------------------------------------------------------------------
+
+/// Placement and compositing information of one frame, read from its ANMF
+/// chunk without decoding any pixels.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FrameHeader {
+    /// Left edge of the frame on the canvas.
+    pub(crate) x: u32,
+    /// Top edge of the frame on the canvas.
+    pub(crate) y: u32,
+    /// Width of the frame rectangle.
+    pub(crate) width: u32,
+    /// Height of the frame rectangle.
+    pub(crate) height: u32,
+    /// Display duration.
+    pub(crate) duration: Duration,
+    /// Whether the frame rectangle is cleared after the frame was shown.
+    pub(crate) dispose_to_background: bool,
+    /// Whether the frame is alpha-blended onto the canvas instead of replacing it.
+    pub(crate) blend: bool,
+    /// Whether the frame can be rendered without any of the frames before it.
+    pub(crate) key_frame: bool,
+}
+
+//---------------------------------------------------------------------
+// Public demuxer
+//---------------------------------------------------------------------
+
+/// Random access to the frames of an animated WebP built on libwebp’s
+/// `WebPDemux`.
+///
+/// [`WebpDecoder`] decodes every frame up front. The demuxer only reads the
+/// frame headers on open; [`decode_frame`](Self::decode_frame) then starts
+/// at the nearest key frame — a frame that does not depend on the ones
+/// before it — and composites forward to the requested frame. Seeking deep
+/// into a long animation therefore decodes a handful of frames instead of
+/// all preceding ones.
+pub struct WebpDemuxer {
+    /// The file contents. libwebp keeps pointers into it, so it must outlive
+    /// `demux` and is never modified.
+    _data: Vec<u8>,
+    /// Owned libwebp demuxer, freed on drop.
+    demux: *mut WebPDemuxer,
+    /// Width of the animation canvas.
+    width: u32,
+    /// Height of the animation canvas.
+    height: u32,
+    /// Headers of all frames in playback order.
+    frames: Vec<FrameHeader>,
+}
+
+impl WebpDemuxer {
+    /// Open the WebP at `path` for random access.
+    ///
+    /// # Errors
+    /// * I/O failures (file not found, no read permission, …)
+    /// * Corrupted / unsupported WebP payloads
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    /// Parse a WebP held in memory, taking ownership of the bytes.
+    ///
+    /// # Errors
+    /// * Corrupted / unsupported WebP payloads
+    /// * Files without frames
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let webp_data = WebPData {
+            bytes: data.as_ptr(),
+            size: data.len(),
+        };
+        let demux = unsafe {
+            WebPDemuxInternal(
+                &webp_data,
+                0,
+                std::ptr::null_mut(),
+                WEBP_DEMUX_ABI_VERSION as c_int,
+            )
+        };
+        if demux.is_null() {
+            return Err(WebpAnimationError::Decode(
+                "WebPDemuxInternal failed".into(),
+            ));
+        }
+
+        let width = unsafe { WebPDemuxGetI(demux, WebPFormatFeature_WEBP_FF_CANVAS_WIDTH) };
+        let height = unsafe { WebPDemuxGetI(demux, WebPFormatFeature_WEBP_FF_CANVAS_HEIGHT) };
+        let mut demuxer = Self {
+            _data: data,
+            demux,
+            width,
+            height,
+            frames: Vec::new(),
+        };
+        demuxer.read_headers()?;
+        Ok(demuxer)
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the width (in pixels) of the animation canvas.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height (in pixels) of the animation canvas.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the display duration of the frame at `index`, or `None` if it is
+    /// out of range.
+    pub fn frame_duration(&self, index: usize) -> Option<Duration> {
+        self.frames.get(index).map(|frame| frame.duration)
+    }
+
+    /// Returns the index of the nearest key frame at or before `index`, i.e.
+    /// where decoding starts for [`decode_frame`](Self::decode_frame).
+    pub fn key_frame_before(&self, index: usize) -> usize {
+        let index = index.min(self.frames.len() - 1);
+        (0..=index)
+            .rev()
+            .find(|&i| self.frames[i].key_frame)
+            .unwrap_or(0)
+    }
+
+    /// Decode the full canvas of the frame at `index` as straight RGBA.
+    ///
+    /// # Errors
+    /// * `index` out of range
+    /// * Corrupted frame bitstreams
+    pub fn decode_frame(&self, index: usize) -> Result<WebpFrame> {
+        self.decode_frame_with_color_mode(index, ColorMode::Rgba)
+    }
+
+    /// Decode the full canvas of the frame at `index` with the requested
+    /// pixel layout.
+    ///
+    /// # Errors
+    /// * `index` out of range
+    /// * Corrupted frame bitstreams
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub fn decode_frame_with_color_mode(
+        &self,
+        index: usize,
+        color_mode: ColorMode,
+    ) -> Result<WebpFrame> {
+        if index >= self.frames.len() {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "frame index {} out of range for {} frames",
+                index,
+                self.frames.len()
+            )));
+        }
+        #[cfg(feature = "tracing")]
+        {
+            let start = self.key_frame_before(index);
+            tracing::debug!(
+                key_frame = start,
+                frames = index - start + 1,
+                "compositing from key frame"
+            );
+        }
+
+        let mut canvas = Vec::new();
+        self.composite(&mut canvas, None, index)?;
+
+        let image = RgbaImage::from_raw(self.width, self.height, canvas)
+            .and_then(|image| color_mode.convert_rgba(image))
+            .ok_or_else(|| {
+                WebpAnimationError::Decode("Failed to create image from RGBA buffer".into())
+            })?;
+        Ok(WebpFrame {
+            image,
+            duration: self.frames[index].duration,
+        })
+    }
+
+    /// Render the full canvas of the frame at `index` into `canvas`.
+    ///
+    /// If `canvas` holds frame `previous` and that is the frame right before
+    /// `index`, only the new frame is composited on top, which makes playing
+    /// through the animation as cheap as decoding it in one go. Otherwise the
+    /// canvas is cleared and composited forward from the nearest key frame.
+    pub(crate) fn composite(
+        &self,
+        canvas: &mut Vec<u8>,
+        previous: Option<usize>,
+        index: usize,
+    ) -> Result<()> {
+        let _slot = loader::decode_slot();
+        let continues = previous.is_some_and(|previous| previous + 1 == index);
+        let start = if continues {
+            index
+        } else {
+            // Like libwebp’s animated decoder, the canvas starts out transparent.
+            canvas.clear();
+            canvas.resize((self.width * self.height * 4) as usize, 0);
+            self.key_frame_before(index)
+        };
+        for i in start..=index {
+            if (i > start || continues) && self.frames[i - 1].dispose_to_background {
+                clear_rect(canvas, self.width, self.height, &self.frames[i - 1]);
+            }
+            self.draw_frame(canvas, i)?;
+        }
+        Ok(())
+    }
+
+    /// Read the header of every frame and mark the key frames.
+    fn read_headers(&mut self) -> Result<()> {
+        let mut iter: WebPIterator = unsafe { std::mem::zeroed() };
+        if unsafe { WebPDemuxGetFrame(self.demux, 1, &mut iter) } == 0 {
+            return Err(WebpAnimationError::NoFrames);
+        }
+        loop {
+            let full = iter.x_offset == 0
+                && iter.y_offset == 0
+                && iter.width as u32 == self.width
+                && iter.height as u32 == self.height;
+            let blend = iter.blend_method == WebPMuxAnimBlend_WEBP_MUX_BLEND;
+            // The rules of libwebp’s `IsKeyFrame`: a frame stands alone if it
+            // covers the canvas without blending, or if the previous frame
+            // cleared everything it depended on.
+            let key_frame = match self.frames.last() {
+                None => true,
+                Some(_) if full && (iter.has_alpha == 0 || !blend) => true,
+                Some(prev) => {
+                    prev.dispose_to_background
+                        && (prev.key_frame
+                            || (prev.x == 0
+                                && prev.y == 0
+                                && prev.width == self.width
+                                && prev.height == self.height))
+                }
+            };
+            self.frames.push(FrameHeader {
+                x: iter.x_offset as u32,
+                y: iter.y_offset as u32,
+                width: iter.width as u32,
+                height: iter.height as u32,
+                duration: Duration::from_millis(iter.duration.max(0) as u64),
+                dispose_to_background: iter.dispose_method
+                    == WebPMuxAnimDispose_WEBP_MUX_DISPOSE_BACKGROUND,
+                blend: blend && iter.has_alpha != 0,
+                key_frame,
+            });
+            if unsafe { WebPDemuxNextFrame(&mut iter) } == 0 {
+                break;
+            }
+        }
+        unsafe { WebPDemuxReleaseIterator(&mut iter) };
+        Ok(())
+    }
+
+    /// Decode the frame at `index` and composite it onto `canvas`.
+    fn draw_frame(&self, canvas: &mut [u8], index: usize) -> Result<()> {
+        let mut iter: WebPIterator = unsafe { std::mem::zeroed() };
+        if unsafe { WebPDemuxGetFrame(self.demux, index as c_int + 1, &mut iter) } == 0 {
+            return Err(WebpAnimationError::Decode(
+                "WebPDemuxGetFrame failed".into(),
+            ));
+        }
+        let bitstream = unsafe { slice::from_raw_parts(iter.fragment.bytes, iter.fragment.size) };
+        let drawn = draw_bitstream(
+            canvas,
+            self.width,
+            self.height,
+            &self.frames[index],
+            bitstream,
+        );
+        unsafe { WebPDemuxReleaseIterator(&mut iter) };
+        drawn.map_err(|_| {
+            WebpAnimationError::Decode(format!("WebPDecodeRGBA failed for frame {}", index))
+        })
+    }
+}
+
+// SAFETY: the demuxer only reads the file contents it owns, which are never
+// modified, and libwebp keeps no thread-local state, so it can be moved to and
+// read from other threads.
+unsafe impl Send for WebpDemuxer {}
+unsafe impl Sync for WebpDemuxer {}
+
+impl Drop for WebpDemuxer {
+    fn drop(&mut self) {
+        unsafe { WebPDemuxDelete(self.demux) };
+    }
+}
+
+impl WebpDecoder {
+    /// Decode only the frame at `index` of `path`, compositing forward from
+    /// the nearest key frame instead of decoding the whole animation.
+    ///
+    /// Open a [`WebpDemuxer`] instead when seeking repeatedly in one file.
+    ///
+    /// # Errors
+    /// * I/O failures (file not found, no read permission, …)
+    /// * Corrupted / unsupported WebP payloads
+    /// * `index` out of range
+    pub fn decode_frame<P: AsRef<Path>>(path: P, index: usize) -> Result<WebpFrame> {
+        WebpDemuxer::from_file(path)?.decode_frame(index)
+    }
+}
+
+//---------------------------------------------------------------------
+// Compositing
+//---------------------------------------------------------------------
+
+/// Decode the bitstream of one frame – an optional `ALPH` chunk followed by
+/// a `VP8 ` or `VP8L` chunk – and composite it onto the `width` × `height`
+/// RGBA `canvas` at the position given by `header`.
+pub(crate) fn draw_bitstream(
+    canvas: &mut [u8],
+    width: u32,
+    height: u32,
+    header: &FrameHeader,
+    bitstream: &[u8],
+) -> Result<()> {
+    let (mut w, mut h): (c_int, c_int) = (0, 0);
+    let rgba = unsafe { WebPDecodeRGBA(bitstream.as_ptr(), bitstream.len(), &mut w, &mut h) };
+    if rgba.is_null() {
+        return Err(WebpAnimationError::Decode("WebPDecodeRGBA failed".into()));
+    }
+
+    let pixels = unsafe { slice::from_raw_parts(rgba, (w * h * 4) as usize) };
+    let (w, h) = (w as u32, h as u32);
+    let visible_w = w.min(width.saturating_sub(header.x)) as usize;
+    for row in 0..h.min(height.saturating_sub(header.y)) {
+        let src = &pixels[(row * w * 4) as usize..][..visible_w * 4];
+        let offset = (((header.y + row) * width + header.x) * 4) as usize;
+        let dst = &mut canvas[offset..][..visible_w * 4];
+        if header.blend {
+            for (dst, src) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+                blend_pixel(dst, src);
+            }
+        } else {
+            dst.copy_from_slice(src);
+        }
+    }
+    unsafe { WebPFree(rgba as *mut c_void) };
+    Ok(())
+}
+
+/// Clear the rectangle of `frame` on the `width` × `height` RGBA `canvas`
+/// to transparent.
+pub(crate) fn clear_rect(canvas: &mut [u8], width: u32, height: u32, frame: &FrameHeader) {
+    let visible_w = frame.width.min(width.saturating_sub(frame.x)) as usize;
+    for row in frame.y..(frame.y + frame.height).min(height) {
+        let offset = ((row * width + frame.x) * 4) as usize;
+        canvas[offset..][..visible_w * 4].fill(0);
+    }
+}
+
+/// Alpha-blend the straight RGBA pixel `src` over `dst`, as libwebp’s
+/// animated decoder does for non-premultiplied output.
+fn blend_pixel(dst: &mut [u8], src: &[u8]) {
+    let src_a = src[3] as u32;
+    if src_a == 0 {
+        return;
+    }
+    let dst_factor_a = (dst[3] as u32 * (256 - src_a)) >> 8;
+    let blend_a = src_a + dst_factor_a;
+    let scale = (1u32 << 24) / blend_a;
+    for c in 0..3 {
+        let blended = src[c] as u32 * src_a + dst[c] as u32 * dst_factor_a;
+        dst[c] = ((blended as u64 * scale as u64) >> 24) as u8;
+    }
+    dst[3] = blend_a as u8;
+}
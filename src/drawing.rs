@@ -0,0 +1,146 @@
+use crate::animation::WebpAnimation;
+use crate::settings::TextureFilter;
+use nannou::prelude::*;
+use nannou::wgpu;
+
+/// Adds animation drawing to nannou's [`Draw`].
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use nannou_webp_animation::{DrawWebpExt, WebpAnimation};
+/// # fn view(app: &App, animation: &WebpAnimation) {
+/// let draw = app.draw();
+/// draw.webp(animation)
+///     .xy(pt2(100.0, 0.0))
+///     .w_h(256.0, 256.0)
+///     .rotate(PI / 8.0)
+///     .alpha(0.5);
+/// # }
+/// ```
+pub trait DrawWebpExt {
+    /// Begins drawing the current frame of `animation`.
+    ///
+    /// Like nannou's own primitives, the frame is drawn when the returned
+    /// [`WebpDrawing`] is dropped, centred at the origin at its native size
+    /// unless configured otherwise.
+    fn webp<'a>(&'a self, animation: &'a WebpAnimation) -> WebpDrawing<'a>;
+}
+
+impl DrawWebpExt for Draw {
+    fn webp<'a>(&'a self, animation: &'a WebpAnimation) -> WebpDrawing<'a> {
+        WebpDrawing {
+            draw: self,
+            animation,
+            xy: Point2::ZERO,
+            wh: vec2(animation.width() as f32, animation.height() as f32),
+            rotation: 0.0,
+            alpha: 1.0,
+        }
+    }
+}
+
+/// An animation frame being drawn, configured by chaining methods.
+///
+/// Created by [`DrawWebpExt::webp`]; the frame is submitted on drop, so the
+/// drawing is usually a statement of its own, like nannou's primitives.
+pub struct WebpDrawing<'a> {
+    /// The `Draw` the frame is submitted to.
+    draw: &'a Draw,
+    /// The animation whose current frame is drawn.
+    animation: &'a WebpAnimation,
+    /// Centre of the frame in world coordinates.
+    xy: Point2,
+    /// Drawn width and height.
+    wh: Vec2,
+    /// Rotation around the z axis in radians.
+    rotation: f32,
+    /// Opacity from `0.0` (invisible) to `1.0` (opaque).
+    alpha: f32,
+}
+
+impl WebpDrawing<'_> {
+    /// Sets the centre of the frame.
+    pub fn xy(mut self, xy: Point2) -> Self {
+        self.xy = xy;
+        self
+    }
+
+    /// Sets the centre of the frame from separate coordinates.
+    pub fn x_y(self, x: f32, y: f32) -> Self {
+        self.xy(pt2(x, y))
+    }
+
+    /// Sets the drawn width and height.
+    pub fn wh(mut self, wh: Vec2) -> Self {
+        self.wh = wh;
+        self
+    }
+
+    /// Sets the drawn width and height from separate values.
+    pub fn w_h(self, w: f32, h: f32) -> Self {
+        self.wh(vec2(w, h))
+    }
+
+    /// Rotates the frame around its centre by `radians`, counter-clockwise.
+    pub fn rotate(mut self, radians: f32) -> Self {
+        self.rotation = radians;
+        self
+    }
+
+    /// Sets the opacity from `0.0` (invisible) to `1.0` (opaque).
+    pub fn alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Draws the `area` of `texture` with the placement of this drawing.
+    fn draw_texture(&self, draw: &Draw, texture: &wgpu::Texture, area: Rect, alpha: f32) {
+        if alpha >= 1.0 {
+            draw.texture(texture)
+                .area(area)
+                .xy(self.xy)
+                .wh(self.wh)
+                .rotate(self.rotation);
+            return;
+        }
+
+        // The texture primitive cannot be tinted, so translucent frames are drawn
+        // as a textured quad whose fill color carries the opacity. Texture
+        // coordinates grow downwards, world coordinates upwards.
+        let half = self.wh / 2.0;
+        let (u0, v0, u1, v1) = (area.left(), area.bottom(), area.right(), area.top());
+        let tl = (pt3(-half.x, half.y, 0.0), vec2(u0, v0));
+        let tr = (pt3(half.x, half.y, 0.0), vec2(u1, v0));
+        let bl = (pt3(-half.x, -half.y, 0.0), vec2(u0, v1));
+        let br = (pt3(half.x, -half.y, 0.0), vec2(u1, v1));
+        draw.mesh()
+            .points_textured(texture, [tl, tr, br, tl, br, bl])
+            .color(rgba(1.0, 1.0, 1.0, alpha))
+            .xy(self.xy)
+            .rotate(self.rotation);
+    }
+}
+
+impl Drop for WebpDrawing<'_> {
+    fn drop(&mut self) {
+        let texture = self.animation.texture();
+        let area = self.animation.texture_area();
+        let draw = match self.animation.filter() {
+            TextureFilter::Linear => self.draw.clone(),
+            TextureFilter::Nearest => self.draw.sampler(wgpu::SamplerDescriptor {
+                mag_filter: wgpu::FilterMode::Nearest,
+                min_filter: wgpu::FilterMode::Nearest,
+                ..Default::default()
+            }),
+        };
+
+        self.draw_texture(&draw, texture, area, self.alpha);
+        // Crossfading draws the next frame over the current one, so only its
+        // opacity changes from one frame to the next.
+        if let Some((next, t)) = self.animation.crossfade() {
+            if t > 0.0 {
+                self.draw_texture(&draw, next, area, self.alpha * t);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Loads libwebpdemux at runtime instead of linking it.
+//!
+//! The functions below mirror the bindgen declarations they replace, so the
+//! decoder calls them unchanged. They must only be called after [`load`]
+//! succeeded.
+
+#![allow(non_snake_case)]
+
+use crate::bindings::{WebPAnimDecoder, WebPAnimDecoderOptions, WebPAnimInfo, WebPData};
+use libc::c_int;
+use libloading::Library;
+use std::sync::OnceLock;
+
+/// File names tried in order; the versioned name is preferred so that a
+/// development symlink is not required.
+#[cfg(target_os = "windows")]
+const CANDIDATES: &[&str] = &["libwebpdemux.dll", "webpdemux.dll"];
+#[cfg(target_os = "macos")]
+const CANDIDATES: &[&str] = &["libwebpdemux.2.dylib", "libwebpdemux.dylib"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const CANDIDATES: &[&str] = &["libwebpdemux.so.2", "libwebpdemux.so"];
+
+/// The libwebpdemux functions used by the decoder.
+struct Api {
+    options_init: unsafe extern "C" fn(*mut WebPAnimDecoderOptions, c_int) -> c_int,
+    new: unsafe extern "C" fn(
+        *const WebPData,
+        *const WebPAnimDecoderOptions,
+        c_int,
+    ) -> *mut WebPAnimDecoder,
+    get_info: unsafe extern "C" fn(*const WebPAnimDecoder, *mut WebPAnimInfo) -> c_int,
+    has_more_frames: unsafe extern "C" fn(*const WebPAnimDecoder) -> c_int,
+    get_next: unsafe extern "C" fn(*mut WebPAnimDecoder, *mut *mut u8, *mut c_int) -> c_int,
+    delete: unsafe extern "C" fn(*mut WebPAnimDecoder),
+    /// Keeps the function pointers above valid.
+    _library: Library,
+}
+
+/// The loaded library, or why loading failed. Loading is attempted once.
+static API: OnceLock<Result<Api, String>> = OnceLock::new();
+
+/// Loads libwebpdemux (and with it libwebp) on first use.
+///
+/// Returns why the library or one of its functions could not be loaded.
+pub(crate) fn load() -> Result<(), String> {
+    API.get_or_init(open)
+        .as_ref()
+        .map(|_| ())
+        .map_err(Clone::clone)
+}
+
+/// Returns the loaded functions.
+fn api() -> &'static Api {
+    match API.get() {
+        Some(Ok(api)) => api,
+        _ => panic!("libwebpdemux used before it was loaded"),
+    }
+}
+
+/// Opens the first library in [`CANDIDATES`] and resolves the functions.
+fn open() -> Result<Api, String> {
+    let mut errors = Vec::new();
+    for name in CANDIDATES {
+        match unsafe { Library::new(name) } {
+            Ok(library) => return resolve(library).map_err(|e| format!("{}: {}", name, e)),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+    Err(format!("libwebpdemux not found ({})", errors.join("; ")))
+}
+
+/// Looks up every function of [`Api`] in `library`.
+fn resolve(library: Library) -> Result<Api, libloading::Error> {
+    unsafe {
+        Ok(Api {
+            options_init: *library.get(b"WebPAnimDecoderOptionsInitInternal\0")?,
+            new: *library.get(b"WebPAnimDecoderNewInternal\0")?,
+            get_info: *library.get(b"WebPAnimDecoderGetInfo\0")?,
+            has_more_frames: *library.get(b"WebPAnimDecoderHasMoreFrames\0")?,
+            get_next: *library.get(b"WebPAnimDecoderGetNext\0")?,
+            delete: *library.get(b"WebPAnimDecoderDelete\0")?,
+            _library: library,
+        })
+    }
+}
+
+pub(crate) unsafe fn WebPAnimDecoderOptionsInitInternal(
+    options: *mut WebPAnimDecoderOptions,
+    abi_version: c_int,
+) -> c_int {
+    (api().options_init)(options, abi_version)
+}
+
+pub(crate) unsafe fn WebPAnimDecoderNewInternal(
+    data: *const WebPData,
+    options: *const WebPAnimDecoderOptions,
+    abi_version: c_int,
+) -> *mut WebPAnimDecoder {
+    (api().new)(data, options, abi_version)
+}
+
+pub(crate) unsafe fn WebPAnimDecoderGetInfo(
+    decoder: *const WebPAnimDecoder,
+    info: *mut WebPAnimInfo,
+) -> c_int {
+    (api().get_info)(decoder, info)
+}
+
+pub(crate) unsafe fn WebPAnimDecoderHasMoreFrames(decoder: *const WebPAnimDecoder) -> c_int {
+    (api().has_more_frames)(decoder)
+}
+
+pub(crate) unsafe fn WebPAnimDecoderGetNext(
+    decoder: *mut WebPAnimDecoder,
+    buffer: *mut *mut u8,
+    timestamp: *mut c_int,
+) -> c_int {
+    (api().get_next)(decoder, buffer, timestamp)
+}
+
+pub(crate) unsafe fn WebPAnimDecoderDelete(decoder: *mut WebPAnimDecoder) {
+    (api().delete)(decoder)
+}
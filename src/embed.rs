@@ -0,0 +1,83 @@
+#[cfg(feature = "nannou")]
+use crate::animation::WebpAnimationPlayer;
+use crate::data::WebpAnimationData;
+use crate::error::Result;
+#[cfg(feature = "nannou")]
+use nannou::App;
+
+/// An animation file compiled into the binary, decoded when it is first used.
+///
+/// Created with the [`webp_animation!`](crate::webp_animation) macro, which
+/// embeds the file with `include_bytes!`. Single-binary installations then
+/// don't need to ship an assets folder next to the executable. Embedding is
+/// free at startup: nothing is decoded until [`decode`](Self::decode) or
+/// [`load`](Self::load) is called.
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedAnimation {
+    /// The encoded file.
+    bytes: &'static [u8],
+}
+
+impl EmbeddedAnimation {
+    /// Wraps an encoded animation that lives for the whole program, e.g. the
+    /// output of `include_bytes!`.
+    pub const fn new(bytes: &'static [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the encoded file.
+    pub fn bytes(&self) -> &'static [u8] {
+        self.bytes
+    }
+
+    /// Decodes the embedded file; see [`WebpAnimationData::from_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded or contains no frames.
+    pub fn decode(&self) -> Result<WebpAnimationData> {
+        WebpAnimationData::from_bytes(self.bytes)
+    }
+
+    /// Decodes the embedded file and uploads its frames.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded or contains no frames.
+    #[cfg(feature = "nannou")]
+    pub fn load(&self, app: &App) -> Result<WebpAnimationPlayer> {
+        WebpAnimationPlayer::from_bytes(self.bytes, app)
+    }
+}
+
+/// Embeds an animation file in the binary at compile time.
+///
+/// The path is relative to the crate root (the directory of the calling
+/// crate's `Cargo.toml`), like the `assets` folder nannou sketches load from.
+/// The macro expands to an [`EmbeddedAnimation`], so it can initialize a
+/// `static` and defers decoding until the animation is used:
+///
+/// ```ignore
+/// use nannou_webp_animation::{webp_animation, EmbeddedAnimation};
+///
+/// static INTRO: EmbeddedAnimation = webp_animation!("assets/intro.webp");
+///
+/// fn model(app: &App) -> Model {
+///     let animation = INTRO.load(app).expect("Failed to decode WEBP animation");
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! webp_animation {
+    ($path:literal) => {
+        $crate::EmbeddedAnimation::new(::std::include_bytes!(::std::concat!(
+            ::std::env!("CARGO_MANIFEST_DIR"),
+            "/",
+            $path
+        )))
+    };
+}
@@ -0,0 +1,902 @@
+use crate::bindings::*;
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use crate::mux::WebpMuxEditor;
+use crate::utils::{letterbox, quantize, Dithering};
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use libc::{c_int, c_void};
+
+//---------------------------------------------------------------------
+// Encoder options
+//---------------------------------------------------------------------
+
+/// Metadata chunks embedded in exported files.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    /// ICC color profile (`ICCP` chunk).
+    pub icc_profile: Option<Vec<u8>>,
+    /// EXIF metadata (`EXIF` chunk).
+    pub exif: Option<Vec<u8>>,
+    /// XMP metadata (`XMP ` chunk).
+    pub xmp: Option<Vec<u8>>,
+}
+
+impl Metadata {
+    /// Returns `true` if no chunk is set.
+    pub fn is_empty(&self) -> bool {
+        self.icc_profile.is_none() && self.exif.is_none() && self.xmp.is_none()
+    }
+
+    /// Add the metadata chunks to an assembled WebP.
+    fn embed(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        if self.is_empty() {
+            return Ok(data);
+        }
+
+        let mut mux = WebpMuxEditor::from_bytes(&data)?;
+        if let Some(icc_profile) = &self.icc_profile {
+            mux.set_icc_profile(icc_profile)?;
+        }
+        if let Some(exif) = &self.exif {
+            mux.set_exif(exif)?;
+        }
+        if let Some(xmp) = &self.xmp {
+            mux.set_xmp(xmp)?;
+        }
+        mux.assemble()
+    }
+}
+
+/// Palette reduction applied to every frame before compression.
+///
+/// Useful for deliberately lo-fi aesthetics and for smaller lossless files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quantization {
+    /// Number of values per color channel (2–255), giving `levels³` colors.
+    pub levels: u8,
+    /// How rounding errors are distributed.
+    pub dithering: Dithering,
+}
+
+/// Compression settings applied to every frame, mirroring libwebp’s
+/// `WebPConfig`.
+///
+/// The defaults match libwebp’s default preset (lossy, quality 75).
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncoderOptions {
+    /// Quality from 0 (smallest) to 100 (best). In lossless mode this is the
+    /// compression effort instead.
+    pub quality: f32,
+    /// Speed/size trade-off from 0 (fastest) to 6 (slowest, smallest).
+    pub method: u8,
+    /// Encode frames losslessly.
+    pub lossless: bool,
+    /// Near-lossless preprocessing from 0 (strongest) to 100 (off). Only
+    /// used when `lossless` is set.
+    pub near_lossless: u8,
+    /// Quality of the alpha plane from 0 to 100.
+    pub alpha_quality: u8,
+    /// Number of times the animation repeats; `0` loops forever.
+    pub loop_count: u16,
+    /// Canvas background color as RGBA. Viewers may use it to clear the
+    /// canvas between loops.
+    pub background_color: [u8; 4],
+    /// Minimum distance between keyframes. `None` keeps libwebp’s default.
+    ///
+    /// Smaller keyframe distances make seeking cheaper at the cost of a
+    /// larger file.
+    pub kmin: Option<u32>,
+    /// Maximum distance between keyframes. `None` keeps libwebp’s default,
+    /// which only inserts keyframes where they reduce the file size.
+    pub kmax: Option<u32>,
+    /// Allow mixing lossy and lossless frames, picking whichever is smaller
+    /// for each frame.
+    pub allow_mixed: bool,
+    /// ICC, EXIF and XMP chunks embedded in the output.
+    pub metadata: Metadata,
+    /// Maximum size of the output in bytes, e.g. for messaging platforms.
+    ///
+    /// When set, whole-sequence encoding ([`WebpEncoder::encode_with_options`],
+    /// [`EncodeJob`]) searches for the highest lossy `quality` whose output
+    /// fits, ignoring `quality` and `lossless`. The incremental
+    /// [`WebpEncoder::add_frame`] API ignores this setting.
+    pub target_size: Option<usize>,
+    /// Optional palette reduction and dithering applied before compression.
+    pub quantization: Option<Quantization>,
+}
+
+impl Default for EncoderOptions {
+    fn default() -> Self {
+        Self {
+            quality: 75.0,
+            method: 4,
+            lossless: false,
+            near_lossless: 100,
+            alpha_quality: 100,
+            loop_count: 0,
+            background_color: [255, 255, 255, 255],
+            kmin: None,
+            kmax: None,
+            allow_mixed: false,
+            metadata: Metadata::default(),
+            target_size: None,
+            quantization: None,
+        }
+    }
+}
+
+impl EncoderOptions {
+    /// Build and validate the libwebp configuration for these options.
+    fn to_config(&self) -> Result<WebPConfig> {
+        let mut config: WebPConfig = unsafe { std::mem::zeroed() };
+        let ok = unsafe {
+            WebPConfigInitInternal(
+                &mut config,
+                WebPPreset_WEBP_PRESET_DEFAULT,
+                self.quality,
+                WEBP_ENCODER_ABI_VERSION as c_int,
+            )
+        };
+        if ok == 0 {
+            return Err(WebpAnimationError::Encode(
+                "WebPConfigInitInternal failed".into(),
+            ));
+        }
+
+        config.quality = self.quality;
+        config.method = self.method as c_int;
+        config.lossless = self.lossless as c_int;
+        config.near_lossless = self.near_lossless as c_int;
+        config.alpha_quality = self.alpha_quality as c_int;
+
+        if unsafe { WebPValidateConfig(&config) } == 0 {
+            return Err(WebpAnimationError::InvalidArgument(
+                "invalid encoder options".into(),
+            ));
+        }
+        Ok(config)
+    }
+}
+
+//---------------------------------------------------------------------
+// Public encoder
+//---------------------------------------------------------------------
+
+/// Incremental animated WebP encoder built on libwebp’s `WebPAnimEncoder`.
+///
+/// Frames are pushed one by one with [`add_frame`](Self::add_frame); the
+/// encoder compresses each frame as it arrives, so only the compressed
+/// output is kept in memory. [`finish`](Self::finish) assembles the final
+/// RIFF container.
+///
+/// Every frame must have the canvas size given to [`new`](Self::new).
+pub struct WebpEncoder {
+    /// Owned libwebp encoder, freed on drop.
+    enc: *mut WebPAnimEncoder,
+    /// Canvas width in pixels.
+    width: u32,
+    /// Canvas height in pixels.
+    height: u32,
+    /// Timestamp (ms) at which the next frame starts.
+    timestamp_ms: c_int,
+    /// Compression settings applied to every frame.
+    config: WebPConfig,
+    /// Metadata chunks added to the assembled file.
+    metadata: Metadata,
+    /// Palette reduction applied to every frame.
+    quantization: Option<Quantization>,
+}
+
+impl WebpEncoder {
+    /// Create an encoder for a `width` × `height` canvas using the default
+    /// [`EncoderOptions`].
+    ///
+    /// # Errors
+    /// * Invalid canvas dimensions
+    /// * libwebp failing to allocate the encoder
+    pub fn new(width: u32, height: u32) -> Result<Self> {
+        Self::with_options(width, height, &EncoderOptions::default())
+    }
+
+    /// Create an encoder for a `width` × `height` canvas with custom
+    /// compression settings.
+    ///
+    /// # Errors
+    /// * Invalid canvas dimensions or encoder options
+    /// * libwebp failing to allocate the encoder
+    pub fn with_options(width: u32, height: u32, options: &EncoderOptions) -> Result<Self> {
+        let config = options.to_config()?;
+        if width == 0 || height == 0 {
+            return Err(WebpAnimationError::InvalidArgument(
+                "canvas dimensions must be non-zero".into(),
+            ));
+        }
+
+        let mut enc_opts: WebPAnimEncoderOptions = unsafe { std::mem::zeroed() };
+        let ok = unsafe {
+            WebPAnimEncoderOptionsInitInternal(&mut enc_opts, WEBP_MUX_ABI_VERSION as c_int)
+        };
+        if ok == 0 {
+            return Err(WebpAnimationError::Encode(
+                "WebPAnimEncoderOptionsInitInternal failed".into(),
+            ));
+        }
+        enc_opts.anim_params.loop_count = options.loop_count as c_int;
+        enc_opts.anim_params.bgcolor = bgcolor(options.background_color);
+        if let Some(kmin) = options.kmin {
+            enc_opts.kmin = kmin as c_int;
+        }
+        if let Some(kmax) = options.kmax {
+            enc_opts.kmax = kmax as c_int;
+        }
+        enc_opts.allow_mixed = options.allow_mixed as c_int;
+
+        let enc = unsafe {
+            WebPAnimEncoderNewInternal(
+                width as c_int,
+                height as c_int,
+                &enc_opts,
+                WEBP_MUX_ABI_VERSION as c_int,
+            )
+        };
+        if enc.is_null() {
+            return Err(WebpAnimationError::Encode(
+                "WebPAnimEncoderNewInternal failed".into(),
+            ));
+        }
+
+        Ok(Self {
+            enc,
+            width,
+            height,
+            timestamp_ms: 0,
+            config,
+            metadata: options.metadata.clone(),
+            quantization: options.quantization,
+        })
+    }
+
+    /// Encode `frames` into an animated WebP held in memory.
+    ///
+    /// The canvas size is taken from the first frame.
+    ///
+    /// # Errors
+    /// * An empty frame list or frames of differing sizes
+    /// * libwebp failing to encode a frame
+    pub fn encode(frames: &[WebpFrame]) -> Result<Vec<u8>> {
+        Self::encode_with_options(frames, &EncoderOptions::default())
+    }
+
+    /// Encode `frames` into an animated WebP held in memory using custom
+    /// compression settings.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode); additionally invalid encoder options.
+    pub fn encode_with_options(frames: &[WebpFrame], options: &EncoderOptions) -> Result<Vec<u8>> {
+        match options.target_size {
+            Some(target_size) => Self::encode_to_size(frames, options, target_size),
+            None => Self::encode_frames(frames, options),
+        }
+    }
+
+    /// Binary-search the lossy quality for the largest output that still
+    /// fits into `target_size` bytes.
+    fn encode_to_size(
+        frames: &[WebpFrame],
+        options: &EncoderOptions,
+        target_size: usize,
+    ) -> Result<Vec<u8>> {
+        let mut options = options.clone();
+        options.lossless = false;
+
+        let (mut lo, mut hi) = (0u32, 100u32);
+        let mut best = None;
+        while lo <= hi {
+            let quality = (lo + hi) / 2;
+            options.quality = quality as f32;
+            let data = Self::encode_frames(frames, &options)?;
+            if data.len() <= target_size {
+                best = Some(data);
+                lo = quality + 1;
+            } else if quality == 0 {
+                break;
+            } else {
+                hi = quality - 1;
+            }
+        }
+
+        best.ok_or_else(|| {
+            WebpAnimationError::Encode(format!(
+                "cannot fit the animation into {} bytes, even at quality 0",
+                target_size
+            ))
+        })
+    }
+
+    /// Encode `frames` with exactly the given options.
+    fn encode_frames(frames: &[WebpFrame], options: &EncoderOptions) -> Result<Vec<u8>> {
+        let first = frames.first().ok_or(WebpAnimationError::NoFrames)?;
+        let mut encoder = Self::with_options(first.image.width(), first.image.height(), options)?;
+        for frame in frames {
+            encoder.add_frame(&frame.image, frame.duration)?;
+        }
+        encoder.finish()
+    }
+
+    /// Encode `frames` and write the animated WebP to `path`.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode); additionally I/O failures while writing.
+    pub fn encode_to_file<P: AsRef<Path>>(frames: &[WebpFrame], path: P) -> Result<()> {
+        let data = Self::encode(frames)?;
+        Ok(fs::write(path, data)?)
+    }
+
+    /// Encode frames produced by an iterator and write the animated WebP to
+    /// `path`.
+    ///
+    /// Frames are compressed as they are produced, so procedural generators
+    /// never need to hold the whole sequence in memory. The canvas size is
+    /// taken from the first frame.
+    ///
+    /// # Errors
+    /// * The iterator yields no frames or frames of differing sizes
+    /// * libwebp failing to encode a frame
+    /// * I/O failures while writing
+    pub fn encode_iter<I, P>(frames: I, path: P, options: &EncoderOptions) -> Result<()>
+    where
+        I: IntoIterator<Item = (DynamicImage, Duration)>,
+        P: AsRef<Path>,
+    {
+        let file = File::create(path)?;
+        Self::encode_iter_to_writer(frames, file, options)
+    }
+
+    /// Encode frames produced by an iterator and write the animated WebP
+    /// into `writer` (socket, pipe, in-memory buffer, …).
+    ///
+    /// # Errors
+    /// See [`encode_iter`](Self::encode_iter).
+    pub fn encode_iter_to_writer<I, W>(frames: I, writer: W, options: &EncoderOptions) -> Result<()>
+    where
+        I: IntoIterator<Item = (DynamicImage, Duration)>,
+        W: Write,
+    {
+        let mut frames = frames.into_iter();
+        let (image, duration) = frames.next().ok_or(WebpAnimationError::NoFrames)?;
+
+        let mut encoder = Self::with_options(image.width(), image.height(), options)?;
+        encoder.add_frame(&image, duration)?;
+        for (image, duration) in frames {
+            encoder.add_frame(&image, duration)?;
+        }
+
+        encoder.finish_to_writer(writer)
+    }
+
+    /// Append `image` to the animation, displayed for `duration`.
+    ///
+    /// # Errors
+    /// * `image` does not match the canvas size
+    /// * libwebp failing to encode the frame
+    pub fn add_frame(&mut self, image: &DynamicImage, duration: Duration) -> Result<()> {
+        let mut rgba = image.to_rgba8();
+        if let Some(quantization) = self.quantization {
+            quantize(&mut rgba, quantization.levels, quantization.dithering);
+        }
+        if rgba.dimensions() != (self.width, self.height) {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "frame size {}x{} does not match canvas size {}x{}",
+                rgba.width(),
+                rgba.height(),
+                self.width,
+                self.height
+            )));
+        }
+
+        //-----------------------------------------------------------------
+        // 1. Import the RGBA pixels into a WebPPicture
+        //-----------------------------------------------------------------
+        let mut pic: WebPPicture = unsafe { std::mem::zeroed() };
+        let ok = unsafe { WebPPictureInitInternal(&mut pic, WEBP_ENCODER_ABI_VERSION as c_int) };
+        if ok == 0 {
+            return Err(WebpAnimationError::Encode(
+                "WebPPictureInitInternal failed".into(),
+            ));
+        }
+        pic.use_argb = 1;
+        pic.width = self.width as c_int;
+        pic.height = self.height as c_int;
+
+        let ok =
+            unsafe { WebPPictureImportRGBA(&mut pic, rgba.as_ptr(), (self.width * 4) as c_int) };
+        if ok == 0 {
+            unsafe { WebPPictureFree(&mut pic) };
+            return Err(WebpAnimationError::Encode(
+                "WebPPictureImportRGBA failed".into(),
+            ));
+        }
+
+        //-----------------------------------------------------------------
+        // 2. Hand the picture to the animation encoder
+        //-----------------------------------------------------------------
+        let ok = unsafe { WebPAnimEncoderAdd(self.enc, &mut pic, self.timestamp_ms, &self.config) };
+        unsafe { WebPPictureFree(&mut pic) };
+        if ok == 0 {
+            return Err(self.last_error("WebPAnimEncoderAdd failed"));
+        }
+
+        self.timestamp_ms += duration.as_millis() as c_int;
+        Ok(())
+    }
+
+    /// Flush pending frames and return the assembled animated WebP bytes.
+    ///
+    /// # Errors
+    /// * libwebp failing to flush or assemble the animation
+    pub fn finish(self) -> Result<Vec<u8>> {
+        // A NULL frame marks the end of the animation and fixes the duration
+        // of the last frame.
+        let ok = unsafe {
+            WebPAnimEncoderAdd(self.enc, ptr::null_mut(), self.timestamp_ms, ptr::null())
+        };
+        if ok == 0 {
+            return Err(self.last_error("WebPAnimEncoderAdd failed"));
+        }
+
+        let mut webp_data: WebPData = unsafe { std::mem::zeroed() };
+        let ok = unsafe { WebPAnimEncoderAssemble(self.enc, &mut webp_data) };
+        if ok == 0 {
+            return Err(self.last_error("WebPAnimEncoderAssemble failed"));
+        }
+
+        // Copy into Rust‑owned memory and release libwebp’s buffer.
+        let bytes = unsafe { slice::from_raw_parts(webp_data.bytes, webp_data.size) }.to_vec();
+        unsafe { WebPFree(webp_data.bytes as *mut c_void) };
+
+        self.metadata.embed(bytes)
+    }
+
+    /// Flush pending frames and write the assembled animated WebP into
+    /// `writer`.
+    ///
+    /// # Errors
+    /// See [`finish`](Self::finish); additionally I/O failures while writing.
+    pub fn finish_to_writer<W: Write>(self, mut writer: W) -> Result<()> {
+        let data = self.finish()?;
+        writer.write_all(&data)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Returns the canvas width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the canvas height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Combine `context` with libwebp’s description of the last error.
+    fn last_error(&self, context: &str) -> WebpAnimationError {
+        let msg = unsafe { WebPAnimEncoderGetError(self.enc) };
+        if msg.is_null() {
+            return WebpAnimationError::Encode(context.to_string());
+        }
+        let msg = unsafe { std::ffi::CStr::from_ptr(msg) };
+        WebpAnimationError::Encode(format!("{}: {}", context, msg.to_string_lossy()))
+    }
+}
+
+//---------------------------------------------------------------------
+// Background encoder
+//---------------------------------------------------------------------
+
+/// A frame queued for the background encoder, tagged with its position.
+type QueuedFrame = (usize, DynamicImage, Duration);
+
+/// Encoder that compresses frames on a worker thread.
+///
+/// [`push`](Self::push) only hands the frame to a channel, so a live sketch
+/// can record at full frame rate while libwebp compresses in the
+/// background. Frames are encoded in the order they were pushed.
+pub struct BackgroundEncoder {
+    /// Sends frames to the worker; dropped to signal the end of input.
+    sender: Option<Sender<QueuedFrame>>,
+    /// Worker thread running the [`WebpEncoder`].
+    worker: Option<JoinHandle<Result<Vec<u8>>>>,
+    /// Position assigned to the next frame.
+    next_index: usize,
+}
+
+impl BackgroundEncoder {
+    /// Spawn a worker encoding a `width` × `height` animation.
+    ///
+    /// Errors creating the encoder are reported by [`finish`](Self::finish).
+    pub fn new(width: u32, height: u32, options: EncoderOptions) -> Self {
+        let (sender, receiver) = mpsc::channel::<QueuedFrame>();
+        let worker = thread::spawn(move || {
+            let mut encoder = WebpEncoder::with_options(width, height, &options)?;
+
+            // Frames may arrive out of order when they are sent from several
+            // threads; buffer them until the next one in sequence arrives.
+            let mut pending = BTreeMap::new();
+            let mut next_index = 0;
+            for (index, image, duration) in receiver {
+                pending.insert(index, (image, duration));
+                while let Some((image, duration)) = pending.remove(&next_index) {
+                    encoder.add_frame(&image, duration)?;
+                    next_index += 1;
+                }
+            }
+            // Frames whose predecessors never arrived are still encoded in order.
+            for (image, duration) in pending.into_values() {
+                encoder.add_frame(&image, duration)?;
+            }
+
+            encoder.finish()
+        });
+
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+            next_index: 0,
+        }
+    }
+
+    /// Queue `image`, displayed for `duration`, without waiting for it to be
+    /// compressed.
+    ///
+    /// # Errors
+    /// * The worker has stopped, e.g. after an encoding error
+    pub fn push(&mut self, image: DynamicImage, duration: Duration) -> Result<()> {
+        let (index, sender) = self.reserve()?;
+        sender
+            .send((index, image, duration))
+            .map_err(|_| WebpAnimationError::Encode("encoder thread has stopped".into()))
+    }
+
+    /// Reserve the next position in the sequence, returning it with a sender
+    /// that may deliver the frame later from another thread.
+    pub(crate) fn reserve(&mut self) -> Result<(usize, Sender<QueuedFrame>)> {
+        let sender = self.sender.clone().ok_or_else(already_finished)?;
+        let index = self.next_index;
+        self.next_index += 1;
+        Ok((index, sender))
+    }
+
+    /// Returns the number of frames queued so far.
+    pub fn frame_count(&self) -> usize {
+        self.next_index
+    }
+
+    /// Wait for all queued frames to be compressed and return the animated
+    /// WebP bytes.
+    ///
+    /// # Errors
+    /// * Any error raised while encoding a frame
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        // Closing the channel lets the worker drain its queue.
+        self.sender.take();
+        match self.worker.take() {
+            Some(worker) => worker
+                .join()
+                .map_err(|_| WebpAnimationError::Encode("encoder thread panicked".into()))?,
+            None => Err(already_finished()),
+        }
+    }
+}
+
+/// The error returned once a [`BackgroundEncoder`] has been finished.
+fn already_finished() -> WebpAnimationError {
+    WebpAnimationError::Encode("encoder has already finished".into())
+}
+
+//---------------------------------------------------------------------
+// Encode jobs
+//---------------------------------------------------------------------
+
+/// Resampling filter used when resizing frames on export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResizeFilter {
+    /// Nearest neighbour: fastest, keeps hard pixel edges.
+    Nearest,
+    /// Bilinear interpolation.
+    Bilinear,
+    /// Lanczos with a window of 3: slowest, sharpest downscaling.
+    #[default]
+    Lanczos,
+}
+
+impl From<ResizeFilter> for FilterType {
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Nearest => FilterType::Nearest,
+            ResizeFilter::Bilinear => FilterType::Triangle,
+            ResizeFilter::Lanczos => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// Closure compositing content onto a frame, given the frame's index in the
+/// output sequence.
+pub type OverlayFn = dyn Fn(usize, &mut RgbaImage) + Send + Sync;
+
+/// Content composited over every frame on export.
+#[derive(Clone)]
+enum Overlay {
+    /// A static image (e.g. a logo) drawn at the given offset.
+    Image(Arc<RgbaImage>, u32, u32),
+    /// Arbitrary per-frame drawing (e.g. a timestamp burn-in).
+    Closure(Arc<OverlayFn>),
+}
+
+/// A sequence of frames queued for encoding together with its settings.
+///
+/// Jobs are usually created from a decoded animation, e.g. with
+/// [`WebpAnimationData::trim`](crate::WebpAnimationData::trim), and then written with
+/// [`encode`](Self::encode) or [`write_to_file`](Self::write_to_file).
+/// Per-frame durations are preserved.
+///
+/// Frame transformations such as [`resize`](Self::resize) are applied on the
+/// CPU right before encoding; the source frames are left untouched.
+#[derive(Clone)]
+pub struct EncodeJob<'a> {
+    /// Frames to encode, borrowed from the source animation where possible.
+    frames: Cow<'a, [WebpFrame]>,
+    /// Compression settings for the output.
+    options: EncoderOptions,
+    /// Bounding size and filter frames are scaled to.
+    resize: Option<(u32, u32, ResizeFilter)>,
+    /// Canvas region (x, y, width, height) kept from every frame.
+    crop: Option<(u32, u32, u32, u32)>,
+    /// Number of leading and trailing frames dropped.
+    trim: (usize, usize),
+    /// Append the reversed sequence for a back-and-forth loop.
+    boomerang: bool,
+    /// Content composited over every frame, in order.
+    overlays: Vec<Overlay>,
+}
+
+impl<'a> EncodeJob<'a> {
+    /// Create a job encoding `frames` with the default [`EncoderOptions`].
+    pub fn new(frames: &'a [WebpFrame]) -> Self {
+        Self {
+            frames: Cow::Borrowed(frames),
+            options: EncoderOptions::default(),
+            resize: None,
+            crop: None,
+            trim: (0, 0),
+            boomerang: false,
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Concatenate several jobs into one animation with combined timing.
+    ///
+    /// The canvas is the largest width and height among all frames; smaller
+    /// frames are centred on a transparent canvas (letterboxed). The options
+    /// of the first job are kept.
+    pub fn concat<I>(jobs: I) -> EncodeJob<'static>
+    where
+        I: IntoIterator<Item = EncodeJob<'a>>,
+    {
+        let jobs: Vec<EncodeJob<'a>> = jobs.into_iter().collect();
+        let options = jobs
+            .first()
+            .map(|job| job.options.clone())
+            .unwrap_or_default();
+
+        let frames = jobs.iter().flat_map(|job| job.frames.iter());
+        let width = frames.clone().map(|f| f.image.width()).max().unwrap_or(0);
+        let height = frames.clone().map(|f| f.image.height()).max().unwrap_or(0);
+        let frames = frames
+            .map(|frame| WebpFrame {
+                image: letterbox(&frame.image, width, height),
+                duration: frame.duration,
+            })
+            .collect::<Vec<_>>();
+
+        EncodeJob {
+            frames: Cow::Owned(frames),
+            options,
+            resize: None,
+            crop: None,
+            trim: (0, 0),
+            boomerang: false,
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Set the compression settings for the output.
+    pub fn options(mut self, options: EncoderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Scale frames to fit within `width` × `height`, preserving the aspect
+    /// ratio, e.g. to write a 4K capture out as a small preview loop.
+    pub fn resize(mut self, width: u32, height: u32, filter: ResizeFilter) -> Self {
+        self.resize = Some((width, height, filter));
+        self
+    }
+
+    /// Crop every frame to the `width` × `height` region at (`x`, `y`).
+    ///
+    /// Cropping happens before resizing. The region is clipped to the canvas.
+    pub fn crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.crop = Some((x, y, width, height));
+        self
+    }
+
+    /// Drop `leading` frames from the start and `trailing` frames from the
+    /// end of the sequence.
+    pub fn trim_frames(mut self, leading: usize, trailing: usize) -> Self {
+        self.trim = (leading, trailing);
+        self
+    }
+
+    /// Append the reversed sequence, without repeating the first and last
+    /// frames, producing a seamless back-and-forth loop.
+    pub fn boomerang(mut self, boomerang: bool) -> Self {
+        self.boomerang = boomerang;
+        self
+    }
+
+    /// Composite `image` over every frame at (`x`, `y`), e.g. a logo
+    /// watermark. Alpha blending is applied; overlays are drawn after
+    /// cropping and resizing.
+    pub fn overlay_image(mut self, image: &DynamicImage, x: u32, y: u32) -> Self {
+        self.overlays
+            .push(Overlay::Image(Arc::new(image.to_rgba8()), x, y));
+        self
+    }
+
+    /// Draw onto every frame with `overlay`, which receives the frame index
+    /// in the output sequence and its RGBA pixels, e.g. to burn in a
+    /// timestamp.
+    pub fn overlay_with<F>(mut self, overlay: F) -> Self
+    where
+        F: Fn(usize, &mut RgbaImage) + Send + Sync + 'static,
+    {
+        self.overlays.push(Overlay::Closure(Arc::new(overlay)));
+        self
+    }
+
+    /// Returns the source frames of the job, before any transformation.
+    pub fn frames(&self) -> &[WebpFrame] {
+        &self.frames
+    }
+
+    /// Encode the job into an animated WebP held in memory.
+    ///
+    /// # Errors
+    /// See [`WebpEncoder::encode_with_options`].
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let frames = self.processed_frames();
+        WebpEncoder::encode_with_options(&frames, &self.options)
+    }
+
+    /// Returns the frames with all transformations applied.
+    fn processed_frames(&self) -> Cow<'_, [WebpFrame]> {
+        let (leading, trailing) = self.trim;
+        let end = self.frames.len().saturating_sub(trailing);
+        let frames = &self.frames[leading.min(end)..end];
+
+        let mut processed =
+            if self.resize.is_none() && self.crop.is_none() && self.overlays.is_empty() {
+                Cow::Borrowed(frames)
+            } else {
+                Cow::Owned(
+                    frames
+                        .iter()
+                        .enumerate()
+                        .map(|(index, frame)| self.process(index, frame))
+                        .collect(),
+                )
+            };
+
+        if self.boomerang && processed.len() > 2 {
+            let mut frames = processed.into_owned();
+            let reversed: Vec<WebpFrame> =
+                frames[1..frames.len() - 1].iter().rev().cloned().collect();
+            frames.extend(reversed);
+            processed = Cow::Owned(frames);
+        }
+        processed
+    }
+
+    /// Apply the job's transformations to the frame at `index`.
+    fn process(&self, index: usize, frame: &WebpFrame) -> WebpFrame {
+        let mut image = match self.crop {
+            Some((x, y, width, height)) => frame.image.crop_imm(x, y, width, height),
+            None => frame.image.clone(),
+        };
+        if let Some((width, height, filter)) = self.resize {
+            image = image.resize(width, height, filter.into());
+        }
+        if !self.overlays.is_empty() {
+            let mut rgba = image.to_rgba8();
+            for overlay in &self.overlays {
+                match overlay {
+                    Overlay::Image(top, x, y) => imageops::overlay(&mut rgba, top.as_ref(), *x, *y),
+                    Overlay::Closure(draw) => draw(index, &mut rgba),
+                }
+            }
+            image = DynamicImage::ImageRgba8(rgba);
+        }
+        WebpFrame {
+            image,
+            duration: frame.duration,
+        }
+    }
+
+    /// Encode the job and write the animated WebP to `path`.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode); additionally I/O failures while writing.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let data = self.encode()?;
+        Ok(fs::write(path, data)?)
+    }
+
+    /// Encode the job and write the animated WebP into `writer`, e.g. to
+    /// upload it without touching disk.
+    ///
+    /// # Errors
+    /// See [`encode`](Self::encode); additionally I/O failures while writing.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        let data = self.encode()?;
+        writer.write_all(&data)?;
+        Ok(writer.flush()?)
+    }
+}
+
+impl fmt::Debug for EncodeJob<'_> {
+    /// Summarizes the frames and overlays instead of dumping pixels.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncodeJob")
+            .field("frame_count", &self.frames.len())
+            .field("options", &self.options)
+            .field("resize", &self.resize)
+            .field("crop", &self.crop)
+            .field("trim", &self.trim)
+            .field("boomerang", &self.boomerang)
+            .field("overlay_count", &self.overlays.len())
+            .finish()
+    }
+}
+
+/// Pack an RGBA color into the byte order libwebp uses for the ANIM
+/// background color (blue in the most significant byte, alpha in the least).
+fn bgcolor([r, g, b, a]: [u8; 4]) -> u32 {
+    u32::from_be_bytes([b, g, r, a])
+}
+
+// SAFETY: the encoder exclusively owns its libwebp state, which has no thread
+// affinity, so it can be moved to another thread; every method that touches
+// it takes `&mut self`.
+unsafe impl Send for WebpEncoder {}
+
+impl Drop for WebpEncoder {
+    fn drop(&mut self) {
+        unsafe { WebPAnimEncoderDelete(self.enc) };
+    }
+}
@@ -0,0 +1,114 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The error type returned by every fallible operation in this crate.
+///
+/// The enum is `#[non_exhaustive]`: new categories may be added in minor
+/// releases, so matches must include a wildcard arm. Underlying I/O, image and
+/// export errors are available through [`Error::source`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WebpAnimationError {
+    /// Reading or writing a file or stream failed.
+    Io(io::Error),
+    /// Converting or saving an image failed.
+    Image(image::ImageError),
+    /// libwebp could not decode the input.
+    Decode(String),
+    /// libwebp could not encode the frames.
+    Encode(String),
+    /// libwebp could not parse or edit the container chunks.
+    Mux(String),
+    /// The animation has no frames.
+    NoFrames,
+    /// An argument was out of range or otherwise invalid.
+    InvalidArgument(String),
+    /// Writing another file format (GIF, APNG, video) failed.
+    Export(Box<dyn Error + Send + Sync>),
+    /// Reading a rendered frame back from the GPU failed.
+    Capture(String),
+    /// A frame cache is malformed, has an unsupported version or needs a
+    /// disabled feature.
+    Cache(String),
+    /// Loading was cancelled through its [`LoadHandle`](crate::LoadHandle).
+    Cancelled,
+    /// Downloading an animation failed, timed out or exceeded the size limit.
+    #[cfg(any(feature = "http", target_arch = "wasm32"))]
+    Http(String),
+    /// Opening or reading a MIDI input failed.
+    #[cfg(feature = "midi")]
+    Midi(String),
+    /// Watching a file for changes failed.
+    #[cfg(feature = "hot-reload")]
+    Watch(notify::Error),
+}
+
+/// A `Result` using [`WebpAnimationError`] as the error type.
+pub type Result<T> = std::result::Result<T, WebpAnimationError>;
+
+#[cfg(any(feature = "gif", feature = "apng", feature = "ffmpeg"))]
+impl WebpAnimationError {
+    /// Wraps an error reported by an export backend.
+    pub(crate) fn export<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> Self {
+        WebpAnimationError::Export(error.into())
+    }
+}
+
+impl fmt::Display for WebpAnimationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebpAnimationError::Io(e) => write!(f, "I/O error: {}", e),
+            WebpAnimationError::Image(e) => write!(f, "image error: {}", e),
+            WebpAnimationError::Decode(message) => write!(f, "decoding failed: {}", message),
+            WebpAnimationError::Encode(message) => write!(f, "encoding failed: {}", message),
+            WebpAnimationError::Mux(message) => write!(f, "container editing failed: {}", message),
+            WebpAnimationError::NoFrames => write!(f, "no frames found in the animation"),
+            WebpAnimationError::InvalidArgument(message) => {
+                write!(f, "invalid argument: {}", message)
+            }
+            WebpAnimationError::Export(e) => write!(f, "export failed: {}", e),
+            WebpAnimationError::Capture(message) => write!(f, "frame capture failed: {}", message),
+            WebpAnimationError::Cache(message) => write!(f, "invalid frame cache: {}", message),
+            WebpAnimationError::Cancelled => write!(f, "loading was cancelled"),
+            #[cfg(any(feature = "http", target_arch = "wasm32"))]
+            WebpAnimationError::Http(message) => write!(f, "download failed: {}", message),
+            #[cfg(feature = "midi")]
+            WebpAnimationError::Midi(message) => write!(f, "MIDI input failed: {}", message),
+            #[cfg(feature = "hot-reload")]
+            WebpAnimationError::Watch(e) => write!(f, "file watching failed: {}", e),
+        }
+    }
+}
+
+impl Error for WebpAnimationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WebpAnimationError::Io(e) => Some(e),
+            WebpAnimationError::Image(e) => Some(e),
+            WebpAnimationError::Export(e) => Some(e.as_ref()),
+            #[cfg(feature = "hot-reload")]
+            WebpAnimationError::Watch(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WebpAnimationError {
+    fn from(error: io::Error) -> Self {
+        WebpAnimationError::Io(error)
+    }
+}
+
+impl From<image::ImageError> for WebpAnimationError {
+    fn from(error: image::ImageError) -> Self {
+        WebpAnimationError::Image(error)
+    }
+}
+
+#[cfg(feature = "hot-reload")]
+impl From<notify::Error> for WebpAnimationError {
+    fn from(error: notify::Error) -> Self {
+        WebpAnimationError::Watch(error)
+    }
+}
@@ -0,0 +1,360 @@
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use image::{imageops, DynamicImage, GenericImageView, ImageFormat, RgbaImage};
+use std::fs;
+#[cfg(any(feature = "gif", feature = "apng"))]
+use std::fs::File;
+#[cfg(any(feature = "gif", feature = "apng"))]
+use std::io::BufWriter;
+#[cfg(feature = "ffmpeg")]
+use std::io::Write;
+use std::path::Path;
+#[cfg(feature = "ffmpeg")]
+use std::process::{Command, Stdio};
+#[cfg(feature = "ffmpeg")]
+use std::time::Duration;
+
+/// Writes every frame to `dir` as numbered image files plus a timing manifest.
+///
+/// Frames are named `frame_0000.png`, `frame_0001.png`, … (or `.jpg` for JPEG).
+/// The manifest `manifest.json` lists each file with its duration in milliseconds:
+///
+/// ```json
+/// {"frames":[{"file":"frame_0000.png","duration_ms":100}]}
+/// ```
+///
+/// # Parameters
+///
+/// - `frames`: The frames to export.
+/// - `dir`: The output directory. It is created if it does not exist.
+/// - `format`: The image format, typically `ImageFormat::Png` or `ImageFormat::Jpeg`.
+///
+/// # Errors
+///
+/// Returns an error if the directory or any file cannot be written.
+pub fn export_frames<P: AsRef<Path>>(
+    frames: &[WebpFrame],
+    dir: P,
+    format: ImageFormat,
+) -> Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let extension = match format {
+        ImageFormat::Jpeg => "jpg",
+        ImageFormat::Png => "png",
+        ImageFormat::Bmp => "bmp",
+        ImageFormat::Tiff => "tiff",
+        _ => {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "unsupported export format: {:?}",
+                format
+            )))
+        }
+    };
+
+    let mut entries = Vec::with_capacity(frames.len());
+    for (i, frame) in frames.iter().enumerate() {
+        let file = format!("frame_{:04}.{}", i, extension);
+
+        // JPEG has no alpha channel.
+        let image = match format {
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(frame.image.to_rgb8()),
+            _ => frame.image.clone(),
+        };
+        image.save_with_format(dir.join(&file), format)?;
+
+        entries.push(format!(
+            "{{\"file\":\"{}\",\"duration_ms\":{}}}",
+            file,
+            frame.duration.as_millis()
+        ));
+    }
+
+    let manifest = format!("{{\"frames\":[{}]}}\n", entries.join(","));
+    Ok(fs::write(dir.join("manifest.json"), manifest)?)
+}
+
+/// Packs every frame into a single sprite-sheet PNG plus a JSON manifest, for
+/// engines that cannot play animated WebP.
+///
+/// Frames are laid out left to right, top to bottom in cells the size of the
+/// largest frame. The manifest is written next to the image with a `.json`
+/// extension and lists each frame's rectangle and duration:
+///
+/// ```json
+/// {"image":"sheet.png","frames":[{"x":0,"y":0,"w":64,"h":64,"duration_ms":100}]}
+/// ```
+///
+/// # Parameters
+///
+/// - `frames`: The frames to pack.
+/// - `path`: The destination of the sprite-sheet PNG.
+/// - `columns`: The number of frames per row, or `None` for a roughly square sheet.
+///
+/// # Errors
+///
+/// Returns an error if there are no frames or a file cannot be written.
+pub fn export_sprite_sheet<P: AsRef<Path>>(
+    frames: &[WebpFrame],
+    path: P,
+    columns: Option<u32>,
+) -> Result<()> {
+    if frames.is_empty() {
+        return Err(WebpAnimationError::NoFrames);
+    }
+    let path = path.as_ref();
+
+    let count = frames.len() as u32;
+    let columns = columns
+        .unwrap_or_else(|| (count as f64).sqrt().ceil() as u32)
+        .clamp(1, count);
+    let rows = count.div_ceil(columns);
+    let cell_width = frames.iter().map(|f| f.image.width()).max().unwrap_or(0);
+    let cell_height = frames.iter().map(|f| f.image.height()).max().unwrap_or(0);
+
+    let mut sheet = RgbaImage::new(columns * cell_width, rows * cell_height);
+    let mut entries = Vec::with_capacity(frames.len());
+    for (i, frame) in frames.iter().enumerate() {
+        let x = i as u32 % columns * cell_width;
+        let y = i as u32 / columns * cell_height;
+        imageops::replace(&mut sheet, &frame.image.to_rgba8(), x, y);
+        entries.push(format!(
+            "{{\"x\":{},\"y\":{},\"w\":{},\"h\":{},\"duration_ms\":{}}}",
+            x,
+            y,
+            frame.image.width(),
+            frame.image.height(),
+            frame.duration.as_millis()
+        ));
+    }
+
+    sheet.save_with_format(path, ImageFormat::Png)?;
+
+    let image_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let manifest = format!(
+        "{{\"image\":\"{}\",\"frames\":[{}]}}\n",
+        image_name,
+        entries.join(",")
+    );
+    Ok(fs::write(path.with_extension("json"), manifest)?)
+}
+
+/// Writes the frames to `path` as an infinitely looping animated GIF.
+///
+/// Each frame is quantized to a 256-color palette with NeuQuant. GIF delays have
+/// a resolution of 10 ms, so durations are rounded to the nearest multiple.
+///
+/// # Parameters
+///
+/// - `frames`: The frames to export. All frames must have the same size.
+/// - `path`: The destination file.
+/// - `speed`: Quantization speed from 1 (best quality) to 30 (fastest).
+///
+/// # Errors
+///
+/// Returns an error if there are no frames, the canvas exceeds the GIF size limit
+/// of 65535 pixels, or the file cannot be written.
+#[cfg(feature = "gif")]
+pub fn export_gif<P: AsRef<Path>>(frames: &[WebpFrame], path: P, speed: i32) -> Result<()> {
+    let first = frames.first().ok_or(WebpAnimationError::NoFrames)?;
+    let (width, height) = (first.image.width(), first.image.height());
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(WebpAnimationError::InvalidArgument(format!(
+            "canvas {}x{} is too large for GIF",
+            width, height
+        )));
+    }
+
+    let file = File::create(path)?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+        .map_err(WebpAnimationError::export)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(WebpAnimationError::export)?;
+
+    for frame in frames {
+        let mut rgba = frame.image.to_rgba8();
+        if rgba.dimensions() != (width, height) {
+            return Err(mismatched_frame_size());
+        }
+        let mut gif_frame =
+            gif::Frame::from_rgba_speed(width as u16, height as u16, &mut rgba, speed.clamp(1, 30));
+        gif_frame.delay = ((frame.duration.as_millis() + 5) / 10).min(u16::MAX as u128) as u16;
+        gif_frame.dispose = gif::DisposalMethod::Background;
+        encoder
+            .write_frame(&gif_frame)
+            .map_err(WebpAnimationError::export)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the frames to `path` as an infinitely looping animated PNG (APNG).
+///
+/// Frames are stored losslessly as 8-bit RGBA with millisecond delays.
+///
+/// # Parameters
+///
+/// - `frames`: The frames to export. All frames must have the same size.
+/// - `path`: The destination file.
+///
+/// # Errors
+///
+/// Returns an error if there are no frames, the frames differ in size, or the
+/// file cannot be written.
+#[cfg(feature = "apng")]
+pub fn export_apng<P: AsRef<Path>>(frames: &[WebpFrame], path: P) -> Result<()> {
+    let first = frames.first().ok_or(WebpAnimationError::NoFrames)?;
+    let (width, height) = (first.image.width(), first.image.height());
+
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .map_err(WebpAnimationError::export)?;
+
+    let mut writer = encoder.write_header().map_err(WebpAnimationError::export)?;
+    for frame in frames {
+        let rgba = frame.image.to_rgba8();
+        if rgba.dimensions() != (width, height) {
+            return Err(mismatched_frame_size());
+        }
+        let delay_ms = frame.duration.as_millis().min(u16::MAX as u128) as u16;
+        writer
+            .set_frame_delay(delay_ms, 1000)
+            .map_err(WebpAnimationError::export)?;
+        writer
+            .write_image_data(rgba.as_raw())
+            .map_err(WebpAnimationError::export)?;
+    }
+    writer.finish().map_err(WebpAnimationError::export)
+}
+
+/// Video container and codec combinations supported by [`export_video`].
+#[cfg(feature = "ffmpeg")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoFormat {
+    /// H.264 in an MP4 container. Transparency is discarded.
+    Mp4,
+    /// VP9 in a WebM container, keeping the alpha channel.
+    WebM,
+}
+
+#[cfg(feature = "ffmpeg")]
+impl VideoFormat {
+    /// Returns the ffmpeg output arguments selecting the codec and pixel format.
+    fn codec_args(self) -> &'static [&'static str] {
+        match self {
+            // yuv420p needs even dimensions, so pad odd canvases by one pixel.
+            VideoFormat::Mp4 => &[
+                "-vf",
+                "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+                "-c:v",
+                "libx264",
+                "-pix_fmt",
+                "yuv420p",
+                "-movflags",
+                "+faststart",
+            ],
+            VideoFormat::WebM => &["-c:v", "libvpx-vp9", "-pix_fmt", "yuva420p"],
+        }
+    }
+}
+
+/// Pipes the frames into an `ffmpeg` process to produce an MP4 or WebM video.
+///
+/// Video files use a constant frame rate, so each frame is repeated for as many
+/// output frames as its duration covers at `fps`. `ffmpeg` must be on the `PATH`.
+///
+/// # Parameters
+///
+/// - `frames`: The frames to export. All frames must have the same size.
+/// - `path`: The destination file.
+/// - `format`: The container and codec to use.
+/// - `fps`: The constant output frame rate.
+///
+/// # Errors
+///
+/// Returns an error if there are no frames, `ffmpeg` cannot be started, or it
+/// exits with a failure status.
+#[cfg(feature = "ffmpeg")]
+pub fn export_video<P: AsRef<Path>>(
+    frames: &[WebpFrame],
+    path: P,
+    format: VideoFormat,
+    fps: f64,
+) -> Result<()> {
+    let first = frames.first().ok_or(WebpAnimationError::NoFrames)?;
+    let (width, height) = (first.image.width(), first.image.height());
+    if !(fps.is_finite() && fps > 0.0) {
+        return Err(WebpAnimationError::InvalidArgument(format!(
+            "invalid frame rate: {}",
+            fps
+        )));
+    }
+
+    let mut child = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+        ])
+        .arg("-s")
+        .arg(format!("{}x{}", width, height))
+        .arg("-r")
+        .arg(fps.to_string())
+        .args(["-i", "-"])
+        .args(format.codec_args())
+        .arg(path.as_ref())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| WebpAnimationError::export(format!("failed to start ffmpeg: {}", e)))?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| WebpAnimationError::export("failed to open ffmpeg stdin"))?;
+    let mut elapsed = Duration::ZERO;
+    let mut written = 0u64;
+    for frame in frames {
+        let rgba = frame.image.to_rgba8();
+        if rgba.dimensions() != (width, height) {
+            return Err(mismatched_frame_size());
+        }
+
+        // Emit output frames until the video catches up with the frame's end time.
+        elapsed += frame.duration;
+        let target = (elapsed.as_secs_f64() * fps).round() as u64;
+        while written < target {
+            stdin.write_all(rgba.as_raw())?;
+            written += 1;
+        }
+    }
+    drop(stdin);
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(WebpAnimationError::export(format!(
+            "ffmpeg exited with {}",
+            status
+        )))
+    }
+}
+
+/// The error returned when a frame's size differs from the first frame's.
+#[cfg(any(feature = "gif", feature = "apng", feature = "ffmpeg"))]
+fn mismatched_frame_size() -> WebpAnimationError {
+    WebpAnimationError::InvalidArgument("all frames must have the same size".to_string())
+}
@@ -0,0 +1,281 @@
+#[cfg(feature = "avif")]
+use crate::avif;
+use crate::decoder::{ColorMode, WebpDecoder};
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use crate::loader;
+#[cfg(feature = "gif")]
+use image::codecs::gif::GifDecoder;
+#[cfg(feature = "apng")]
+use image::codecs::png::PngDecoder;
+#[cfg(any(feature = "gif", feature = "apng"))]
+use image::{AnimationDecoder, Frames};
+use image::{DynamicImage, GenericImageView};
+use std::fs::{self, File};
+use std::io::{BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long each frame of an image sequence is shown.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SequenceTiming {
+    /// Every frame is shown for `1 / fps` seconds.
+    Fps(f64),
+    /// One duration per frame, in playback order.
+    Durations(Vec<Duration>),
+    /// A text file listing one duration in milliseconds per line, in playback
+    /// order. Blank lines and lines starting with `#` are ignored.
+    File(PathBuf),
+}
+
+impl SequenceTiming {
+    /// Returns the duration of each of `count` frames.
+    fn durations(&self, count: usize) -> Result<Vec<Duration>> {
+        let durations = match self {
+            SequenceTiming::Fps(fps) => {
+                if !(fps.is_finite() && *fps > 0.0) {
+                    return Err(WebpAnimationError::InvalidArgument(format!(
+                        "invalid frame rate: {}",
+                        fps
+                    )));
+                }
+                vec![Duration::from_secs_f64(1.0 / fps); count]
+            }
+            SequenceTiming::Durations(durations) => durations.clone(),
+            SequenceTiming::File(path) => fs::read_to_string(path)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    line.parse().map(Duration::from_millis).map_err(|_| {
+                        WebpAnimationError::InvalidArgument(format!(
+                            "invalid duration in timing file: {:?}",
+                            line
+                        ))
+                    })
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        if durations.len() != count {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "expected {} frame durations, got {}",
+                count,
+                durations.len()
+            )));
+        }
+        Ok(durations)
+    }
+}
+
+/// Animation container formats recognised from a file's leading bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Animated or still WebP, decoded by libwebp.
+    Webp,
+    /// Animated GIF.
+    Gif,
+    /// Animated (or still) PNG.
+    Png,
+    /// AVIF image sequence or still image.
+    Avif,
+}
+
+impl Format {
+    /// Identifies the format from the first bytes of a file, falling back to
+    /// WebP so that libwebp reports unrecognised input.
+    fn sniff(header: &[u8]) -> Self {
+        if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+            Format::Gif
+        } else if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Format::Png
+        } else if header.get(4..12) == Some(b"ftypavis") || header.get(4..12) == Some(b"ftypavif") {
+            Format::Avif
+        } else {
+            Format::Webp
+        }
+    }
+}
+
+/// Decodes the animation at `path`, detecting its format from the file contents
+/// rather than the extension.
+pub(crate) fn decode_file<P: AsRef<Path>>(
+    path: P,
+    color_mode: ColorMode,
+) -> Result<Vec<WebpFrame>> {
+    let path = path.as_ref();
+    let mut header = Vec::with_capacity(12);
+    File::open(path)?.take(12).read_to_end(&mut header)?;
+
+    let _slot = loader::decode_slot();
+    match Format::sniff(&header) {
+        Format::Webp => WebpDecoder::decode_with_color_mode(path, color_mode),
+        Format::Gif => decode_gif(open(path)?, color_mode),
+        Format::Png => decode_apng(open(path)?, color_mode),
+        Format::Avif => decode_avif_file(path, color_mode),
+    }
+}
+
+/// Decodes an in-memory animation, detecting its format from the leading bytes.
+pub(crate) fn decode_bytes(data: &[u8], color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    let _slot = loader::decode_slot();
+    match Format::sniff(data) {
+        Format::Webp => WebpDecoder::decode_bytes(data, color_mode),
+        Format::Gif => decode_gif(Cursor::new(data), color_mode),
+        Format::Png => decode_apng(Cursor::new(data), color_mode),
+        Format::Avif => decode_avif(data, color_mode),
+    }
+}
+
+/// Opens `path` for one of the `image` animation decoders.
+fn open(path: &Path) -> Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(path)?))
+}
+
+/// Loads the numbered PNG and JPEG files in `dir` as frames.
+///
+/// Files are ordered by the number at the end of their name, so `frame_2.png`
+/// comes before `frame_10.png` even without zero padding. Other files are
+/// ignored.
+pub(crate) fn decode_sequence<P: AsRef<Path>>(
+    dir: P,
+    timing: &SequenceTiming,
+) -> Result<Vec<WebpFrame>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_frame = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                ["png", "jpg", "jpeg"]
+                    .iter()
+                    .any(|frame| extension.eq_ignore_ascii_case(frame))
+            });
+        if is_frame {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        return Err(WebpAnimationError::NoFrames);
+    }
+    paths.sort_by_cached_key(|path| sequence_key(path));
+
+    let durations = timing.durations(paths.len())?;
+    let mut frames: Vec<WebpFrame> = Vec::with_capacity(paths.len());
+    for (path, duration) in paths.iter().zip(durations) {
+        let image = DynamicImage::ImageRgba8(image::open(path)?.to_rgba8());
+        if let Some(first) = frames.first() {
+            if image.dimensions() != first.image.dimensions() {
+                return Err(WebpAnimationError::InvalidArgument(format!(
+                    "{} does not match the size of the first frame",
+                    path.display()
+                )));
+            }
+        }
+        frames.push(WebpFrame { image, duration });
+        loader::frame_decoded(frames.len(), Some(paths.len()))?;
+    }
+    Ok(frames)
+}
+
+/// Splits a file name into the text before its trailing number and that number.
+fn sequence_key(path: &Path) -> (String, u64, String) {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+    let number = stem[prefix.len()..].parse().unwrap_or(0);
+    (prefix.to_string(), number, stem)
+}
+
+/// Decodes an animated GIF.
+#[cfg(feature = "gif")]
+fn decode_gif<R: Read>(reader: R, color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    let decoder = GifDecoder::new(reader)?;
+    collect_frames(decoder.into_frames(), color_mode)
+}
+
+/// GIF input needs the `gif` feature.
+#[cfg(not(feature = "gif"))]
+fn decode_gif<R>(_reader: R, _color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    Err(WebpAnimationError::Decode(
+        "GIF input requires the `gif` feature".into(),
+    ))
+}
+
+/// Decodes an animated PNG. A PNG without animation control chunks becomes a
+/// single frame.
+#[cfg(feature = "apng")]
+fn decode_apng<R: Read>(reader: R, color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    let decoder = PngDecoder::new(reader)?;
+    collect_frames(decoder.apng().into_frames(), color_mode)
+}
+
+/// APNG input needs the `apng` feature.
+#[cfg(not(feature = "apng"))]
+fn decode_apng<R>(_reader: R, _color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    Err(WebpAnimationError::Decode(
+        "PNG input requires the `apng` feature".into(),
+    ))
+}
+
+/// Decodes an AVIF image sequence file.
+#[cfg(feature = "avif")]
+fn decode_avif_file(path: &Path, color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    avif::decode_file(path, color_mode)
+}
+
+/// AVIF input needs the `avif` feature.
+#[cfg(not(feature = "avif"))]
+fn decode_avif_file(_path: &Path, color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    decode_avif(&[], color_mode)
+}
+
+/// Decodes an in-memory AVIF image sequence.
+#[cfg(feature = "avif")]
+fn decode_avif(data: &[u8], color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    avif::decode_bytes(data, color_mode)
+}
+
+/// AVIF input needs the `avif` feature.
+#[cfg(not(feature = "avif"))]
+fn decode_avif(_data: &[u8], _color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    Err(WebpAnimationError::Decode(
+        "AVIF input requires the `avif` feature".into(),
+    ))
+}
+
+/// Converts the frames of an `image` animation decoder.
+///
+/// The decoder composites every frame onto the full canvas, applying the
+/// format's disposal and blend operations, so the frames can be played back
+/// like those decoded by libwebp.
+#[cfg(any(feature = "gif", feature = "apng"))]
+fn collect_frames(frames: Frames<'_>, color_mode: ColorMode) -> Result<Vec<WebpFrame>> {
+    let frames = frames
+        .enumerate()
+        .map(|(index, frame)| {
+            let frame = frame?;
+            let (numer, denom) = frame.delay().numer_denom_ms();
+            // Like browsers, treat a zero delay as the conventional 100 ms.
+            let duration = match Duration::from_millis(numer as u64) / denom.max(1) {
+                duration if duration.is_zero() => Duration::from_millis(100),
+                duration => duration,
+            };
+            let image = color_mode
+                .convert_rgba(frame.into_buffer())
+                .ok_or_else(|| {
+                    WebpAnimationError::Decode("failed to create image from frame".into())
+                })?;
+            loader::frame_decoded(index + 1, None)?;
+            Ok(WebpFrame { image, duration })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if frames.is_empty() {
+        return Err(WebpAnimationError::NoFrames);
+    }
+    Ok(frames)
+}
@@ -1,6 +1,28 @@
-use nannou::image::DynamicImage;
+use crate::error::Result;
+#[cfg(feature = "serde")]
+use crate::utils::create_image_from_raw;
+use image::{Delay, DynamicImage, Frame, GenericImageView, Rgba};
+#[cfg(feature = "serde")]
+use std::borrow::Cow;
+use std::fmt;
+use std::path::Path;
 use std::time::Duration;
 
+/// The tight bounding box of a frame's non-transparent pixels, in pixels
+/// measured from the top-left corner of the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpaqueBounds {
+    /// Column of the leftmost visible pixel.
+    pub x: u32,
+    /// Row of the topmost visible pixel.
+    pub y: u32,
+    /// Number of columns spanned by the visible pixels.
+    pub width: u32,
+    /// Number of rows spanned by the visible pixels.
+    pub height: u32,
+}
+
 /// Represents a single frame in a WebP animation, containing the image data and its display duration.
 #[derive(Clone)]
 pub struct WebpFrame {
@@ -9,3 +31,171 @@ pub struct WebpFrame {
     /// The duration for which this frame should be displayed.
     pub duration: Duration,
 }
+
+impl WebpFrame {
+    /// Saves the frame's image to `path`.
+    ///
+    /// The image format is inferred from the file extension, e.g. `.png`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the format is unsupported or the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(self.image.save(path)?)
+    }
+
+    /// Computes the tight bounding box of the pixels with non-zero alpha, or
+    /// `None` if the frame is fully transparent.
+    ///
+    /// This scans every pixel; `WebpAnimationPlayer` caches the result per frame.
+    pub fn opaque_bounds(&self) -> Option<OpaqueBounds> {
+        let width = self.image.width();
+        let converted;
+        let pixels = match &self.image {
+            DynamicImage::ImageRgba8(buffer) => buffer.as_raw(),
+            DynamicImage::ImageBgra8(buffer) => buffer.as_raw(),
+            image => {
+                converted = image.to_rgba8();
+                converted.as_raw()
+            }
+        };
+
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (index, pixel) in pixels.chunks_exact(4).enumerate() {
+            if pixel[3] == 0 {
+                continue;
+            }
+            let (x, y) = (index as u32 % width, index as u32 / width);
+            bounds = Some(match bounds {
+                Some((left, top, right, bottom)) => {
+                    (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                }
+                None => (x, y, x, y),
+            });
+        }
+
+        bounds.map(|(left, top, right, bottom)| OpaqueBounds {
+            x: left,
+            y: top,
+            width: right - left + 1,
+            height: bottom - top + 1,
+        })
+    }
+
+    /// Returns the RGBA color of the pixel at (`x`, `y`), measured in pixels from
+    /// the top-left corner, or `None` if the position lies outside the image.
+    pub fn pixel_at(&self, x: u32, y: u32) -> Option<Rgba<u8>> {
+        if self.image.in_bounds(x, y) {
+            Some(self.image.get_pixel(x, y))
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts a frame produced by an `image` animation decoder, keeping its delay.
+impl From<Frame> for WebpFrame {
+    fn from(frame: Frame) -> Self {
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let duration = Duration::from_micros(numer as u64 * 1000 / denom.max(1) as u64);
+        Self {
+            image: DynamicImage::ImageRgba8(frame.into_buffer()),
+            duration,
+        }
+    }
+}
+
+/// Converts to an `image` crate frame, e.g. for `image`'s GIF encoder. The delay
+/// keeps microsecond precision.
+impl From<&WebpFrame> for Frame {
+    fn from(frame: &WebpFrame) -> Self {
+        let micros = u32::try_from(frame.duration.as_micros()).unwrap_or(u32::MAX);
+        let delay = Delay::from_numer_denom_ms(micros, 1000);
+        Frame::from_parts(frame.image.to_rgba8(), 0, 0, delay)
+    }
+}
+
+impl From<WebpFrame> for Frame {
+    fn from(frame: WebpFrame) -> Self {
+        Frame::from(&frame)
+    }
+}
+
+impl fmt::Debug for WebpFrame {
+    /// Summarizes the image instead of dumping its pixels.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebpFrame")
+            .field("width", &self.image.width())
+            .field("height", &self.image.height())
+            .field("color", &self.image.color())
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+impl fmt::Display for WebpFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} frame, {} ms",
+            self.image.width(),
+            self.image.height(),
+            self.duration.as_millis()
+        )
+    }
+}
+
+/// Serialized form of a frame: raw RGBA pixels plus the display duration.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RawFrame<'a> {
+    width: u32,
+    height: u32,
+    rgba: Cow<'a, [u8]>,
+    duration: Duration,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WebpFrame {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let rgba = match self.image.as_rgba8() {
+            Some(buffer) => Cow::Borrowed(buffer.as_raw().as_slice()),
+            None => Cow::Owned(self.image.to_rgba8().into_raw()),
+        };
+        let raw = RawFrame {
+            width: self.image.width(),
+            height: self.image.height(),
+            rgba,
+            duration: self.duration,
+        };
+        serde::Serialize::serialize(&raw, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WebpFrame {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let raw: RawFrame = serde::Deserialize::deserialize(deserializer)?;
+        let image = create_image_from_raw(raw.width, raw.height, raw.rgba.into_owned())
+            .ok_or_else(|| serde::de::Error::custom("RGBA buffer does not match the frame size"))?;
+        Ok(Self {
+            image,
+            duration: raw.duration,
+        })
+    }
+}
+
+/// Frames are equal if they have the same duration, color type, dimensions and pixels.
+impl PartialEq for WebpFrame {
+    fn eq(&self, other: &Self) -> bool {
+        self.duration == other.duration
+            && self.image.color() == other.image.color()
+            && self.image.dimensions() == other.image.dimensions()
+            && self.image.as_bytes() == other.image.as_bytes()
+    }
+}
@@ -0,0 +1,80 @@
+use crate::data::WebpAnimationData;
+use crate::decoder::ColorMode;
+use crate::error::Result;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+/// Watches an animation file and re-decodes it after it changes on disk.
+pub(crate) struct FileWatcher {
+    /// Keeps the OS watch alive; dropping it stops the events.
+    _watcher: RecommendedWatcher,
+    /// File system events for the watched directory.
+    events: Receiver<notify::Result<notify::Event>>,
+    /// Canonical path of the watched file.
+    path: PathBuf,
+    /// Pixel layout the file is decoded to.
+    color_mode: ColorMode,
+}
+
+impl FileWatcher {
+    /// Starts watching the file at `path`.
+    ///
+    /// The parent directory is watched rather than the file itself, because
+    /// many tools save by writing a temporary file and renaming it over the
+    /// original, which would end a watch on the old file.
+    pub(crate) fn new<P: AsRef<Path>>(path: P, color_mode: ColorMode) -> Result<Self> {
+        let path = path.as_ref().canonicalize()?;
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        let directory = path.parent().unwrap_or(&path);
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            path,
+            color_mode,
+        })
+    }
+
+    /// Returns the re-decoded animation if the file changed since the last call.
+    ///
+    /// Pending events are coalesced into a single reload. A file that cannot be
+    /// decoded, e.g. because it is still being written, is skipped and picked
+    /// up again by the next change event.
+    pub(crate) fn poll(&self) -> Option<WebpAnimationData> {
+        self.poll_with(|path, color_mode| {
+            WebpAnimationData::from_file_with_color_mode(path, color_mode)
+        })
+    }
+
+    /// Like [`poll`](Self::poll), decoding the changed file with `decode`.
+    pub(crate) fn poll_with<T, F>(&self, decode: F) -> Option<T>
+    where
+        F: FnOnce(&Path, ColorMode) -> Result<T>,
+    {
+        // Counting drains every pending event, unlike `any`.
+        let changes = self
+            .events
+            .try_iter()
+            .flatten()
+            .filter(|event| {
+                matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|path| path == &self.path)
+            })
+            .count();
+        if changes == 0 {
+            return None;
+        }
+
+        match decode(&self.path, self.color_mode) {
+            Ok(decoded) => Some(decoded),
+            Err(_error) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(path = %self.path.display(), error = %_error, "hot reload failed");
+                None
+            }
+        }
+    }
+}
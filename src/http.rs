@@ -0,0 +1,180 @@
+#[cfg(feature = "nannou")]
+use crate::animation::WebpAnimationPlayer;
+use crate::data::WebpAnimationData;
+use crate::error::{Result, WebpAnimationError};
+use futures_channel::oneshot;
+#[cfg(feature = "nannou")]
+use nannou::App;
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+
+/// Limits applied when downloading an animation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// Maximum time for the whole request, from connecting until the last byte
+    /// is received. Defaults to 30 seconds.
+    pub timeout: Duration,
+    /// Largest response body accepted, in bytes. Larger downloads are aborted
+    /// before they are decoded. Defaults to 64 MiB.
+    pub max_bytes: u64,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Downloads the body of `url`, enforcing `options`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(options))
+)]
+fn download(url: &str, options: &HttpOptions) -> Result<Vec<u8>> {
+    let agent = ureq::AgentBuilder::new().timeout(options.timeout).build();
+    let response = agent
+        .get(url)
+        .call()
+        .map_err(|e| WebpAnimationError::Http(e.to_string()))?;
+
+    // Reject oversized responses up front when the server announces the size,
+    // and stop reading once the limit is passed when it does not.
+    let too_large = || {
+        WebpAnimationError::Http(format!(
+            "response exceeds the limit of {} bytes",
+            options.max_bytes
+        ))
+    };
+    let length = response
+        .header("Content-Length")
+        .and_then(|length| length.parse::<u64>().ok());
+    if length.is_some_and(|length| length > options.max_bytes) {
+        return Err(too_large());
+    }
+
+    let mut data = Vec::with_capacity(length.unwrap_or(0) as usize);
+    response
+        .into_reader()
+        .take(options.max_bytes + 1)
+        .read_to_end(&mut data)?;
+    if data.len() as u64 > options.max_bytes {
+        return Err(too_large());
+    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(bytes = data.len(), "downloaded animation");
+    Ok(data)
+}
+
+impl WebpAnimationData {
+    /// Downloads and decodes the animation at `url` with the default
+    /// [`HttpOptions`], blocking until it is done.
+    ///
+    /// The format is detected from the downloaded bytes like in
+    /// [`from_file`](Self::from_file).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or times out, the response exceeds
+    /// the size limit, or the body cannot be decoded.
+    pub fn from_url(url: &str) -> Result<Self> {
+        Self::from_url_with_options(url, &HttpOptions::default())
+    }
+
+    /// Downloads and decodes the animation at `url` with custom limits, blocking
+    /// until it is done.
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The `http` or `https` URL of the animation.
+    /// - `options`: The timeout and size limit of the download.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or times out, the response exceeds
+    /// the size limit, or the body cannot be decoded.
+    pub fn from_url_with_options(url: &str, options: &HttpOptions) -> Result<Self> {
+        Self::from_bytes(&download(url, options)?)
+    }
+
+    /// Downloads and decodes the animation at `url` without blocking the caller.
+    ///
+    /// The work runs on a background thread, so the returned future does not
+    /// need a particular async runtime.
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The `http` or `https` URL of the animation.
+    /// - `options`: The timeout and size limit of the download.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or times out, the response exceeds
+    /// the size limit, or the body cannot be decoded.
+    pub async fn from_url_async(url: &str, options: &HttpOptions) -> Result<Self> {
+        let (sender, receiver) = oneshot::channel();
+        let (url, options) = (url.to_string(), options.clone());
+        thread::spawn(move || {
+            let _ = sender.send(Self::from_url_with_options(&url, &options));
+        });
+        receiver
+            .await
+            .map_err(|_| WebpAnimationError::Http("download thread panicked".into()))?
+    }
+}
+
+#[cfg(feature = "nannou")]
+impl WebpAnimationPlayer {
+    /// Downloads and decodes the animation at `url` with the default
+    /// [`HttpOptions`], blocking until it is done; see
+    /// [`WebpAnimationData::from_url`].
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The `http` or `https` URL of the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the body cannot be decoded.
+    pub fn from_url(url: &str, app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::from_url(url)?.into_player(app))
+    }
+
+    /// Downloads and decodes the animation at `url` with custom limits, blocking
+    /// until it is done.
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The `http` or `https` URL of the animation.
+    /// - `options`: The timeout and size limit of the download.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the body cannot be decoded.
+    pub fn from_url_with_options(url: &str, options: &HttpOptions, app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::from_url_with_options(url, options)?.into_player(app))
+    }
+
+    /// Downloads and decodes the animation at `url` on a background thread, then
+    /// uploads the textures; see [`WebpAnimationData::from_url_async`].
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The `http` or `https` URL of the animation.
+    /// - `options`: The timeout and size limit of the download.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the body cannot be decoded.
+    pub async fn from_url_async(url: &str, options: &HttpOptions, app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::from_url_async(url, options)
+            .await?
+            .into_player(app))
+    }
+}
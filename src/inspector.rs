@@ -0,0 +1,111 @@
+use crate::animation::WebpAnimation;
+use nannou_egui::egui::{self, Response, Ui, Widget};
+
+/// An egui widget for inspecting and controlling a [`WebpAnimation`].
+///
+/// Shows play/pause buttons, a scrub bar, the current frame index, the frame
+/// durations and the memory held by the decoded frames:
+///
+/// ```no_run
+/// # use nannou_egui::{egui, Egui};
+/// # use nannou_webp_animation::{AnimationInspector, WebpAnimation};
+/// # fn update(egui: &mut Egui, animation: &mut WebpAnimation) {
+/// let ctx = egui.begin_frame();
+/// egui::Window::new("Animation").show(&ctx, |ui| {
+///     ui.add(AnimationInspector::new(animation));
+/// });
+/// # }
+/// ```
+pub struct AnimationInspector<'a> {
+    /// The animation being inspected.
+    animation: &'a mut WebpAnimation,
+}
+
+impl<'a> AnimationInspector<'a> {
+    /// Creates an inspector bound to `animation`.
+    pub fn new(animation: &'a mut WebpAnimation) -> Self {
+        Self { animation }
+    }
+}
+
+impl Widget for AnimationInspector<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let animation = self.animation;
+
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                if animation.is_playing() {
+                    if ui.button("⏸ Pause").clicked() {
+                        animation.pause();
+                    }
+                } else if ui.button("▶ Play").clicked() {
+                    animation.play();
+                }
+                if ui.button("⏮").clicked() {
+                    animation.seek_frame(0);
+                }
+                ui.label(format!(
+                    "frame {}/{}",
+                    animation.current_frame_index() + 1,
+                    animation.frame_count()
+                ));
+            });
+
+            let mut index = animation.current_frame_index();
+            let last = animation.frame_count() - 1;
+            if ui
+                .add(egui::Slider::new(&mut index, 0..=last).text("frame"))
+                .changed()
+            {
+                animation.seek_frame(index);
+            }
+
+            let mut speed = animation.speed();
+            if ui
+                .add(egui::Slider::new(&mut speed, 0.0..=4.0).text("speed"))
+                .changed()
+            {
+                animation.set_speed(speed);
+            }
+
+            ui.label(format!(
+                "{}x{}, {:.2} s, {:.1} fps",
+                animation.width(),
+                animation.height(),
+                animation.duration().as_secs_f64(),
+                animation.average_fps()
+            ));
+            let frame_bytes: usize = animation
+                .frames()
+                .iter()
+                .map(|frame| frame.image.as_bytes().len())
+                .sum();
+            ui.label(format!(
+                "decoded frames: {:.1} MiB, textures uploaded: {}/{}",
+                frame_bytes as f64 / (1024.0 * 1024.0),
+                animation.uploaded_texture_count(),
+                animation.frame_count()
+            ));
+
+            egui::CollapsingHeader::new("Durations").show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        let current = animation.current_frame_index();
+                        let durations: Vec<_> = animation
+                            .frames()
+                            .iter()
+                            .map(|frame| frame.duration)
+                            .collect();
+                        for (index, duration) in durations.into_iter().enumerate() {
+                            let text = format!("{:>4}  {} ms", index, duration.as_millis());
+                            if ui.selectable_label(index == current, text).clicked() {
+                                animation.seek_frame(index);
+                            }
+                        }
+                    });
+            });
+        })
+        .response
+    }
+}
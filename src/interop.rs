@@ -0,0 +1,91 @@
+use crate::data::WebpAnimationData;
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use std::time::Duration;
+use webp_animation::{ColorMode, Decoder, Encoder, WebPData};
+
+/// Collects the frames of a `webp-animation` decoder.
+///
+/// Frame durations are the differences between consecutive frame timestamps.
+impl TryFrom<Decoder<'_>> for WebpAnimationData {
+    type Error = WebpAnimationError;
+
+    fn try_from(decoder: Decoder<'_>) -> Result<Self> {
+        let mut previous = 0;
+        let mut frames = Vec::new();
+        for frame in decoder {
+            let (width, height) = frame.dimensions();
+            let data = frame.data().to_vec();
+            let image = match frame.color_mode() {
+                ColorMode::Rgba => {
+                    RgbaImage::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+                }
+                ColorMode::Rgb => {
+                    RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+                }
+                ColorMode::Bgra => RgbaImage::from_raw(width, height, swap_red_blue(data, 4))
+                    .map(DynamicImage::ImageRgba8),
+                ColorMode::Bgr => RgbImage::from_raw(width, height, swap_red_blue(data, 3))
+                    .map(DynamicImage::ImageRgb8),
+            }
+            .ok_or_else(|| {
+                WebpAnimationError::Decode("frame buffer does not match its dimensions".into())
+            })?;
+
+            let timestamp = frame.timestamp();
+            let duration = Duration::from_millis(timestamp.saturating_sub(previous).max(0) as u64);
+            previous = timestamp;
+            frames.push(WebpFrame { image, duration });
+        }
+        WebpAnimationData::from_frames(frames)
+    }
+}
+
+/// Encodes the frames with a default `webp-animation` encoder.
+impl TryFrom<&WebpAnimationData> for WebPData {
+    type Error = WebpAnimationError;
+
+    fn try_from(data: &WebpAnimationData) -> Result<Self> {
+        let mut encoder = Encoder::new((data.width(), data.height())).map_err(encode_error)?;
+        let end = data.add_to_encoder(&mut encoder, 0)?;
+        encoder.finalize(end).map_err(encode_error)
+    }
+}
+
+impl WebpAnimationData {
+    /// Adds every frame to a `webp-animation` encoder, starting at `start_ms`,
+    /// and returns the timestamp at which the last frame ends.
+    ///
+    /// Pass the returned timestamp to `Encoder::finalize`, or keep adding frames
+    /// from it to concatenate animations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoder rejects a frame, e.g. because its size
+    /// does not match the encoder's dimensions.
+    pub fn add_to_encoder(&self, encoder: &mut Encoder, start_ms: i32) -> Result<i32> {
+        let mut timestamp = start_ms;
+        for frame in self.frames() {
+            let rgba = frame.image.to_rgba8();
+            encoder
+                .add_frame(rgba.as_raw(), timestamp)
+                .map_err(encode_error)?;
+            timestamp += frame.duration.as_millis() as i32;
+        }
+        Ok(timestamp)
+    }
+}
+
+/// Reorders the red and blue channels of `data` in place.
+fn swap_red_blue(mut data: Vec<u8>, channels: usize) -> Vec<u8> {
+    for pixel in data.chunks_exact_mut(channels) {
+        pixel.swap(0, 2);
+    }
+    data
+}
+
+/// Wraps an error reported by `webp-animation`'s encoder.
+fn encode_error(error: webp_animation::Error) -> WebpAnimationError {
+    WebpAnimationError::Encode(format!("{:?}", error))
+}
@@ -1,16 +1,286 @@
+/// The bindgen-generated FFI layer shared by the decoder and encoder.
+#[cfg(any(libwebp, feature = "dynamic-loading"))]
+mod bindings;
+
+/// The module sharing decoded animations between loads of the same file.
+mod asset_cache;
+
+/// The module decoding AVIF image sequences with libavif.
+#[cfg(feature = "avif")]
+mod avif;
+
 /// The module handling the animation playback, including rendering and frame management.
+#[cfg(feature = "nannou")]
 pub mod animation;
 
+/// The module providing the builder for configuring how animations are loaded.
+#[cfg(feature = "nannou")]
+pub mod builder;
+
+/// The module coordinating a crate-wide memory budget across players.
+#[cfg(feature = "nannou")]
+pub mod budget;
+
+/// The module defining the clock playback is timed against.
+pub mod clock;
+
+/// The module reading and writing the binary cache of decoded frames.
+mod cache;
+
+/// The module transcoding frames to block-compressed GPU texture formats.
+#[cfg(feature = "texture-compression")]
+pub mod compression;
+
+/// The module holding decoded animation frames independently of the GPU.
+pub mod data;
+
 /// The module responsible for decoding WebP files and extracting frames for animation.
 pub mod decoder;
 
+/// The module decoding single frames on demand with libwebp's demuxer.
+#[cfg(libwebp)]
+pub mod demux;
+
+/// The module loading libwebpdemux at runtime for the `dynamic-loading` feature.
+#[cfg(all(feature = "dynamic-loading", not(feature = "pure-rust")))]
+mod dynload;
+
+/// The module extending nannou's `Draw` with animation drawing.
+#[cfg(feature = "nannou")]
+pub mod drawing;
+
+/// The module embedding animation files in the binary.
+pub mod embed;
+
+/// The module responsible for encoding frames into animated WebP files.
+#[cfg(libwebp_encode)]
+pub mod encoder;
+
+/// The module defining the error type returned throughout the crate.
+pub mod error;
+
+/// The module exporting decoded frames to other file formats.
+pub mod export;
+
+/// The module detecting the format of animation files and decoding GIF, APNG,
+/// AVIF and image sequence input.
+mod formats;
+
 /// The module defining the structure and properties of a single animation frame.
 pub mod frame;
 
+/// The module watching animation files and reloading them when they change.
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+
+/// The module downloading animations over HTTP.
+#[cfg(feature = "http")]
+pub mod http;
+
+/// The module providing an egui widget for inspecting animations.
+#[cfg(feature = "nannou_egui")]
+pub mod inspector;
+
+/// The module converting between this crate's types and the `webp-animation` crate.
+#[cfg(feature = "webp-animation")]
+mod interop;
+
+/// The module decoding animations on background threads.
+pub mod loader;
+
+/// The module managing many named animations together.
+#[cfg(feature = "nannou")]
+pub mod manager;
+
+/// The module synchronizing playback to an incoming MIDI clock.
+#[cfg(feature = "midi")]
+pub mod midi;
+
+/// The module editing animated WebP files at the chunk level without re-encoding.
+#[cfg(libwebp_encode)]
+pub mod mux;
+
+/// The module mapping incoming OSC messages to playback controls.
+#[cfg(feature = "nannou_osc")]
+pub mod osc;
+
+/// The module defining the playback state shared by players and the GPU-free `HeadlessPlayer`.
+pub mod playback;
+
+/// The module recording the output of a sketch into an animated WebP file.
+#[cfg(all(feature = "nannou", libwebp_encode))]
+pub mod recorder;
+
+/// The module holding the crate-wide default settings.
+pub mod settings;
+
+/// The module paging decoded frames to a memory-mapped temporary file.
+#[cfg(feature = "spill-to-disk")]
+mod spill;
+
+/// The module re-exporting the raw libwebp FFI bindings.
+#[cfg(all(feature = "sys", libwebp))]
+pub mod sys;
+
+/// The module decoding animated WebP frames from a `Read` stream as they arrive.
+#[cfg(libwebp)]
+pub mod stream;
+
+/// The module playing long animations from a sliding window of decoded frames.
+#[cfg(all(feature = "nannou", libwebp))]
+pub mod streaming;
+
+/// The module defining the `AnimatedTexture` trait shared by playable animations.
+#[cfg(feature = "nannou")]
+pub mod texture;
+
+/// The module staging frame textures on a background thread before upload.
+#[cfg(feature = "nannou")]
+mod uploader;
+
 /// The module containing utility functions for image processing.
 pub mod utils;
 
+/// The module fetching animations with the browser's `fetch` API on WebAssembly.
+#[cfg(target_arch = "wasm32")]
+mod web;
+
 /// Re-exports the `WebpAnimation` struct for easy access.
 ///
 /// `WebpAnimation` is the primary structure for managing and displaying WebP animations.
-pub use crate::animation::WebpAnimation;
+#[cfg(feature = "nannou")]
+pub use crate::animation::{Interpolation, WebpAnimation, WebpAnimationPlayer};
+
+/// Re-exports the `Clock` trait and its real and manual implementations.
+pub use crate::clock::{Clock, ManualClock, SystemClock};
+
+/// Re-exports the playback state types and the `HeadlessPlayer` for testing without a GPU.
+pub use crate::playback::{HeadlessPlayer, PlaybackDirection, PlaybackSnapshot};
+
+/// Re-exports the `AnimatedTexture` trait for code accepting any animation.
+#[cfg(feature = "nannou")]
+pub use crate::texture::AnimatedTexture;
+
+/// Re-exports the `DrawWebpExt` trait adding `draw.webp(&animation)`.
+#[cfg(feature = "nannou")]
+pub use crate::drawing::{DrawWebpExt, WebpDrawing};
+
+/// Re-exports the `WebpAnimationError` enum and the matching `Result` alias.
+pub use crate::error::{Result, WebpAnimationError};
+
+/// Re-exports the `WebpAnimationData` struct holding decoded frames without GPU
+/// resources and the `MemoryReport` returned by `memory_usage()`.
+pub use crate::data::{MemoryReport, WebpAnimationData};
+
+/// Re-exports the `EmbeddedAnimation` created by the `webp_animation!` macro.
+pub use crate::embed::EmbeddedAnimation;
+
+/// Re-exports the `LoadHandle` returned by the `load_async` constructors, the
+/// priority-ordered `LoadQueue` and the `LoadRecovery` policy for failed loads.
+pub use crate::loader::{LoadHandle, LoadQueue, LoadRecovery};
+
+/// Re-exports the `SequenceTiming` enum used to load image sequences.
+pub use crate::formats::SequenceTiming;
+
+/// Re-exports the `WebpAnimationBuilder` and the decoder's `ColorMode`.
+#[cfg(feature = "nannou")]
+pub use crate::builder::WebpAnimationBuilder;
+pub use crate::decoder::ColorMode;
+
+/// Re-exports the `WebpStream` decoding frames from a `Read` stream.
+#[cfg(libwebp)]
+pub use crate::stream::WebpStream;
+
+/// Re-exports the `StreamingPlayer` for animations too long to decode up front.
+#[cfg(all(feature = "nannou", libwebp))]
+pub use crate::streaming::StreamingPlayer;
+
+/// Re-exports the `WebpDemuxer` struct for random access to frames.
+#[cfg(libwebp)]
+pub use crate::demux::WebpDemuxer;
+
+/// Re-exports the `AnimationManager` and its `MemoryStats`.
+#[cfg(feature = "nannou")]
+pub use crate::manager::{AnimationManager, MemoryStats};
+
+/// Re-exports the `MemoryBudget` limiting the memory of all players together.
+#[cfg(feature = "nannou")]
+pub use crate::budget::MemoryBudget;
+
+/// Re-exports the `WebpEncoder` struct and its options for writing animated WebP files.
+#[cfg(libwebp_encode)]
+pub use crate::encoder::{
+    BackgroundEncoder, EncodeJob, EncoderOptions, Metadata, OverlayFn, Quantization, ResizeFilter,
+    WebpEncoder,
+};
+
+/// Re-exports the `AnimationCache` holding decoded animations shared by path.
+pub use crate::asset_cache::AnimationCache;
+
+/// Re-exports the `AnimationSettings` struct for configuring crate-wide defaults.
+pub use crate::settings::{AnimationSettings, Prefetch, TextureFilter};
+
+/// Re-exports the `Dithering` enum used by [`Quantization`].
+pub use crate::utils::Dithering;
+
+/// Re-exports the `WebpMuxEditor` struct for lossless chunk-level editing.
+#[cfg(libwebp_encode)]
+pub use crate::mux::WebpMuxEditor;
+
+/// Re-exports the download limits used by the `from_url` constructors.
+#[cfg(feature = "http")]
+pub use crate::http::HttpOptions;
+
+/// Re-exports the `AnimationInspector` egui widget.
+#[cfg(feature = "nannou_egui")]
+pub use crate::inspector::AnimationInspector;
+
+/// Re-exports the `MidiClockSync` adapter for beat-locked playback.
+#[cfg(feature = "midi")]
+pub use crate::midi::MidiClockSync;
+
+/// Re-exports the `OscControl` adapter for remote control over OSC.
+#[cfg(feature = "nannou_osc")]
+pub use crate::osc::OscControl;
+
+/// Re-exports the `WebpRecorder` struct for capturing sketches as animated WebP files.
+#[cfg(all(feature = "nannou", libwebp_encode))]
+pub use crate::recorder::WebpRecorder;
+
+/// Re-exports the `TextureCompression` enum used to load block-compressed animations.
+#[cfg(feature = "texture-compression")]
+pub use crate::compression::TextureCompression;
+
+// The CPU-side types hold no GPU resources and can be decoded, loaded and
+// inspected on any thread. Fails to compile if one of them loses `Send` or
+// `Sync`; the `Receiver`-backed handles and the libwebp encoders are only
+// `Send`, as they are driven through `&mut self` by a single owner.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    const fn assert_send<T: Send>() {}
+
+    assert_send_sync::<WebpAnimationData>();
+    assert_send_sync::<frame::WebpFrame>();
+    assert_send_sync::<HeadlessPlayer>();
+    assert_send_sync::<PlaybackSnapshot>();
+    assert_send_sync::<PlaybackDirection>();
+    assert_send_sync::<WebpAnimationError>();
+    assert_send_sync::<ColorMode>();
+    assert_send_sync::<AnimationSettings>();
+    assert_send_sync::<EmbeddedAnimation>();
+    assert_send_sync::<AnimationCache>();
+    assert_send_sync::<ManualClock>();
+    assert_send_sync::<SystemClock>();
+    assert_send_sync::<LoadQueue>();
+    assert_send::<LoadHandle>();
+    #[cfg(feature = "nannou")]
+    assert_send_sync::<WebpAnimationBuilder>();
+    #[cfg(libwebp)]
+    assert_send_sync::<WebpDemuxer>();
+    #[cfg(libwebp)]
+    assert_send::<WebpStream>();
+    #[cfg(libwebp_encode)]
+    assert_send::<WebpEncoder>();
+    #[cfg(libwebp_encode)]
+    assert_send::<WebpMuxEditor>();
+};
@@ -0,0 +1,717 @@
+#[cfg(feature = "nannou")]
+use crate::animation::WebpAnimationPlayer;
+#[cfg(feature = "nannou")]
+use crate::builder::WebpAnimationBuilder;
+use crate::data::WebpAnimationData;
+use crate::error::{Result, WebpAnimationError};
+use crate::settings::AnimationSettings;
+#[cfg(feature = "async")]
+use futures_channel::oneshot;
+#[cfg(feature = "nannou")]
+use nannou::App;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// State shared between a [`LoadHandle`] and its decoding thread.
+#[derive(Debug, Default)]
+struct LoadState {
+    /// Set when the handle is cancelled or dropped.
+    cancelled: AtomicBool,
+    /// Number of frames decoded so far.
+    decoded: AtomicUsize,
+    /// Number of frames in the file, or `0` while unknown.
+    total: AtomicUsize,
+    /// Set when the decoding thread has sent its result.
+    finished: AtomicBool,
+    /// Order in which a [`LoadQueue`] starts its loads; higher goes first.
+    priority: AtomicI32,
+    /// Why the load fell back to [`LoadRecovery::fallback`], until taken.
+    failure: Mutex<Option<WebpAnimationError>>,
+}
+
+/// A decode waiting for or running on a worker thread.
+struct Job {
+    /// Shared with the handle.
+    state: Arc<LoadState>,
+    /// Delivers the result to the handle.
+    sender: Sender<Result<WebpAnimationData>>,
+    /// Decodes the frames; called again for every retry.
+    decode: Box<dyn Fn() -> Result<WebpAnimationData> + Send>,
+    /// How a failed decode is retried or replaced.
+    recovery: LoadRecovery,
+}
+
+impl Job {
+    /// Decodes on the current thread and sends the result to the handle,
+    /// unless the load was cancelled before it started.
+    fn run(self) {
+        if self.state.cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+        CURRENT_LOAD.with(|load| *load.borrow_mut() = Some(self.state.clone()));
+        let result = self.recovery.recover(|| {
+            if self.state.cancelled.load(Ordering::Relaxed) {
+                return Err(WebpAnimationError::Cancelled);
+            }
+            (self.decode)()
+        });
+        let result = result.map(|(data, failure)| {
+            *self.state.failure.lock().unwrap() = failure;
+            data
+        });
+        let _ = self.sender.send(result);
+        self.state.finished.store(true, Ordering::Relaxed);
+        CURRENT_LOAD.with(|load| *load.borrow_mut() = None);
+    }
+}
+
+thread_local! {
+    /// The load the current thread is decoding for, if any.
+    static CURRENT_LOAD: RefCell<Option<Arc<LoadState>>> = const { RefCell::new(None) };
+}
+
+/// How failed loads are retried or replaced, so an installation keeps
+/// showing something when an asset is missing or corrupt instead of
+/// panicking on the error.
+///
+/// Applies to loads on a background thread — [`LoadHandle`]s from
+/// `load_async`, a [`LoadQueue`] or
+/// [`load_progressive`](crate::WebpAnimationPlayer::load_progressive). A
+/// [`reload`](crate::WebpAnimationPlayer::reload) runs on the caller's thread
+/// and only uses the fallback, without retries. Register it crate-wide
+/// with [`AnimationSettings::recovery`]:
+///
+/// ```ignore
+/// use nannou_webp_animation::{webp_animation, AnimationSettings, EmbeddedAnimation, LoadRecovery};
+///
+/// static MISSING: EmbeddedAnimation = webp_animation!("assets/missing.webp");
+///
+/// AnimationSettings {
+///     recovery: LoadRecovery {
+///         retries: 2,
+///         retry_delay: Duration::from_millis(500),
+///         fallback: Some(MISSING.decode()?),
+///     },
+///     ..AnimationSettings::global()
+/// }
+/// .set_global();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadRecovery {
+    /// Number of further attempts after a failed decode, e.g. for files on a
+    /// network share that may be briefly unavailable. Defaults to `0`.
+    pub retries: u32,
+    /// Pause before each further attempt. Defaults to zero.
+    pub retry_delay: Duration,
+    /// Animation used in place of one that still fails, e.g. a "missing
+    /// asset" placeholder. Defaults to `None`, which reports the error.
+    pub fallback: Option<WebpAnimationData>,
+}
+
+impl LoadRecovery {
+    /// The built-in policy: no retries and no fallback.
+    pub(crate) const NONE: Self = Self {
+        retries: 0,
+        retry_delay: Duration::ZERO,
+        fallback: None,
+    };
+
+    /// Runs `decode`, retrying after failures, and returns the frames together
+    /// with the error that made it fall back to [`fallback`](Self::fallback),
+    /// if it did.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error if every attempt failed and there is no
+    /// fallback. Cancelled loads are neither retried nor replaced.
+    pub(crate) fn recover<F>(
+        &self,
+        decode: F,
+    ) -> Result<(WebpAnimationData, Option<WebpAnimationError>)>
+    where
+        F: Fn() -> Result<WebpAnimationData>,
+    {
+        let mut result = decode();
+        for _ in 0..self.retries {
+            if matches!(result, Ok(_) | Err(WebpAnimationError::Cancelled)) {
+                break;
+            }
+            thread::sleep(self.retry_delay);
+            result = decode();
+        }
+        match (result, &self.fallback) {
+            (Err(error), Some(fallback)) if !matches!(error, WebpAnimationError::Cancelled) => {
+                Ok((fallback.clone(), Some(error)))
+            }
+            (result, _) => result.map(|data| (data, None)),
+        }
+    }
+}
+
+/// Called by the decoders after every frame with the number of frames decoded
+/// so far and, if the container declares it, the total number of frames.
+///
+/// Records the progress of the load running on this thread and returns
+/// [`WebpAnimationError::Cancelled`] once it has been cancelled, so the
+/// decoder stops before the next frame. Decodes outside a [`LoadHandle`] are
+/// never cancelled.
+pub(crate) fn frame_decoded(decoded: usize, total: Option<usize>) -> Result<()> {
+    CURRENT_LOAD.with(|load| {
+        let Some(state) = &*load.borrow() else {
+            return Ok(());
+        };
+        state.decoded.store(decoded, Ordering::Relaxed);
+        state.total.store(total.unwrap_or(0), Ordering::Relaxed);
+        if state.cancelled.load(Ordering::Relaxed) {
+            Err(WebpAnimationError::Cancelled)
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Number of whole-file decodes currently running on any thread.
+static RUNNING_DECODES: Mutex<usize> = Mutex::new(0);
+
+/// Signalled whenever a decode finishes and frees its slot.
+static DECODE_FINISHED: Condvar = Condvar::new();
+
+thread_local! {
+    /// Number of slots claimed on this thread and not yet dropped.
+    static HELD_SLOTS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// One of the [`AnimationSettings::max_concurrent_decodes`] slots, released
+/// on drop.
+pub(crate) struct DecodeSlot;
+
+/// Blocks until fewer decodes than [`AnimationSettings::max_concurrent_decodes`]
+/// are running, then claims a slot for the decode about to start.
+///
+/// A thread that already holds a slot gets another one at once, so decoders
+/// built on top of each other, e.g. a whole-file decode compositing through
+/// the demuxer, count once and never wait for themselves.
+pub(crate) fn decode_slot() -> DecodeSlot {
+    if HELD_SLOTS.with(|held| held.replace(held.get() + 1)) > 0 {
+        return DecodeSlot;
+    }
+    let mut running = RUNNING_DECODES.lock().unwrap();
+    loop {
+        let limit = AnimationSettings::global().max_concurrent_decodes;
+        if limit == 0 || *running < limit {
+            break;
+        }
+        running = DECODE_FINISHED.wait(running).unwrap();
+    }
+    *running += 1;
+    DecodeSlot
+}
+
+impl Drop for DecodeSlot {
+    fn drop(&mut self) {
+        if HELD_SLOTS.with(|held| held.replace(held.get() - 1)) > 1 {
+            return;
+        }
+        *RUNNING_DECODES.lock().unwrap() -= 1;
+        DECODE_FINISHED.notify_all();
+    }
+}
+
+/// An animation being decoded on a background thread.
+///
+/// Created by [`WebpAnimationData::load_async`] or
+/// [`WebpAnimationPlayer::load_async`]. Poll it once per frame, e.g. in
+/// nannou's `update`, so `model` returns immediately instead of freezing the
+/// window while a large file decodes:
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use nannou_webp_animation::{LoadHandle, WebpAnimation};
+/// struct Model {
+///     loading: LoadHandle,
+///     animation: Option<WebpAnimation>,
+/// }
+///
+/// fn update(app: &App, model: &mut Model, _update: Update) {
+///     if let Some(result) = model.loading.try_take(app) {
+///         model.animation = Some(result.expect("Failed to decode WEBP animation"));
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LoadHandle {
+    /// Receives the decoded frames, or `None` once the result was taken.
+    receiver: Option<Receiver<Result<WebpAnimationData>>>,
+    /// Shared with the decoding thread.
+    state: Arc<LoadState>,
+    /// Options applied when the player is created.
+    #[cfg(feature = "nannou")]
+    builder: Option<WebpAnimationBuilder>,
+}
+
+impl LoadHandle {
+    /// Creates a handle and the job that delivers its result by running `decode`.
+    fn with_job<F>(decode: F, priority: i32) -> (Self, Job)
+    where
+        F: Fn() -> Result<WebpAnimationData> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let state = Arc::new(LoadState {
+            priority: AtomicI32::new(priority),
+            ..LoadState::default()
+        });
+        let job = Job {
+            state: state.clone(),
+            sender,
+            decode: Box::new(decode),
+            recovery: AnimationSettings::global().recovery,
+        };
+        let handle = Self {
+            receiver: Some(receiver),
+            state,
+            #[cfg(feature = "nannou")]
+            builder: None,
+        };
+        (handle, job)
+    }
+
+    /// Runs `decode` on a new thread.
+    fn spawn<F>(decode: F) -> Self
+    where
+        F: Fn() -> Result<WebpAnimationData> + Send + 'static,
+    {
+        let (handle, job) = Self::with_job(decode, 0);
+        thread::spawn(move || job.run());
+        handle
+    }
+
+    /// Returns `true` until the result has been taken or the load was cancelled.
+    pub fn is_pending(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Returns the fraction of frames decoded so far, from `0.0` to `1.0`,
+    /// for drawing a progress bar on a loading screen.
+    ///
+    /// Stays at `0.0` for formats that do not declare their frame count up
+    /// front (GIF, APNG) and for still images, and becomes `1.0` once
+    /// decoding has finished.
+    pub fn progress(&self) -> f32 {
+        if self.state.finished.load(Ordering::Relaxed) {
+            return 1.0;
+        }
+        match self.state.total.load(Ordering::Relaxed) {
+            0 => 0.0,
+            total => (self.state.decoded.load(Ordering::Relaxed) as f32 / total as f32).min(1.0),
+        }
+    }
+
+    /// Returns the number of frames decoded so far.
+    pub fn frames_decoded(&self) -> usize {
+        self.state.decoded.load(Ordering::Relaxed)
+    }
+
+    /// Changes the priority of a load waiting in a [`LoadQueue`]; higher
+    /// priorities start first.
+    ///
+    /// Raise it when the animation is about to appear on screen and lower it
+    /// when it scrolls away. Has no effect once decoding has started, or on
+    /// handles that were not created by a queue.
+    pub fn set_priority(&self, priority: i32) {
+        self.state.priority.store(priority, Ordering::Relaxed);
+    }
+
+    /// Returns the priority of the load in its [`LoadQueue`].
+    pub fn priority(&self) -> i32 {
+        self.state.priority.load(Ordering::Relaxed)
+    }
+
+    /// Returns why the load failed, once, if the decoded animation is the
+    /// [`LoadRecovery::fallback`] rather than the requested file.
+    ///
+    /// Call it after the result was taken, e.g. to log the missing asset.
+    pub fn take_failure(&self) -> Option<WebpAnimationError> {
+        self.state.failure.lock().unwrap().take()
+    }
+
+    /// Stops decoding before the next frame and discards the result.
+    ///
+    /// The worker thread exits shortly after; the handle then stays empty.
+    /// Dropping the handle cancels the load as well.
+    pub fn cancel(&mut self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+        self.receiver = None;
+    }
+
+    /// Returns the decoded frames if decoding has finished, without blocking.
+    ///
+    /// Returns `None` while decoding is still running and after the result was
+    /// taken; the result is returned exactly once.
+    pub fn try_take_data(&mut self) -> Option<Result<WebpAnimationData>> {
+        let result = match self.receiver.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(WebpAnimationError::Decode(
+                "decoding thread panicked".into(),
+            )),
+        };
+        self.receiver = None;
+        Some(result)
+    }
+
+    /// Returns the player if decoding has finished, uploading its textures,
+    /// without blocking.
+    ///
+    /// Returns `None` while decoding is still running and after the result was
+    /// taken; the result is returned exactly once.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    #[cfg(feature = "nannou")]
+    pub fn try_take(&mut self, app: &App) -> Option<Result<WebpAnimationPlayer>> {
+        let result = self.try_take_data()?;
+        Some(result.map(|data| match self.builder.take() {
+            Some(builder) => builder.build_from_file_data(data, app),
+            None => data.into_player(app),
+        }))
+    }
+}
+
+/// Loads waiting for a worker of a [`LoadQueue`].
+#[derive(Default)]
+struct QueuedJobs {
+    /// Jobs that have not started yet, in submission order.
+    pending: Vec<Job>,
+    /// Set when the queue is dropped; workers exit once `pending` is empty.
+    shutdown: bool,
+}
+
+/// State shared between a [`LoadQueue`] and its workers.
+#[derive(Default)]
+struct QueueShared {
+    /// Loads waiting for a worker.
+    jobs: Mutex<QueuedJobs>,
+    /// Signalled when a job is queued or the queue shuts down.
+    available: Condvar,
+}
+
+impl QueueShared {
+    /// Blocks until a job is available and removes the one with the highest
+    /// priority, or returns `None` once the queue has shut down.
+    ///
+    /// Cancelled jobs are dropped; among equal priorities the oldest job wins.
+    fn next_job(&self) -> Option<Job> {
+        let mut jobs = self.jobs.lock().unwrap();
+        loop {
+            jobs.pending
+                .retain(|job| !job.state.cancelled.load(Ordering::Relaxed));
+            let best = jobs
+                .pending
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|(_, job)| job.state.priority.load(Ordering::Relaxed))
+                .map(|(index, _)| index);
+            match best {
+                Some(index) => return Some(jobs.pending.remove(index)),
+                None if jobs.shutdown => return None,
+                None => jobs = self.available.wait(jobs).unwrap(),
+            }
+        }
+    }
+}
+
+/// Decodes animations on a fixed pool of worker threads, starting the loads
+/// with the highest priority first.
+///
+/// Unlike [`WebpAnimationData::load_async`], which starts a thread per file
+/// right away, the queue runs only as many decodes at once as it has workers.
+/// The rest wait, and [`LoadHandle::set_priority`] can move them forward while
+/// they do, so the animation about to appear on screen is decoded before the
+/// off-screen ones:
+///
+/// ```no_run
+/// # use nannou_webp_animation::LoadQueue;
+/// let queue = LoadQueue::new();
+/// let background = queue.load("assets/background.webp", 0);
+/// let next_scene = queue.load("assets/next_scene.webp", 0);
+/// // The next scene is about to be shown: decode it first.
+/// next_scene.set_priority(10);
+/// ```
+///
+/// Dropping the queue lets the workers finish the loads already queued and
+/// then exit.
+pub struct LoadQueue {
+    /// Shared with the workers.
+    shared: Arc<QueueShared>,
+}
+
+impl LoadQueue {
+    /// Creates a queue with one worker per CPU core.
+    pub fn new() -> Self {
+        Self::with_workers(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    /// Creates a queue decoding at most `workers` files at once (at least one).
+    pub fn with_workers(workers: usize) -> Self {
+        let shared = Arc::new(QueueShared::default());
+        for _ in 0..workers.max(1) {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                while let Some(job) = shared.next_job() {
+                    job.run();
+                }
+            });
+        }
+        Self { shared }
+    }
+
+    /// Queues the file at `path` for decoding.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `priority`: Loads with higher priorities start first; change it later
+    ///   with [`LoadHandle::set_priority`].
+    pub fn load<P: AsRef<Path>>(&self, path: P, priority: i32) -> LoadHandle {
+        let path = path.as_ref().to_path_buf();
+        self.push(LoadHandle::with_job(
+            move || WebpAnimationData::from_file(&path),
+            priority,
+        ))
+    }
+
+    /// Queues the file of `builder` for decoding; the player returned by
+    /// [`LoadHandle::try_take`] uses the options of the builder.
+    ///
+    /// # Parameters
+    ///
+    /// - `builder`: The file and options of the player.
+    /// - `priority`: Loads with higher priorities start first; change it later
+    ///   with [`LoadHandle::set_priority`].
+    #[cfg(feature = "nannou")]
+    pub fn load_builder(&self, builder: WebpAnimationBuilder, priority: i32) -> LoadHandle {
+        let decoder = builder.clone();
+        let mut handle = self.push(LoadHandle::with_job(move || decoder.decode(), priority));
+        handle.builder = Some(builder);
+        handle
+    }
+
+    /// Returns the number of loads waiting for a worker.
+    pub fn len(&self) -> usize {
+        self.shared.jobs.lock().unwrap().pending.len()
+    }
+
+    /// Returns `true` if no load is waiting for a worker.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds the job to the queue and wakes a worker.
+    fn push(&self, (handle, job): (LoadHandle, Job)) -> LoadHandle {
+        self.shared.jobs.lock().unwrap().pending.push(job);
+        self.shared.available.notify_one();
+        handle
+    }
+}
+
+impl Default for LoadQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LoadQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadQueue")
+            .field("pending", &self.len())
+            .finish()
+    }
+}
+
+impl Drop for LoadQueue {
+    fn drop(&mut self) {
+        self.shared.jobs.lock().unwrap().shutdown = true;
+        self.shared.available.notify_all();
+    }
+}
+
+/// Decodes `paths` on a pool of worker threads, one per CPU core, and calls
+/// `on_result` on the calling thread with the index and result of each file
+/// as soon as it is decoded.
+fn decode_parallel<F>(paths: &[PathBuf], mut on_result: F)
+where
+    F: FnMut(usize, Result<WebpAnimationData>),
+{
+    let workers = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(paths.len());
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let (next, sender) = (&next, sender.clone());
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                if sender
+                    .send((index, WebpAnimationData::from_file(path)))
+                    .is_err()
+                {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        for (index, result) in receiver {
+            on_result(index, result);
+        }
+    });
+}
+
+impl Drop for LoadHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl WebpAnimationData {
+    /// Starts decoding the file at `path` on a background thread.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    pub fn load_async<P: AsRef<Path>>(path: P) -> LoadHandle {
+        let path = path.as_ref().to_path_buf();
+        LoadHandle::spawn(move || Self::from_file(&path))
+    }
+
+    /// Decodes several files concurrently on a pool of worker threads.
+    ///
+    /// # Parameters
+    ///
+    /// - `paths`: Paths to the files to decode.
+    ///
+    /// # Returns
+    ///
+    /// The result for each path, in the order of `paths`.
+    pub fn load_many<I, P>(paths: I) -> Vec<Result<Self>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .map(|p| p.as_ref().to_path_buf())
+            .collect();
+        let mut results: Vec<Option<Result<Self>>> = paths.iter().map(|_| None).collect();
+        decode_parallel(&paths, |index, result| results[index] = Some(result));
+        results.into_iter().flatten().collect()
+    }
+
+    /// Decodes the file at `path` without blocking the caller.
+    ///
+    /// The decode runs on its own thread, like a `spawn_blocking` task, so the
+    /// returned future works with any async runtime.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let (sender, receiver) = oneshot::channel();
+        let path = path.as_ref().to_path_buf();
+        thread::spawn(move || {
+            let _ = sender.send(Self::from_file(path));
+        });
+        receiver
+            .await
+            .map_err(|_| WebpAnimationError::Decode("decoding thread panicked".into()))?
+    }
+}
+
+#[cfg(feature = "nannou")]
+impl WebpAnimationPlayer {
+    /// Starts decoding the file at `path` on a background thread; poll the
+    /// returned handle with [`LoadHandle::try_take`] to get the player.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    pub fn load_async<P: AsRef<Path>>(path: P) -> LoadHandle {
+        WebpAnimationData::load_async(path)
+    }
+
+    /// Decodes several files concurrently on a pool of worker threads and
+    /// uploads the textures on the calling thread as each file finishes, so
+    /// uploads overlap with the remaining decodes.
+    ///
+    /// # Parameters
+    ///
+    /// - `paths`: Paths to the files to decode.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Returns
+    ///
+    /// The player or error for each path, in the order of `paths`.
+    pub fn load_many<I, P>(paths: I, app: &App) -> Vec<Result<Self>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .map(|p| p.as_ref().to_path_buf())
+            .collect();
+        let mut results: Vec<Option<Result<Self>>> = paths.iter().map(|_| None).collect();
+        decode_parallel(&paths, |index, result| {
+            results[index] = Some(result.map(|data| data.into_player(app)));
+        });
+        results.into_iter().flatten().collect()
+    }
+
+    /// Decodes the file at `path` without blocking the caller, then uploads
+    /// the textures; see [`WebpAnimationData::from_file_async`].
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P, app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::from_file_async(path)
+            .await?
+            .into_player(app))
+    }
+}
+
+#[cfg(feature = "nannou")]
+impl WebpAnimationBuilder {
+    /// Starts decoding the file on a background thread; the player returned
+    /// by [`LoadHandle::try_take`] uses the options of this builder.
+    ///
+    /// Hot reloading is not started for players loaded this way.
+    pub fn load_async(self) -> LoadHandle {
+        let builder = self.clone();
+        let mut handle = LoadHandle::spawn(move || builder.decode());
+        handle.builder = Some(self);
+        handle
+    }
+}
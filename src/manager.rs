@@ -0,0 +1,215 @@
+use crate::animation::WebpAnimationPlayer;
+use crate::error::{Result, WebpAnimationError};
+use nannou::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Aggregate memory use of the animations in an [`AnimationManager`].
+///
+/// Frames and textures shared between players (clones, or loads through the
+/// [`AnimationCache`](crate::AnimationCache)) are counted once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of animations managed.
+    pub animations: usize,
+    /// Number of distinct decoded frames.
+    pub frames: usize,
+    /// Bytes held by the decoded frames on the CPU.
+    pub decoded_bytes: u64,
+    /// Bytes of the frame textures uploaded so far, estimated as RGBA8.
+    pub texture_bytes: u64,
+}
+
+/// Owns many animations keyed by name.
+///
+/// Larger sketches can keep every clip in one place instead of hand-rolling
+/// the bookkeeping: load them in `model`, call
+/// [`update_all`](Self::update_all) once per frame and look them up by name
+/// when drawing.
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use nannou_webp_animation::{AnimationManager, DrawWebpExt};
+/// # fn model(app: &App) -> nannou_webp_animation::Result<AnimationManager> {
+/// let mut animations = AnimationManager::new();
+/// animations.load("intro", "assets/intro.webp", app)?;
+/// animations.load("loop", "assets/loop.webp", app)?;
+/// # Ok(animations)
+/// # }
+/// # fn view(app: &App, animations: &AnimationManager) {
+/// let draw = app.draw();
+/// if let Some(intro) = animations.get("intro") {
+///     draw.webp(intro).xy(pt2(0.0, 0.0)).w_h(256.0, 256.0);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AnimationManager {
+    /// The animations, ordered by name.
+    animations: BTreeMap<String, WebpAnimationPlayer>,
+}
+
+impl AnimationManager {
+    /// Creates an empty manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `animation` under `name`, returning the animation it replaces.
+    pub fn insert<N: Into<String>>(
+        &mut self,
+        name: N,
+        animation: WebpAnimationPlayer,
+    ) -> Option<WebpAnimationPlayer> {
+        self.animations.insert(name.into(), animation)
+    }
+
+    /// Decodes the file at `path` and adds it under `name`.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The name to look the animation up by.
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be decoded or contains no frames.
+    pub fn load<N, P>(&mut self, name: N, path: P, app: &App) -> Result<()>
+    where
+        N: Into<String>,
+        P: AsRef<Path>,
+    {
+        let animation = WebpAnimationPlayer::from_file(path, app)?;
+        self.insert(name, animation);
+        Ok(())
+    }
+
+    /// Decodes several files concurrently with
+    /// [`WebpAnimationPlayer::load_many`] and adds them under their names.
+    ///
+    /// # Parameters
+    ///
+    /// - `entries`: Pairs of names and paths.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Returns
+    ///
+    /// The names of the files that could not be loaded, with their errors.
+    /// Every other file has been added.
+    pub fn load_many<I, N, P>(&mut self, entries: I, app: &App) -> Vec<(String, WebpAnimationError)>
+    where
+        I: IntoIterator<Item = (N, P)>,
+        N: Into<String>,
+        P: AsRef<Path>,
+    {
+        let (names, paths): (Vec<String>, Vec<P>) = entries
+            .into_iter()
+            .map(|(name, path)| (name.into(), path))
+            .unzip();
+        let mut failures = Vec::new();
+        for (name, result) in names
+            .into_iter()
+            .zip(WebpAnimationPlayer::load_many(paths, app))
+        {
+            match result {
+                Ok(animation) => {
+                    self.animations.insert(name, animation);
+                }
+                Err(error) => failures.push((name, error)),
+            }
+        }
+        failures
+    }
+
+    /// Removes and returns the animation called `name`.
+    pub fn remove(&mut self, name: &str) -> Option<WebpAnimationPlayer> {
+        self.animations.remove(name)
+    }
+
+    /// Returns the animation called `name`.
+    pub fn get(&self, name: &str) -> Option<&WebpAnimationPlayer> {
+        self.animations.get(name)
+    }
+
+    /// Returns the animation called `name` for playback control.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut WebpAnimationPlayer> {
+        self.animations.get_mut(name)
+    }
+
+    /// Returns `true` if an animation is called `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.animations.contains_key(name)
+    }
+
+    /// Returns the number of animations.
+    pub fn len(&self) -> usize {
+        self.animations.len()
+    }
+
+    /// Returns `true` if no animations are managed.
+    pub fn is_empty(&self) -> bool {
+        self.animations.is_empty()
+    }
+
+    /// Iterates over the names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.animations.keys().map(String::as_str)
+    }
+
+    /// Iterates over the animations with their names, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &WebpAnimationPlayer)> + '_ {
+        self.animations
+            .iter()
+            .map(|(name, animation)| (name.as_str(), animation))
+    }
+
+    /// Iterates mutably over the animations with their names, sorted by name.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut WebpAnimationPlayer)> + '_ {
+        self.animations
+            .iter_mut()
+            .map(|(name, animation)| (name.as_str(), animation))
+    }
+
+    /// Calls [`WebpAnimationPlayer::update`] on every animation.
+    pub fn update_all(&mut self) {
+        for animation in self.animations.values_mut() {
+            animation.update();
+        }
+    }
+
+    /// Calls [`WebpAnimationPlayer::warm_up`] on every animation.
+    pub fn warm_up_all(&self, app: &App) {
+        for animation in self.animations.values() {
+            animation.warm_up(app);
+        }
+    }
+
+    /// Removes every animation.
+    pub fn clear(&mut self) {
+        self.animations.clear();
+    }
+
+    /// Sums the memory used by the managed animations.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut stats = MemoryStats {
+            animations: self.animations.len(),
+            ..MemoryStats::default()
+        };
+        let mut seen_frames = HashSet::new();
+        let mut seen_textures = HashSet::new();
+        for animation in self.animations.values() {
+            let data = animation.data();
+            if seen_frames.insert(Arc::as_ptr(&data.frames)) {
+                stats.frames += data.frame_count();
+                stats.decoded_bytes += data.memory_usage().cpu_bytes;
+            }
+            let (textures, bytes) = animation.texture_usage();
+            if seen_textures.insert(textures) {
+                stats.texture_bytes += bytes;
+            }
+        }
+        stats
+    }
+}
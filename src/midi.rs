@@ -0,0 +1,186 @@
+use crate::animation::WebpAnimation;
+use crate::error::{Result, WebpAnimationError};
+use midir::{MidiInput, MidiInputConnection};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// MIDI clock pulses per quarter note.
+const PPQN: u64 = 24;
+/// MIDI realtime status bytes.
+const CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const CONTINUE: u8 = 0xFB;
+const STOP: u8 = 0xFC;
+
+/// Clock state shared with the MIDI input thread.
+#[derive(Debug, Default)]
+struct ClockState {
+    /// Pulses received since the last start message.
+    ticks: u64,
+    /// Whether the clock source is playing.
+    running: bool,
+    /// When the most recent pulse arrived.
+    last_tick: Option<Instant>,
+    /// Smoothed time between pulses.
+    tick_interval: Option<Duration>,
+}
+
+impl ClockState {
+    /// Updates the state from one incoming MIDI message.
+    fn handle(&mut self, message: &[u8]) {
+        match message.first() {
+            Some(&CLOCK) => {
+                let now = Instant::now();
+                if let Some(last) = self.last_tick {
+                    let interval = now - last;
+                    // Average out the jitter of USB MIDI and the OS scheduler.
+                    self.tick_interval = Some(match self.tick_interval {
+                        Some(smoothed) => smoothed.mul_f64(0.9) + interval.mul_f64(0.1),
+                        None => interval,
+                    });
+                }
+                self.last_tick = Some(now);
+                if self.running {
+                    self.ticks += 1;
+                }
+            }
+            Some(&START) => {
+                self.ticks = 0;
+                self.running = true;
+            }
+            Some(&CONTINUE) => self.running = true,
+            Some(&STOP) => self.running = false,
+            _ => {}
+        }
+    }
+
+    /// Returns the position in pulses, interpolated between pulses so playback
+    /// stays smooth at display frame rates.
+    fn position(&self) -> f64 {
+        let fraction = match (self.last_tick, self.tick_interval) {
+            (Some(last), Some(interval)) if self.running && !interval.is_zero() => {
+                (last.elapsed().as_secs_f64() / interval.as_secs_f64()).min(1.0)
+            }
+            _ => 0.0,
+        };
+        self.ticks as f64 + fraction
+    }
+}
+
+/// Slaves animation playback to an incoming MIDI clock (24 pulses per quarter
+/// note), keeping loops beat-locked in live sets.
+///
+/// One playthrough of the animation is stretched to a whole number of bars, so
+/// every loop restarts on a bar line. A MIDI start message restarts the
+/// animation on the downbeat, stop pauses it and continue resumes it.
+///
+/// ```no_run
+/// # use nannou_webp_animation::{MidiClockSync, WebpAnimation};
+/// # fn update(clock: &MidiClockSync, animation: &mut WebpAnimation) {
+/// // In `model`: let clock = MidiClockSync::connect("IAC")?.bars_per_loop(2);
+/// clock.apply(animation);
+/// # }
+/// ```
+pub struct MidiClockSync {
+    /// Keeps the MIDI input open; dropping it closes the port.
+    _connection: MidiInputConnection<()>,
+    /// Clock state updated by the MIDI input thread.
+    state: Arc<Mutex<ClockState>>,
+    /// Bars one playthrough of the animation is stretched to.
+    bars_per_loop: u32,
+    /// Quarter notes per bar.
+    beats_per_bar: u32,
+}
+
+impl MidiClockSync {
+    /// Listens for MIDI clock on the first input port whose name contains
+    /// `port_name`. An empty name selects the first port.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if MIDI is unavailable, no port matches or the port
+    /// cannot be opened.
+    pub fn connect(port_name: &str) -> Result<Self> {
+        let mut input = MidiInput::new("nannou_webp_animation").map_err(midi_error)?;
+        // Clock and transport messages are filtered out by default.
+        input.ignore(midir::Ignore::SysexAndActiveSense);
+
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| {
+                input
+                    .port_name(port)
+                    .is_ok_and(|name| name.contains(port_name))
+            })
+            .ok_or_else(|| {
+                WebpAnimationError::Midi(format!("no MIDI input port matching {:?}", port_name))
+            })?;
+
+        let state = Arc::new(Mutex::new(ClockState::default()));
+        let shared = Arc::clone(&state);
+        let connection = input
+            .connect(
+                &port,
+                "nannou_webp_animation clock",
+                move |_, message, _| {
+                    if let Ok(mut state) = shared.lock() {
+                        state.handle(message);
+                    }
+                },
+                (),
+            )
+            .map_err(midi_error)?;
+
+        Ok(Self {
+            _connection: connection,
+            state,
+            bars_per_loop: 1,
+            beats_per_bar: 4,
+        })
+    }
+
+    /// Sets how many bars one playthrough of the animation lasts. Defaults to `1`.
+    pub fn bars_per_loop(mut self, bars: u32) -> Self {
+        self.bars_per_loop = bars.max(1);
+        self
+    }
+
+    /// Sets the number of quarter notes per bar. Defaults to `4`.
+    pub fn beats_per_bar(mut self, beats: u32) -> Self {
+        self.beats_per_bar = beats.max(1);
+        self
+    }
+
+    /// Returns the tempo of the incoming clock, once at least two pulses arrived.
+    pub fn bpm(&self) -> Option<f64> {
+        let interval = self.state.lock().ok()?.tick_interval?;
+        (!interval.is_zero()).then(|| 60.0 / (interval.as_secs_f64() * PPQN as f64))
+    }
+
+    /// Returns `true` while the clock source is playing.
+    pub fn is_running(&self) -> bool {
+        self.state.lock().is_ok_and(|state| state.running)
+    }
+
+    /// Moves the playhead of `animation` to the current clock position.
+    ///
+    /// Call this every update instead of [`WebpAnimation::update`]; the
+    /// animation's own clock is paused while it is slaved.
+    pub fn apply(&self, animation: &mut WebpAnimation) {
+        let position = match self.state.lock() {
+            Ok(state) => state.position(),
+            Err(_) => return,
+        };
+        let loop_ticks = (PPQN * self.beats_per_bar as u64 * self.bars_per_loop as u64) as f64;
+        let phase = (position % loop_ticks) / loop_ticks;
+
+        animation.pause();
+        animation.seek_time(animation.duration().mul_f64(phase));
+    }
+}
+
+/// Wraps an error reported by `midir`.
+fn midi_error<E: std::fmt::Display>(error: E) -> WebpAnimationError {
+    WebpAnimationError::Midi(error.to_string())
+}
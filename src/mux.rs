@@ -0,0 +1,330 @@
+use crate::bindings::*;
+use crate::error::{Result, WebpAnimationError};
+
+use std::ffi::CStr;
+use std::fs;
+use std::path::Path;
+use std::slice;
+use std::time::Duration;
+
+use libc::{c_int, c_void};
+
+//---------------------------------------------------------------------
+// Owned frame chunk
+//---------------------------------------------------------------------
+
+/// A single ANMF chunk copied out of the mux, together with the metadata
+/// needed to push it back.
+#[derive(Clone)]
+struct MuxFrame {
+    /// Compressed frame bitstream (VP8/VP8L + optional ALPH).
+    bitstream: Vec<u8>,
+    /// Frame metadata as returned by `WebPMuxGetFrame`. Its `bitstream`
+    /// field is re-pointed at `bitstream` before use.
+    info: WebPMuxFrameInfo,
+}
+
+//---------------------------------------------------------------------
+// Public mux editor
+//---------------------------------------------------------------------
+
+/// Chunk-level editor for animated WebP files built on libwebp’s `WebPMux`.
+///
+/// Unlike [`WebpEncoder`](crate::WebpEncoder), the editor never decodes or
+/// re-encodes pixels: it rewrites the frame chunks of an existing file. This
+/// preserves quality exactly and is orders of magnitude faster, which makes
+/// it the right tool for retiming.
+pub struct WebpMuxEditor {
+    /// Owned libwebp mux object, freed on drop.
+    mux: *mut WebPMux,
+}
+
+impl WebpMuxEditor {
+    /// Open the animated WebP at `path` for editing.
+    ///
+    /// # Errors
+    /// * I/O failures (file not found, no read permission, …)
+    /// * Corrupted / unsupported WebP payloads
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = fs::read(path)?;
+        Self::from_bytes(&data)
+    }
+
+    /// Parse an animated WebP held in memory. The data is copied.
+    ///
+    /// # Errors
+    /// * Corrupted / unsupported WebP payloads
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let webp_data = WebPData {
+            bytes: data.as_ptr(),
+            size: data.len(),
+        };
+        let mux = unsafe { WebPMuxCreateInternal(&webp_data, 1, WEBP_MUX_ABI_VERSION as c_int) };
+        if mux.is_null() {
+            return Err(WebpAnimationError::Mux(
+                "WebPMuxCreateInternal failed".into(),
+            ));
+        }
+        Ok(Self { mux })
+    }
+
+    /// Returns the number of frames in the animation.
+    ///
+    /// # Errors
+    /// * libwebp failing to count the frame chunks
+    pub fn frame_count(&self) -> Result<usize> {
+        let mut count: c_int = 0;
+        let err = unsafe { WebPMuxNumChunks(self.mux, WebPChunkId_WEBP_CHUNK_ANMF, &mut count) };
+        check(err, "WebPMuxNumChunks failed")?;
+        Ok(count as usize)
+    }
+
+    /// Returns the display duration of every frame.
+    ///
+    /// # Errors
+    /// * libwebp failing to read a frame chunk
+    pub fn durations(&self) -> Result<Vec<Duration>> {
+        Ok(self
+            .frames()?
+            .iter()
+            .map(|frame| Duration::from_millis(frame.info.duration.max(0) as u64))
+            .collect())
+    }
+
+    /// Replace the duration of every frame.
+    ///
+    /// # Errors
+    /// * `durations` does not have one entry per frame
+    /// * libwebp failing to rewrite the frame chunks
+    pub fn set_durations(&mut self, durations: &[Duration]) -> Result<()> {
+        let mut frames = self.frames()?;
+        if durations.len() != frames.len() {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "expected {} durations, got {}",
+                frames.len(),
+                durations.len()
+            )));
+        }
+        for (frame, duration) in frames.iter_mut().zip(durations) {
+            frame.info.duration = duration.as_millis() as c_int;
+        }
+        self.replace_frames(frames)
+    }
+
+    /// Change the playback speed by scaling every frame duration by
+    /// `1 / speed`; e.g. `2.0` plays twice as fast.
+    ///
+    /// # Errors
+    /// * `speed` is not a positive finite number
+    /// * libwebp failing to rewrite the frame chunks
+    pub fn set_speed(&mut self, speed: f64) -> Result<()> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(WebpAnimationError::InvalidArgument(format!(
+                "invalid playback speed: {}",
+                speed
+            )));
+        }
+        let mut frames = self.frames()?;
+        for frame in frames.iter_mut() {
+            frame.info.duration = (frame.info.duration as f64 / speed).round() as c_int;
+        }
+        self.replace_frames(frames)
+    }
+
+    /// Remove the frame at `index` (0-based).
+    ///
+    /// # Errors
+    /// * `index` is out of range
+    /// * libwebp failing to delete the frame chunk
+    pub fn remove_frame(&mut self, index: usize) -> Result<()> {
+        self.check_index(index)?;
+        let err = unsafe { WebPMuxDeleteFrame(self.mux, index as u32 + 1) };
+        check(err, "WebPMuxDeleteFrame failed")
+    }
+
+    /// Insert a copy of the frame at `index` directly after it.
+    ///
+    /// # Errors
+    /// * `index` is out of range
+    /// * libwebp failing to rewrite the frame chunks
+    pub fn duplicate_frame(&mut self, index: usize) -> Result<()> {
+        self.check_index(index)?;
+        let mut frames = self.frames()?;
+        let copy = frames[index].clone();
+        frames.insert(index + 1, copy);
+        self.replace_frames(frames)
+    }
+
+    /// Rebuild the animation from the frames at `order`.
+    ///
+    /// `order` lists source frame indices (0-based) in their new order.
+    /// Indices may be repeated or omitted, so this covers reordering,
+    /// dropping and duplicating frames in a single pass.
+    ///
+    /// # Errors
+    /// * `order` is empty or contains an out-of-range index
+    /// * libwebp failing to rewrite the frame chunks
+    pub fn reorder(&mut self, order: &[usize]) -> Result<()> {
+        if order.is_empty() {
+            return Err(WebpAnimationError::InvalidArgument(
+                "an animation needs at least one frame".into(),
+            ));
+        }
+        let frames = self.frames()?;
+        let reordered = order
+            .iter()
+            .map(|&index| {
+                frames.get(index).cloned().ok_or_else(|| {
+                    WebpAnimationError::InvalidArgument(format!(
+                        "frame index {} out of range",
+                        index
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.replace_frames(reordered)
+    }
+
+    /// Embed an ICC color profile (`ICCP` chunk), replacing any existing one.
+    ///
+    /// # Errors
+    /// * libwebp failing to store the chunk
+    pub fn set_icc_profile(&mut self, data: &[u8]) -> Result<()> {
+        self.set_chunk(c"ICCP", data)
+    }
+
+    /// Embed EXIF metadata (`EXIF` chunk), replacing any existing one.
+    ///
+    /// # Errors
+    /// * libwebp failing to store the chunk
+    pub fn set_exif(&mut self, data: &[u8]) -> Result<()> {
+        self.set_chunk(c"EXIF", data)
+    }
+
+    /// Embed XMP metadata (`XMP ` chunk), replacing any existing one.
+    ///
+    /// # Errors
+    /// * libwebp failing to store the chunk
+    pub fn set_xmp(&mut self, data: &[u8]) -> Result<()> {
+        self.set_chunk(c"XMP ", data)
+    }
+
+    /// Assemble the edited animation into a WebP held in memory.
+    ///
+    /// # Errors
+    /// * libwebp failing to assemble the RIFF container
+    pub fn assemble(&self) -> Result<Vec<u8>> {
+        let mut webp_data: WebPData = unsafe { std::mem::zeroed() };
+        let err = unsafe { WebPMuxAssemble(self.mux, &mut webp_data) };
+        check(err, "WebPMuxAssemble failed")?;
+
+        // Copy into Rust‑owned memory and release libwebp’s buffer.
+        let bytes = unsafe { slice::from_raw_parts(webp_data.bytes, webp_data.size) }.to_vec();
+        unsafe { WebPFree(webp_data.bytes as *mut c_void) };
+
+        Ok(bytes)
+    }
+
+    /// Assemble the edited animation and write it to `path`.
+    ///
+    /// # Errors
+    /// See [`assemble`](Self::assemble); additionally I/O failures while writing.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let data = self.assemble()?;
+        Ok(fs::write(path, data)?)
+    }
+
+    /// Store a copy of `data` in the chunk identified by `fourcc`.
+    fn set_chunk(&mut self, fourcc: &CStr, data: &[u8]) -> Result<()> {
+        let chunk = WebPData {
+            bytes: data.as_ptr(),
+            size: data.len(),
+        };
+        let err = unsafe { WebPMuxSetChunk(self.mux, fourcc.as_ptr(), &chunk, 1) };
+        check(err, "WebPMuxSetChunk failed")
+    }
+
+    /// Ensure `index` refers to an existing frame.
+    fn check_index(&self, index: usize) -> Result<()> {
+        let count = self.frame_count()?;
+        if index < count {
+            Ok(())
+        } else {
+            Err(WebpAnimationError::InvalidArgument(format!(
+                "frame index {} out of range for {} frames",
+                index, count
+            )))
+        }
+    }
+
+    /// Copy every frame chunk out of the mux.
+    fn frames(&self) -> Result<Vec<MuxFrame>> {
+        let count = self.frame_count()?;
+        let mut frames = Vec::with_capacity(count);
+        for nth in 1..=count {
+            let mut info: WebPMuxFrameInfo = unsafe { std::mem::zeroed() };
+            let err = unsafe { WebPMuxGetFrame(self.mux, nth as u32, &mut info) };
+
+            // `WebPMuxGetFrame` allocates `info.bitstream` with `WebPMalloc`;
+            // copy it and free the original, on failure too.
+            let bitstream = if info.bitstream.bytes.is_null() {
+                Vec::new()
+            } else {
+                let bytes =
+                    unsafe { slice::from_raw_parts(info.bitstream.bytes, info.bitstream.size) }
+                        .to_vec();
+                unsafe { WebPFree(info.bitstream.bytes as *mut c_void) };
+                bytes
+            };
+            info.bitstream = WebPData {
+                bytes: std::ptr::null(),
+                size: 0,
+            };
+            check(err, "WebPMuxGetFrame failed")?;
+            frames.push(MuxFrame { bitstream, info });
+        }
+        Ok(frames)
+    }
+
+    /// Remove all frame chunks and push `frames` in their place.
+    fn replace_frames(&mut self, frames: Vec<MuxFrame>) -> Result<()> {
+        for _ in 0..self.frame_count()? {
+            let err = unsafe { WebPMuxDeleteFrame(self.mux, 1) };
+            check(err, "WebPMuxDeleteFrame failed")?;
+        }
+
+        for mut frame in frames {
+            frame.info.bitstream = WebPData {
+                bytes: frame.bitstream.as_ptr(),
+                size: frame.bitstream.len(),
+            };
+            // copy_data = 1: the mux keeps its own copy of the bitstream.
+            let err = unsafe { WebPMuxPushFrame(self.mux, &frame.info, 1) };
+            check(err, "WebPMuxPushFrame failed")?;
+        }
+        Ok(())
+    }
+}
+
+// SAFETY: the editor exclusively owns the mux object, which holds its own copy
+// of the chunk data, and libwebp keeps no thread-local state, so it can be
+// moved to another thread; every method that edits it takes `&mut self`.
+unsafe impl Send for WebpMuxEditor {}
+
+impl Drop for WebpMuxEditor {
+    fn drop(&mut self) {
+        unsafe { WebPMuxDelete(self.mux) };
+    }
+}
+
+/// Turn a `WebPMuxError` into a `Result`, using `context` as the message.
+fn check(err: WebPMuxError, context: &str) -> Result<()> {
+    if err == WebPMuxError_WEBP_MUX_OK {
+        Ok(())
+    } else {
+        Err(WebpAnimationError::Mux(format!(
+            "{} (error code {})",
+            context, err
+        )))
+    }
+}
@@ -0,0 +1,149 @@
+use crate::animation::WebpAnimation;
+use crate::error::Result;
+use nannou_osc as osc;
+
+/// A playback command decoded from an OSC message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Command {
+    /// Resumes playback.
+    Play,
+    /// Pauses playback.
+    Pause,
+    /// Jumps to a frame index.
+    SeekFrame(usize),
+    /// Jumps to a position from `0.0` (first frame) to `1.0` (last frame).
+    SeekPosition(f32),
+    /// Sets the playback speed.
+    Speed(f32),
+    /// Enables or disables looping.
+    Looping(bool),
+}
+
+/// Drives animations from OSC messages, e.g. sent by TouchOSC or Max.
+///
+/// With the default `/webp` prefix the following addresses are understood:
+///
+/// | Address | Argument | Effect |
+/// | --- | --- | --- |
+/// | `/webp/play` | none or non-zero | Resumes playback |
+/// | `/webp/pause` | none or non-zero | Pauses playback |
+/// | `/webp/seek` | int | Jumps to a frame index |
+/// | `/webp/seek` | float | Jumps to a position from `0.0` to `1.0` |
+/// | `/webp/speed` | float | Sets the playback speed |
+/// | `/webp/loop` | bool, int or float | Enables looping if true or non-zero |
+///
+/// Buttons typically send `1` on press and `0` on release, so `play` and
+/// `pause` ignore a zero argument. Other messages are ignored.
+///
+/// ```no_run
+/// # use nannou_webp_animation::{OscControl, WebpAnimation};
+/// # fn update(osc: &OscControl, animation: &mut WebpAnimation) {
+/// // In `model`: let osc = OscControl::bind(9000)?;
+/// osc.apply(animation);
+/// animation.update();
+/// # }
+/// ```
+pub struct OscControl {
+    /// Socket receiving the messages.
+    receiver: osc::Receiver,
+    /// Address prefix the commands are expected under.
+    prefix: String,
+}
+
+impl OscControl {
+    /// Listens for OSC messages on UDP `port`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the port cannot be bound.
+    pub fn bind(port: u16) -> Result<Self> {
+        Ok(Self {
+            receiver: osc::receiver(port)?,
+            prefix: "/webp".to_string(),
+        })
+    }
+
+    /// Sets the address prefix, e.g. `"/deck/1"` to control one of several
+    /// animations listening on the same port. Defaults to `"/webp"`.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Applies all messages received since the last call to `animation`.
+    pub fn apply(&self, animation: &mut WebpAnimation) {
+        self.apply_all(std::iter::once(animation));
+    }
+
+    /// Applies all messages received since the last call to every animation in
+    /// `animations`, keeping a group of animations in sync.
+    pub fn apply_all<'a, I>(&self, animations: I)
+    where
+        I: IntoIterator<Item = &'a mut WebpAnimation>,
+    {
+        let commands: Vec<Command> = self
+            .receiver
+            .try_iter()
+            .flat_map(|(packet, _)| packet.into_msgs())
+            .filter_map(|message| self.parse(&message))
+            .collect();
+        if commands.is_empty() {
+            return;
+        }
+
+        for animation in animations {
+            for &command in &commands {
+                execute(animation, command);
+            }
+        }
+    }
+
+    /// Decodes `message`, returning `None` for unknown addresses or arguments.
+    fn parse(&self, message: &osc::Message) -> Option<Command> {
+        let command = message.addr.strip_prefix(&self.prefix)?;
+        let argument = message.args.first();
+        let pressed = argument.and_then(as_f32).is_none_or(|value| value != 0.0);
+
+        match (command, argument) {
+            ("/play", _) if pressed => Some(Command::Play),
+            ("/pause", _) if pressed => Some(Command::Pause),
+            ("/seek", Some(osc::Type::Int(index))) => {
+                Some(Command::SeekFrame((*index).max(0) as usize))
+            }
+            ("/seek", Some(argument)) => as_f32(argument).map(Command::SeekPosition),
+            ("/speed", Some(argument)) => as_f32(argument).map(Command::Speed),
+            ("/loop", Some(argument)) => {
+                as_f32(argument).map(|value| Command::Looping(value != 0.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Applies `command` to `animation`.
+fn execute(animation: &mut WebpAnimation, command: Command) {
+    match command {
+        Command::Play => animation.play(),
+        Command::Pause => animation.pause(),
+        Command::SeekFrame(index) => animation.seek_frame(index),
+        Command::SeekPosition(position) => {
+            let last = animation.frame_count() - 1;
+            let index = (position.clamp(0.0, 1.0) * last as f32).round() as usize;
+            animation.seek_frame(index);
+        }
+        Command::Speed(speed) => animation.set_speed(speed),
+        Command::Looping(looping) => animation.set_looping(looping),
+    }
+}
+
+/// Reads a numeric or boolean OSC argument as `f32`.
+fn as_f32(argument: &osc::Type) -> Option<f32> {
+    match argument {
+        osc::Type::Float(value) => Some(*value),
+        osc::Type::Double(value) => Some(*value as f32),
+        osc::Type::Int(value) => Some(*value as f32),
+        osc::Type::Long(value) => Some(*value as f32),
+        osc::Type::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
@@ -0,0 +1,569 @@
+use crate::clock::{Clock, Instant, ManualClock};
+use crate::data::WebpAnimationData;
+use crate::frame::WebpFrame;
+use crate::settings::AnimationSettings;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The order in which frames are played.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlaybackDirection {
+    /// From the first frame to the last.
+    #[default]
+    Forward,
+    /// From the last frame to the first.
+    Backward,
+}
+
+/// The complete playhead state of a player, captured with `playback_state`
+/// and applied with `restore`.
+///
+/// With the `serde` feature the snapshot can be persisted, so an installation
+/// resumes exactly where it left off after a restart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaybackSnapshot {
+    /// Index of the frame being shown.
+    pub frame_index: usize,
+    /// Time already spent on that frame, in animation time (i.e. scaled by the
+    /// playback speed).
+    pub elapsed: Duration,
+    /// Direction frames are advanced in.
+    pub direction: PlaybackDirection,
+    /// Number of times playback has wrapped around.
+    pub loop_count: u64,
+    /// Whether playback was running rather than paused.
+    pub is_playing: bool,
+}
+
+/// Plays decoded frames without a window, a GPU or the real clock.
+///
+/// `HeadlessPlayer` follows the same playback rules as
+/// [`WebpAnimationPlayer`](crate::WebpAnimationPlayer), but time only passes
+/// when [`advance`](Self::advance) is called. Animation logic can therefore be
+/// unit-tested in CI without creating a nannou `App`, and does not need the
+/// `nannou` feature.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use nannou_webp_animation::{HeadlessPlayer, WebpAnimationData};
+/// # fn main() -> nannou_webp_animation::Result<()> {
+/// let mut player = HeadlessPlayer::new(WebpAnimationData::from_file("intro.webp")?);
+/// player.set_looping(false);
+/// for _ in 0..1000 {
+///     player.advance(Duration::from_millis(16));
+/// }
+/// assert_eq!(player.current_frame_index(), player.frame_count() - 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct HeadlessPlayer {
+    /// Decoded frames of the animation.
+    data: WebpAnimationData,
+    /// The clock `advance` moves forward. Not shared with clones.
+    clock: ManualClock,
+    /// The current frame and the time spent on it, measured against `clock`.
+    playhead: Playhead,
+}
+
+impl HeadlessPlayer {
+    /// Creates a playing player at the first frame, looping according to
+    /// [`AnimationSettings::looping`].
+    pub fn new(data: WebpAnimationData) -> Self {
+        let clock = ManualClock::new();
+        let playhead = Playhead::new(Arc::new(clock.clone()), AnimationSettings::global().looping);
+        Self {
+            data,
+            clock,
+            playhead,
+        }
+    }
+
+    /// Lets `delta` of real time pass and updates the current frame, like one
+    /// call to [`WebpAnimationPlayer::update`](crate::WebpAnimationPlayer::update)
+    /// made `delta` after the previous one.
+    ///
+    /// As with the player, at most one frame is advanced per call and the
+    /// time beyond the end of the frame is dropped.
+    pub fn advance(&mut self, delta: Duration) {
+        self.clock.advance(delta);
+        self.playhead.advance(&self.data.frames);
+    }
+
+    /// Returns the decoded frames.
+    pub fn data(&self) -> &WebpAnimationData {
+        &self.data
+    }
+
+    /// Returns the decoded frames, dropping the playhead.
+    pub fn into_data(self) -> WebpAnimationData {
+        self.data
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn frame_count(&self) -> usize {
+        self.data.frame_count()
+    }
+
+    /// Returns the total duration of one playthrough at normal speed.
+    pub fn duration(&self) -> Duration {
+        self.data.duration()
+    }
+
+    /// Returns the index of the frame currently being shown.
+    pub fn current_frame_index(&self) -> usize {
+        self.playhead.frame_index
+    }
+
+    /// Returns the frame currently being shown.
+    pub fn current_frame(&self) -> &WebpFrame {
+        &self.data.frames[self.playhead.frame_index]
+    }
+
+    /// Returns the playhead position measured from the start of the animation.
+    pub fn timestamp(&self) -> Duration {
+        self.playhead.timestamp(&self.data.frames)
+    }
+
+    /// Jumps to the start of the frame at `index`, clamped to the last frame.
+    pub fn seek_frame(&mut self, index: usize) {
+        self.playhead.seek_frame(index, self.data.frames.len());
+    }
+
+    /// Jumps to the playhead position `time`, measured from the start of the
+    /// animation at normal speed and clamped to its duration.
+    pub fn seek_time(&mut self, time: Duration) {
+        self.playhead.seek_time(&self.data.frames, time);
+    }
+
+    /// Sets whether the animation should loop after reaching the final frame.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.playhead.looping = looping;
+    }
+
+    /// Returns whether the animation loops after reaching the final frame.
+    pub fn is_looping(&self) -> bool {
+        self.playhead.looping
+    }
+
+    /// Sets the playback speed multiplier, from the current position onwards.
+    ///
+    /// Negative or non-finite values are treated as `0.0`, which pauses playback.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.playhead.set_speed(speed);
+    }
+
+    /// Returns the playback speed multiplier.
+    pub fn speed(&self) -> f32 {
+        self.playhead.speed()
+    }
+
+    /// Sets the order in which frames are played.
+    pub fn set_direction(&mut self, direction: PlaybackDirection) {
+        self.playhead.direction = direction;
+    }
+
+    /// Returns the order in which frames are played.
+    pub fn direction(&self) -> PlaybackDirection {
+        self.playhead.direction
+    }
+
+    /// Returns how many times playback has wrapped around since the player was
+    /// created or restored.
+    pub fn loop_count(&self) -> u64 {
+        self.playhead.loop_count
+    }
+
+    /// Captures the playhead so it can be resumed later with [`restore`](Self::restore).
+    pub fn playback_state(&self) -> PlaybackSnapshot {
+        self.playhead.snapshot()
+    }
+
+    /// Moves the playhead to a state captured with [`playback_state`](Self::playback_state).
+    ///
+    /// The frame index is clamped to the last frame, so snapshots stay usable
+    /// after the animation was edited.
+    pub fn restore(&mut self, snapshot: PlaybackSnapshot) {
+        self.playhead.restore(snapshot, self.data.frames.len());
+    }
+
+    /// Freezes the playhead until [`play`](Self::play) is called.
+    pub fn pause(&mut self) {
+        self.playhead.pause();
+    }
+
+    /// Resumes playback after [`pause`](Self::pause) from where it stopped.
+    pub fn play(&mut self) {
+        self.playhead.play();
+    }
+
+    /// Returns `true` unless playback is paused.
+    pub fn is_playing(&self) -> bool {
+        self.playhead.is_playing()
+    }
+}
+
+impl Clone for HeadlessPlayer {
+    fn clone(&self) -> Self {
+        // Clones of a `ManualClock` share its time, so the clone gets its own.
+        let clock = ManualClock::new();
+        let mut playhead = self.playhead.clone();
+        playhead.set_clock(Arc::new(clock.clone()));
+        Self {
+            data: self.data.clone(),
+            clock,
+            playhead,
+        }
+    }
+}
+
+impl fmt::Debug for HeadlessPlayer {
+    /// Summarizes the animation and playhead instead of dumping frames.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeadlessPlayer")
+            .field("frame_count", &self.frame_count())
+            .field("current_frame_index", &self.playhead.frame_index)
+            .field("timestamp", &self.timestamp())
+            .field("is_playing", &self.is_playing())
+            .field("is_looping", &self.playhead.looping)
+            .field("direction", &self.playhead.direction)
+            .field("loop_count", &self.playhead.loop_count)
+            .field("speed", &self.playhead.speed())
+            .finish()
+    }
+}
+
+impl From<WebpAnimationData> for HeadlessPlayer {
+    fn from(data: WebpAnimationData) -> Self {
+        Self::new(data)
+    }
+}
+
+/// The current frame of a player and the time spent on it, measured against a
+/// [`Clock`]. Shared by [`HeadlessPlayer`] and
+/// [`WebpAnimationPlayer`](crate::WebpAnimationPlayer), which differ only in
+/// how they present the frames.
+#[derive(Clone, Debug)]
+pub(crate) struct Playhead {
+    /// Index of the current frame in the animation sequence.
+    pub(crate) frame_index: usize,
+    /// Time when the current frame started.
+    last_frame_time: Instant,
+    /// Source of `last_frame_time` and the current time.
+    clock: Arc<dyn Clock>,
+    /// Unscaled time spent on the current frame when playback was paused, or
+    /// `None` while playing.
+    paused: Option<Duration>,
+    /// Indicates whether the animation should loop when it reaches the end.
+    pub(crate) looping: bool,
+    /// Direction frames are advanced in.
+    pub(crate) direction: PlaybackDirection,
+    /// Number of times playback has wrapped around.
+    pub(crate) loop_count: u64,
+    /// Playback speed multiplier applied to the elapsed time.
+    speed: f32,
+}
+
+impl Playhead {
+    /// Creates a playing playhead at the start of the first frame.
+    pub(crate) fn new(clock: Arc<dyn Clock>, looping: bool) -> Self {
+        Self {
+            frame_index: 0,
+            last_frame_time: clock.now(),
+            clock,
+            paused: None,
+            looping,
+            direction: PlaybackDirection::Forward,
+            loop_count: 0,
+            speed: 1.0,
+        }
+    }
+
+    /// Moves to the next of `frames` once the current one has been shown for
+    /// its duration, returning whether the frame changed.
+    pub(crate) fn advance(&mut self, frames: &[WebpFrame]) -> bool {
+        if self.paused.is_some() || self.elapsed() < frames[self.frame_index].duration {
+            return false;
+        }
+        let next = self.next_frame_index(frames.len());
+        if next == self.frame_index {
+            // A finished animation holds its last frame at the end.
+            return false;
+        }
+        if wraps(self.frame_index, next, self.direction) {
+            self.loop_count += 1;
+        }
+        self.frame_index = next;
+        self.last_frame_time = self.clock.now();
+        true
+    }
+
+    /// Returns the index of the frame that follows the current one in an
+    /// animation of `len` frames.
+    pub(crate) fn next_frame_index(&self, len: usize) -> usize {
+        next_frame_index(self.frame_index, len, self.direction, self.looping)
+    }
+
+    /// Returns the playhead position in `frames`, measured from the start of
+    /// the animation.
+    pub(crate) fn timestamp(&self, frames: &[WebpFrame]) -> Duration {
+        let start: Duration = frames[..self.frame_index]
+            .iter()
+            .map(|frame| frame.duration)
+            .sum();
+        start + self.elapsed().min(frames[self.frame_index].duration)
+    }
+
+    /// Returns the time spent on the current frame, scaled by the playback speed.
+    ///
+    /// Scaling in `f64` keeps durations exact to the nanosecond at normal speed.
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.unscaled_elapsed().mul_f64(self.speed as f64)
+    }
+
+    /// Jumps to the start of the frame at `index`, clamped to the last of
+    /// `len` frames.
+    pub(crate) fn seek_frame(&mut self, index: usize, len: usize) {
+        self.frame_index = index.min(len - 1);
+        self.set_elapsed(Duration::ZERO);
+    }
+
+    /// Jumps to the position `time` in `frames`, measured from the start of
+    /// the animation at normal speed and clamped to its duration.
+    pub(crate) fn seek_time(&mut self, frames: &[WebpFrame], time: Duration) {
+        let (index, start) = frames
+            .iter()
+            .scan(Duration::ZERO, |start, frame| {
+                let frame_start = *start;
+                *start += frame.duration;
+                Some(frame_start)
+            })
+            .enumerate()
+            .take_while(|(_, start)| *start <= time)
+            .last()
+            .unwrap_or((0, Duration::ZERO));
+        let elapsed = (time - start).min(frames[index].duration);
+        self.restore(
+            PlaybackSnapshot {
+                frame_index: index,
+                elapsed,
+                direction: self.direction,
+                loop_count: self.loop_count,
+                is_playing: self.is_playing(),
+            },
+            frames.len(),
+        );
+    }
+
+    /// Sets the playback speed multiplier. The time already spent on the
+    /// current frame is kept, so the new speed only applies from now on.
+    pub(crate) fn set_speed(&mut self, speed: f32) {
+        let elapsed = self.elapsed();
+        self.speed = if speed.is_finite() {
+            speed.max(0.0)
+        } else {
+            0.0
+        };
+        if self.speed > 0.0 {
+            self.set_elapsed(elapsed.div_f64(self.speed as f64));
+        }
+    }
+
+    /// Returns the playback speed multiplier.
+    pub(crate) fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Captures the playhead.
+    pub(crate) fn snapshot(&self) -> PlaybackSnapshot {
+        PlaybackSnapshot {
+            frame_index: self.frame_index,
+            elapsed: self.elapsed(),
+            direction: self.direction,
+            loop_count: self.loop_count,
+            is_playing: self.is_playing(),
+        }
+    }
+
+    /// Moves the playhead to `snapshot`, clamping its frame index to the last
+    /// of `len` frames.
+    pub(crate) fn restore(&mut self, snapshot: PlaybackSnapshot, len: usize) {
+        self.frame_index = snapshot.frame_index.min(len - 1);
+        self.direction = snapshot.direction;
+        self.loop_count = snapshot.loop_count;
+        self.paused = (!snapshot.is_playing).then_some(Duration::ZERO);
+        // The snapshot stores animation time; the clock runs in real time.
+        if self.speed > 0.0 {
+            self.set_elapsed(snapshot.elapsed.div_f64(self.speed as f64));
+        } else {
+            self.set_elapsed(Duration::ZERO);
+        }
+    }
+
+    /// Freezes the playhead until [`play`](Self::play) is called.
+    pub(crate) fn pause(&mut self) {
+        if self.paused.is_none() {
+            self.paused = Some(self.since_last_frame());
+        }
+    }
+
+    /// Resumes playback after [`pause`](Self::pause) from where it stopped.
+    pub(crate) fn play(&mut self) {
+        if let Some(elapsed) = self.paused.take() {
+            self.set_elapsed(elapsed);
+        }
+    }
+
+    /// Returns `true` unless playback is paused.
+    pub(crate) fn is_playing(&self) -> bool {
+        self.paused.is_none()
+    }
+
+    /// Replaces the clock, keeping the time spent on the current frame.
+    pub(crate) fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        let elapsed = self.unscaled_elapsed();
+        self.clock = clock;
+        self.set_elapsed(elapsed);
+    }
+
+    /// Returns the unscaled time spent on the current frame.
+    fn unscaled_elapsed(&self) -> Duration {
+        self.paused.unwrap_or_else(|| self.since_last_frame())
+    }
+
+    /// Returns the unscaled time since the current frame started.
+    fn since_last_frame(&self) -> Duration {
+        self.clock
+            .now()
+            .saturating_duration_since(self.last_frame_time)
+    }
+
+    /// Sets the unscaled time spent on the current frame.
+    fn set_elapsed(&mut self, elapsed: Duration) {
+        match &mut self.paused {
+            Some(paused) => *paused = elapsed,
+            None => {
+                let now = self.clock.now();
+                self.last_frame_time = now.checked_sub(elapsed).unwrap_or(now);
+            }
+        }
+    }
+}
+
+/// Returns the index of the frame that follows `index` in an animation of
+/// `len` frames, honouring the playback direction and the looping setting.
+pub(crate) fn next_frame_index(
+    index: usize,
+    len: usize,
+    direction: PlaybackDirection,
+    looping: bool,
+) -> usize {
+    let last = len - 1;
+    match direction {
+        PlaybackDirection::Forward if index < last => index + 1,
+        PlaybackDirection::Backward if index > 0 => index - 1,
+        PlaybackDirection::Forward if looping => 0,
+        PlaybackDirection::Backward if looping => last,
+        _ => index,
+    }
+}
+
+/// Returns whether moving from `current` to `next` wrapped around the end of
+/// the animation.
+fn wraps(current: usize, next: usize, direction: PlaybackDirection) -> bool {
+    match direction {
+        PlaybackDirection::Forward => next < current,
+        PlaybackDirection::Backward => next > current,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::DynamicImage;
+
+    /// Returns a looping player of three 1×1 frames shown for 100 ms each.
+    fn player() -> HeadlessPlayer {
+        let frames = (0..3)
+            .map(|_| WebpFrame {
+                image: DynamicImage::new_rgba8(1, 1),
+                duration: Duration::from_millis(100),
+            })
+            .collect();
+        let mut player = HeadlessPlayer::new(WebpAnimationData::from_frames(frames).unwrap());
+        player.set_looping(true);
+        player
+    }
+
+    #[test]
+    fn advance_moves_one_frame_per_duration() {
+        let mut player = player();
+        player.advance(Duration::from_millis(99));
+        assert_eq!(player.current_frame_index(), 0);
+        player.advance(Duration::from_millis(1));
+        assert_eq!(player.current_frame_index(), 1);
+        // Time beyond the end of the frame is dropped.
+        player.advance(Duration::from_millis(250));
+        assert_eq!(player.current_frame_index(), 2);
+        assert_eq!(player.timestamp(), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn backward_playback_wraps_to_the_last_frame() {
+        let mut player = player();
+        player.set_direction(PlaybackDirection::Backward);
+        player.advance(Duration::from_millis(100));
+        assert_eq!(player.current_frame_index(), 2);
+        assert_eq!(player.loop_count(), 1);
+    }
+
+    #[test]
+    fn paused_player_keeps_its_position() {
+        let mut player = player();
+        player.advance(Duration::from_millis(40));
+        player.pause();
+        player.advance(Duration::from_millis(500));
+        assert_eq!(player.current_frame_index(), 0);
+        assert_eq!(player.timestamp(), Duration::from_millis(40));
+        player.play();
+        player.advance(Duration::from_millis(60));
+        assert_eq!(player.current_frame_index(), 1);
+    }
+
+    #[test]
+    fn seek_time_lands_inside_the_frame() {
+        let mut player = player();
+        player.seek_time(Duration::from_millis(150));
+        assert_eq!(player.current_frame_index(), 1);
+        assert_eq!(player.timestamp(), Duration::from_millis(150));
+        player.seek_time(Duration::from_secs(10));
+        assert_eq!(player.current_frame_index(), 2);
+        assert_eq!(player.timestamp(), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn restore_resumes_a_snapshot() {
+        let mut player = player();
+        player.seek_time(Duration::from_millis(130));
+        player.pause();
+        let snapshot = player.playback_state();
+
+        let mut other = self::player();
+        other.restore(snapshot);
+        assert_eq!(other.playback_state(), snapshot);
+        assert!(!other.is_playing());
+    }
+
+    #[test]
+    fn clones_keep_their_own_time() {
+        let mut player = player();
+        player.advance(Duration::from_millis(50));
+        let mut clone = player.clone();
+        clone.advance(Duration::from_millis(50));
+        assert_eq!(clone.current_frame_index(), 1);
+        assert_eq!(player.current_frame_index(), 0);
+        assert_eq!(player.timestamp(), Duration::from_millis(50));
+    }
+}
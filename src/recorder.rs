@@ -0,0 +1,144 @@
+use crate::encoder::{BackgroundEncoder, EncoderOptions};
+use crate::error::{Result, WebpAnimationError};
+use nannou::draw::{Renderer, RendererBuilder};
+use nannou::image::DynamicImage;
+use nannou::prelude::*;
+use nannou::wgpu;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Records the output of a nannou sketch into an animated WebP file.
+///
+/// Each call to [`capture`](Self::capture) renders a `Draw` into an offscreen
+/// texture and reads it back asynchronously. The captured frames are
+/// compressed by a [`BackgroundEncoder`], so recording does not stall the
+/// sketch while libwebp encodes.
+pub struct WebpRecorder {
+    /// Offscreen texture the captured `Draw` is rendered into.
+    texture: wgpu::Texture,
+    /// Renderer used to draw into the offscreen texture.
+    renderer: Renderer,
+    /// Reads the offscreen texture back to the CPU.
+    texture_capturer: wgpu::TextureCapturer,
+    /// Compresses captured frames on a worker thread.
+    encoder: BackgroundEncoder,
+    /// Destination of the animated WebP file.
+    path: PathBuf,
+    /// How long each captured frame is displayed.
+    frame_duration: Duration,
+}
+
+impl WebpRecorder {
+    /// Creates a new recorder writing to `path`.
+    ///
+    /// # Parameters
+    ///
+    /// - `app`: Reference to the Nannou `App` instance, used for creating GPU resources.
+    /// - `path`: Destination of the animated WebP file.
+    /// - `size`: Width and height of the recording in pixels, usually the window size.
+    /// - `frame_duration`: How long each captured frame is displayed in the output.
+    pub fn new<P: AsRef<Path>>(
+        app: &App,
+        path: P,
+        size: [u32; 2],
+        frame_duration: Duration,
+    ) -> Self {
+        Self::with_options(app, path, size, frame_duration, EncoderOptions::default())
+    }
+
+    /// Creates a new recorder writing to `path` with custom compression settings.
+    ///
+    /// # Parameters
+    ///
+    /// See [`new`](Self::new); additionally:
+    ///
+    /// - `options`: The encoder settings used for every captured frame.
+    pub fn with_options<P: AsRef<Path>>(
+        app: &App,
+        path: P,
+        size: [u32; 2],
+        frame_duration: Duration,
+        options: EncoderOptions,
+    ) -> Self {
+        let window = app.main_window();
+        let device = window.device();
+
+        let texture = wgpu::TextureBuilder::new()
+            .size(size)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .sample_count(1)
+            .format(wgpu::TextureFormat::Rgba16Float)
+            .build(device);
+        let renderer =
+            RendererBuilder::new().build_from_texture_descriptor(device, texture.descriptor());
+
+        Self {
+            texture,
+            renderer,
+            texture_capturer: wgpu::TextureCapturer::default(),
+            encoder: BackgroundEncoder::new(size[0], size[1], options),
+            path: path.as_ref().to_path_buf(),
+            frame_duration,
+        }
+    }
+
+    /// Renders `draw` into the recording and queues it for encoding.
+    ///
+    /// Call this once per `view` (or `update`) with the same `Draw` that is
+    /// rendered to the window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the captured frame could not be read back.
+    pub fn capture(&mut self, app: &App, draw: &Draw) -> Result<()> {
+        let (index, sender) = self.encoder.reserve()?;
+        let frame_duration = self.frame_duration;
+
+        let window = app.main_window();
+        let device = window.device();
+        let ce_desc = wgpu::CommandEncoderDescriptor {
+            label: Some("webp recorder"),
+        };
+        let mut encoder = device.create_command_encoder(&ce_desc);
+        self.renderer
+            .render_to_texture(device, &mut encoder, draw, &self.texture);
+        let snapshot = self
+            .texture_capturer
+            .capture(device, &mut encoder, &self.texture);
+        window.queue().submit(Some(encoder.finish()));
+
+        snapshot
+            .read(move |result| {
+                if let Ok(image) = result {
+                    let image = DynamicImage::ImageRgba8(image.to_owned());
+                    let _ = sender.send((index, image, frame_duration));
+                }
+            })
+            .map_err(|_| {
+                WebpAnimationError::Capture("timed out waiting for the frame capture".into())
+            })
+    }
+
+    /// Returns the number of frames captured so far.
+    pub fn frame_count(&self) -> usize {
+        self.encoder.frame_count()
+    }
+
+    /// Waits for outstanding captures, finishes encoding and writes the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encoding or writing the output file fails.
+    pub fn finish(self, app: &App) -> Result<()> {
+        let window = app.main_window();
+        self.texture_capturer
+            .await_active_snapshots(window.device())
+            .map_err(|_| {
+                WebpAnimationError::Capture("timed out waiting for frame captures".into())
+            })?;
+
+        let data = self.encoder.finish()?;
+        Ok(fs::write(&self.path, data)?)
+    }
+}
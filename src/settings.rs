@@ -0,0 +1,177 @@
+#[cfg(feature = "nannou")]
+use crate::budget::MemoryBudget;
+#[cfg(feature = "texture-compression")]
+use crate::compression::TextureCompression;
+use crate::loader::LoadRecovery;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// How frame textures are sampled when drawn at a size other than their own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextureFilter {
+    /// Blend neighbouring texels for smooth scaling.
+    #[default]
+    Linear,
+    /// Pick the nearest texel, keeping pixel art crisp.
+    Nearest,
+}
+
+/// How far ahead of the playhead frames are prepared in the lazy modes.
+///
+/// A longer distance absorbs longer decoding or upload hiccups at the cost of
+/// memory for the frames held in advance. Measuring in [`Time`](Self::Time)
+/// keeps the same safety margin for animations with short and long frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prefetch {
+    /// Prepare this many upcoming frames.
+    Frames(usize),
+    /// Prepare every upcoming frame that starts within this time after the
+    /// current frame, at normal speed.
+    Time(Duration),
+}
+
+impl Prefetch {
+    /// Returns `true` if an upcoming frame belongs to the prefetched ones.
+    ///
+    /// # Parameters
+    ///
+    /// - `ahead`: Position of the frame after the current one, starting at `1`.
+    /// - `offset`: Time from the end of the current frame to the start of the
+    ///   upcoming one.
+    #[cfg(feature = "nannou")]
+    pub(crate) fn includes(self, ahead: usize, offset: Duration) -> bool {
+        match self {
+            Prefetch::Frames(frames) => ahead <= frames,
+            Prefetch::Time(time) => offset < time,
+        }
+    }
+}
+
+impl Default for Prefetch {
+    /// Prepares nothing ahead of time.
+    fn default() -> Self {
+        Prefetch::Frames(0)
+    }
+}
+
+impl From<usize> for Prefetch {
+    fn from(frames: usize) -> Self {
+        Prefetch::Frames(frames)
+    }
+}
+
+impl From<Duration> for Prefetch {
+    fn from(time: Duration) -> Self {
+        Prefetch::Time(time)
+    }
+}
+
+/// Crate-wide defaults applied to every animation created afterwards.
+///
+/// Sketches with many assets can configure loading once instead of repeating
+/// the same builder calls for every file. Options set explicitly on a
+/// [`WebpAnimationBuilder`](crate::WebpAnimationBuilder) still take precedence.
+///
+/// ```no_run
+/// # use nannou_webp_animation::{AnimationSettings, TextureFilter};
+/// AnimationSettings {
+///     looping: false,
+///     lazy_textures: true,
+///     filter: TextureFilter::Nearest,
+///     ..AnimationSettings::global()
+/// }
+/// .set_global();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationSettings {
+    /// Whether playback restarts after the final frame. Defaults to `true`.
+    pub looping: bool,
+    /// Whether frame textures are uploaded on first display instead of up
+    /// front. Defaults to `false`.
+    pub lazy_textures: bool,
+    /// How frame textures are sampled when drawn. Defaults to [`TextureFilter::Linear`].
+    pub filter: TextureFilter,
+    /// Whether libwebp may decode on a worker thread. Defaults to `true`.
+    pub decode_threads: bool,
+    /// Maximum number of files decoded at the same time across all threads,
+    /// or `0` for no limit. Defaults to `0`.
+    ///
+    /// Further decodes wait for a running one to finish, so loading many
+    /// assets at once, e.g. with [`AnimationManager::load_many`](crate::AnimationManager::load_many)
+    /// or one [`load_async`](crate::WebpAnimationData::load_async) per file,
+    /// leaves CPU time for the render thread. Frames decoded one at a time, by
+    /// a [`WebpDemuxer`](crate::WebpDemuxer), a [`WebpStream`](crate::WebpStream)
+    /// or a streaming player, take a slot for each frame.
+    pub max_concurrent_decodes: usize,
+    /// How failed background loads and reloads are retried or replaced.
+    /// Defaults to no retries and no fallback.
+    pub recovery: LoadRecovery,
+    /// Limits on the memory of all players together. Defaults to no limits.
+    #[cfg(feature = "nannou")]
+    pub memory_budget: MemoryBudget,
+    /// Whether decoded files are kept in the [`AnimationCache`](crate::AnimationCache)
+    /// and shared by later loads of the same path. Defaults to `false`.
+    pub cache_decoded: bool,
+    /// Bytes of decoded frames above which a new player moves them to a
+    /// temporary file and keeps only the frames near the playhead in memory,
+    /// or `None` to keep every frame. Defaults to `None`.
+    ///
+    /// See [`WebpAnimationPlayer::spill_to_disk`](crate::WebpAnimationPlayer::spill_to_disk).
+    #[cfg(feature = "spill-to-disk")]
+    pub spill_threshold: Option<u64>,
+    /// Block compression format frame textures are transcoded to. Defaults to
+    /// [`TextureCompression::None`].
+    #[cfg(feature = "texture-compression")]
+    pub texture_compression: TextureCompression,
+}
+
+/// The built-in defaults, also used until [`AnimationSettings::set_global`] is called.
+const DEFAULT_SETTINGS: AnimationSettings = AnimationSettings {
+    looping: true,
+    lazy_textures: false,
+    filter: TextureFilter::Linear,
+    decode_threads: true,
+    max_concurrent_decodes: 0,
+    recovery: LoadRecovery::NONE,
+    #[cfg(feature = "nannou")]
+    memory_budget: MemoryBudget::UNLIMITED,
+    cache_decoded: false,
+    #[cfg(feature = "spill-to-disk")]
+    spill_threshold: None,
+    #[cfg(feature = "texture-compression")]
+    texture_compression: TextureCompression::None,
+};
+
+/// The settings returned by [`AnimationSettings::global`].
+static GLOBAL_SETTINGS: RwLock<AnimationSettings> = RwLock::new(DEFAULT_SETTINGS);
+
+impl AnimationSettings {
+    /// Returns the current crate-wide defaults.
+    pub fn global() -> Self {
+        GLOBAL_SETTINGS
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Makes these settings the defaults for every animation created afterwards.
+    ///
+    /// Animations that already exist are not affected.
+    pub fn set_global(self) {
+        *GLOBAL_SETTINGS
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = self;
+    }
+
+    /// Restores the built-in defaults.
+    pub fn reset_global() {
+        DEFAULT_SETTINGS.set_global();
+    }
+}
+
+impl Default for AnimationSettings {
+    /// Returns the built-in defaults, ignoring [`set_global`](Self::set_global).
+    fn default() -> Self {
+        DEFAULT_SETTINGS
+    }
+}
@@ -0,0 +1,248 @@
+use crate::error::{Result, WebpAnimationError};
+use crate::frame::WebpFrame;
+use image::{DynamicImage, GenericImageView, RgbaImage};
+use memmap2::Mmap;
+use std::borrow::Borrow;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Distinguishes the spill files created by this process.
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// Decoded frames written to a temporary file as raw RGBA8 pixels and mapped
+/// into memory, so the operating system pages them in and out on demand
+/// instead of keeping every frame in RAM.
+pub(crate) struct SpillFile {
+    /// The mapped file; `None` only until writing has finished.
+    map: Option<Mmap>,
+    /// Where the file lives. It is removed on drop.
+    path: PathBuf,
+    /// Byte offset, width and height of every frame in the file.
+    layout: Vec<(usize, u32, u32)>,
+}
+
+impl SpillFile {
+    /// Writes `frames` to a new temporary file one at a time and maps it, so
+    /// a decoder can spill an animation without holding more than one frame.
+    ///
+    /// # Returns
+    ///
+    /// The file and the duration of every frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of `frames`, [`WebpAnimationError::NoFrames`]
+    /// if there are none, or an I/O error if the file cannot be written or
+    /// mapped. The file is removed again on error.
+    pub(crate) fn write<I, F>(frames: I) -> Result<(Self, Vec<Duration>)>
+    where
+        I: IntoIterator<Item = Result<F>>,
+        F: Borrow<WebpFrame>,
+    {
+        let (file, path) = create_file()?;
+        let mut spill = Self {
+            map: None,
+            path,
+            layout: Vec::new(),
+        };
+        let mut writer = BufWriter::new(file);
+        let mut durations = Vec::new();
+        let mut offset = 0;
+        for frame in frames {
+            let frame = frame?;
+            let frame = frame.borrow();
+            let (width, height) = frame.image.dimensions();
+            match frame.image.as_rgba8() {
+                Some(rgba) => writer.write_all(rgba.as_raw())?,
+                None => writer.write_all(frame.image.to_rgba8().as_raw())?,
+            }
+            spill.layout.push((offset, width, height));
+            offset += (width * height * 4) as usize;
+            durations.push(frame.duration);
+        }
+        if durations.is_empty() {
+            return Err(WebpAnimationError::NoFrames);
+        }
+
+        let file = writer.into_inner().map_err(|error| error.into_error())?;
+        // SAFETY: the file was created by this process under a unique name
+        // and is never written again while it is mapped.
+        spill.map = Some(unsafe { Mmap::map(&file)? });
+        Ok((spill, durations))
+    }
+
+    /// Reads the frame at `index` back as an RGBA8 image.
+    fn read(&self, index: usize) -> Option<DynamicImage> {
+        let (offset, width, height) = *self.layout.get(index)?;
+        let bytes = self
+            .map
+            .as_ref()?
+            .get(offset..offset + (width * height * 4) as usize)?;
+        RgbaImage::from_raw(width, height, bytes.to_vec()).map(DynamicImage::ImageRgba8)
+    }
+}
+
+/// Creates a new file in the temporary directory under a name no other file
+/// has, never opening one that exists already: another user could have put a
+/// symbolic link there to make us overwrite its target.
+fn create_file() -> io::Result<(File, PathBuf)> {
+    loop {
+        let name = format!(
+            "nannou_webp_animation-{}-{}.rgba",
+            process::id(),
+            NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = env::temp_dir().join(name);
+        match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((file, path)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        // Unmap first: Windows refuses to delete a mapped file.
+        self.map = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A player's view of its spilled frames: the file, shared with clones, and
+/// which frames this player currently holds in memory.
+///
+/// The first frame always stays in memory, as the canvas size is read from
+/// it; every other frame is an empty 0×0 image while it is paged out.
+#[derive(Clone)]
+pub(crate) struct SpilledFrames {
+    /// The frames on disk.
+    file: Arc<SpillFile>,
+    /// Indices of the frames paged in, besides the first.
+    resident: Vec<usize>,
+}
+
+impl SpilledFrames {
+    /// Wraps `file` with only the first frame in memory.
+    pub(crate) fn new(file: SpillFile) -> Self {
+        Self {
+            file: Arc::new(file),
+            resident: Vec::new(),
+        }
+    }
+
+    /// Returns frame `index` read from the file if it is paged out, or `None`
+    /// if the frame in memory can be used as is.
+    pub(crate) fn paged_out(&self, index: usize, duration: Duration) -> Option<WebpFrame> {
+        if index == 0 || self.resident.contains(&index) {
+            return None;
+        }
+        Some(WebpFrame {
+            image: self.file.read(index)?,
+            duration,
+        })
+    }
+
+    /// Returns frames with the given `durations` for an animation decoded
+    /// straight into the file, with every frame but the first paged out.
+    #[cfg(libwebp)]
+    pub(crate) fn placeholders(&self, durations: &[Duration]) -> Vec<WebpFrame> {
+        durations
+            .iter()
+            .enumerate()
+            .map(|(index, &duration)| WebpFrame {
+                image: match index {
+                    0 => self
+                        .file
+                        .read(0)
+                        .unwrap_or_else(|| DynamicImage::new_rgba8(0, 0)),
+                    _ => DynamicImage::new_rgba8(0, 0),
+                },
+                duration,
+            })
+            .collect()
+    }
+
+    /// Replaces `frames` with placeholders for every frame but the first,
+    /// keeping the durations.
+    pub(crate) fn page_out_all(&mut self, frames: &mut Arc<Vec<WebpFrame>>) {
+        *frames = Arc::new(
+            frames
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| match index {
+                    0 => frame.clone(),
+                    _ => WebpFrame {
+                        image: DynamicImage::new_rgba8(0, 0),
+                        duration: frame.duration,
+                    },
+                })
+                .collect(),
+        );
+        self.resident.clear();
+    }
+
+    /// Pages every frame into `frames`, e.g. before they are edited.
+    pub(crate) fn page_in_all(&mut self, frames: &mut Arc<Vec<WebpFrame>>) {
+        let all: Vec<usize> = (0..frames.len()).collect();
+        self.page(frames, &all);
+    }
+
+    /// Pages the frames in `wanted` into `frames` and the others out.
+    ///
+    /// Frames added after the file was written, e.g. from a stream, are not
+    /// in the file and stay in memory.
+    ///
+    /// # Returns
+    ///
+    /// The indices of the frames that were paged out.
+    pub(crate) fn page(
+        &mut self,
+        frames: &mut Arc<Vec<WebpFrame>>,
+        wanted: &[usize],
+    ) -> Vec<usize> {
+        let evicted: Vec<usize> = self
+            .resident
+            .iter()
+            .copied()
+            .filter(|index| !wanted.contains(index))
+            .collect();
+        let mut loaded = Vec::new();
+        for &index in wanted {
+            if index != 0
+                && index < self.file.layout.len()
+                && !self.resident.contains(&index)
+                && !loaded.contains(&index)
+            {
+                loaded.push(index);
+            }
+        }
+        if evicted.is_empty() && loaded.is_empty() {
+            return evicted;
+        }
+
+        let frames = Arc::make_mut(frames);
+        for &index in &evicted {
+            frames[index].image = DynamicImage::new_rgba8(0, 0);
+        }
+        for &index in &loaded {
+            if let Some(image) = self.file.read(index) {
+                frames[index].image = image;
+            }
+        }
+        self.resident.retain(|index| wanted.contains(index));
+        self.resident.extend(loaded);
+        evicted
+    }
+}
@@ -0,0 +1,261 @@
+use crate::decoder::ColorMode;
+use crate::demux::{clear_rect, draw_bitstream, FrameHeader};
+use crate::error::{Result, WebpAnimationError};
+use crate::formats;
+use crate::frame::WebpFrame;
+use crate::loader;
+
+use image::{DynamicImage, RgbaImage};
+use std::io::{self, BufReader, Read};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+/// Size of the buffer between the reader and the chunk parser.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Largest canvas width or height libwebp accepts.
+const MAX_DIMENSION: u32 = 16384;
+
+//---------------------------------------------------------------------
+// Public stream
+//---------------------------------------------------------------------
+
+/// Frames of an animated WebP decoded from a [`Read`] stream as they arrive.
+///
+/// A background thread reads the stream through a buffer, one RIFF chunk at
+/// a time, and decodes every frame as soon as its `ANMF` chunk is complete.
+/// Only the chunk being read and the composited canvas are held in memory,
+/// so playback of a file sent over the network, e.g. through a
+/// [`TcpStream`](std::net::TcpStream), can start long before the last byte
+/// has arrived.
+///
+/// [`WebpAnimationPlayer::from_reader`](crate::WebpAnimationPlayer::from_reader)
+/// plays a stream directly; use this type to feed the frames somewhere else.
+pub struct WebpStream {
+    /// Receives decoded frames, or `None` once the stream has ended.
+    receiver: Option<Receiver<Result<WebpFrame>>>,
+    /// Why the stream ended early, until taken.
+    error: Option<WebpAnimationError>,
+}
+
+impl WebpStream {
+    /// Starts reading and decoding `reader` on a background thread.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: The source of the WebP file, e.g. a network connection.
+    pub fn from_reader<R: Read + Send + 'static>(reader: R) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let reader = BufReader::with_capacity(BUFFER_SIZE, reader);
+            let result = read_frames(reader, |frame| sender.send(Ok(frame)).is_ok());
+            if let Err(error) = result {
+                let _ = sender.send(Err(error));
+            }
+        });
+        Self {
+            receiver: Some(receiver),
+            error: None,
+        }
+    }
+
+    /// Returns the frames that arrived since the last call, without blocking.
+    pub fn try_frames(&mut self) -> Vec<WebpFrame> {
+        let mut frames = Vec::new();
+        while let Some(receiver) = &self.receiver {
+            match receiver.try_recv() {
+                Ok(message) => self.receive(message, &mut frames),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.receiver = None,
+            }
+        }
+        frames
+    }
+
+    /// Blocks until `count` frames have arrived or the stream has ended, and
+    /// returns the frames received so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the stream if it failed before delivering a
+    /// single frame. Later errors are reported by [`take_error`](Self::take_error).
+    pub fn wait_frames(&mut self, count: usize) -> Result<Vec<WebpFrame>> {
+        let mut frames = Vec::new();
+        while frames.len() < count {
+            let Some(receiver) = &self.receiver else {
+                break;
+            };
+            match receiver.recv() {
+                Ok(message) => self.receive(message, &mut frames),
+                Err(_) => self.receiver = None,
+            }
+        }
+        match self.error.take() {
+            Some(error) if frames.is_empty() => Err(error),
+            error => {
+                self.error = error;
+                Ok(frames)
+            }
+        }
+    }
+
+    /// Returns `true` once the whole stream has been read, or it failed.
+    pub fn is_finished(&self) -> bool {
+        self.receiver.is_none()
+    }
+
+    /// Returns why the stream ended before the end of the file, once.
+    pub fn take_error(&mut self) -> Option<WebpAnimationError> {
+        self.error.take()
+    }
+
+    /// Adds a frame to `frames`, or records the error that ended the stream.
+    fn receive(&mut self, message: Result<WebpFrame>, frames: &mut Vec<WebpFrame>) {
+        match message {
+            Ok(frame) => frames.push(frame),
+            Err(error) => {
+                self.error = Some(error);
+                self.receiver = None;
+            }
+        }
+    }
+}
+
+//---------------------------------------------------------------------
+// Chunk parser
+//---------------------------------------------------------------------
+
+/// Reads the WebP file from `reader` chunk by chunk and passes each decoded
+/// frame to `emit`, stopping early once `emit` returns `false`.
+fn read_frames<R, F>(mut reader: R, mut emit: F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(WebpFrame) -> bool,
+{
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WEBP" {
+        return Err(WebpAnimationError::Decode("not a WebP stream".into()));
+    }
+    let riff_end = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64 + 8;
+
+    let mut position = header.len() as u64;
+    let mut canvas: Option<(u32, u32, Vec<u8>)> = None;
+    let mut previous: Option<FrameHeader> = None;
+    let mut alpha: Vec<u8> = Vec::new();
+    let mut emitted = false;
+    while position + 8 <= riff_end {
+        let mut chunk = [0u8; 8];
+        match reader.read_exact(&mut chunk) {
+            // A sender closing the connection right after the last chunk is fine.
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            result => result?,
+        }
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+        // The size comes from the network, so it is checked before allocating.
+        if size > riff_end - position - chunk.len() as u64 {
+            return Err(WebpAnimationError::Decode(
+                "chunk extends past the end of the file".into(),
+            ));
+        }
+        // Chunks are padded to an even size. The payload only grows with the
+        // bytes actually received, so a lying header cannot force a huge buffer.
+        let padded = size + (size & 1);
+        let mut payload = Vec::new();
+        reader.by_ref().take(padded).read_to_end(&mut payload)?;
+        if payload.len() as u64 != padded {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let size = size as usize;
+        position += (chunk.len() + payload.len()) as u64;
+
+        match &chunk[0..4] {
+            b"VP8X" if size >= 10 => {
+                let width = read_u24(&payload[4..]) + 1;
+                let height = read_u24(&payload[7..]) + 1;
+                // Like libwebp, refuse canvases larger than the format allows.
+                if width > MAX_DIMENSION || height > MAX_DIMENSION {
+                    return Err(WebpAnimationError::Decode("canvas too large".into()));
+                }
+                canvas = Some((width, height, vec![0u8; (width * height * 4) as usize]));
+            }
+            b"ANMF" if size >= 16 => {
+                let Some((width, height, canvas)) = canvas.as_mut() else {
+                    return Err(WebpAnimationError::Decode(
+                        "ANMF chunk before the VP8X header".into(),
+                    ));
+                };
+                let frame = read_frame_header(&payload);
+                if frame.x + frame.width > *width || frame.y + frame.height > *height {
+                    return Err(WebpAnimationError::Decode(
+                        "frame extends past the canvas".into(),
+                    ));
+                }
+                if let Some(previous) = previous.filter(|p| p.dispose_to_background) {
+                    clear_rect(canvas, *width, *height, &previous);
+                }
+                {
+                    let _slot = loader::decode_slot();
+                    draw_bitstream(canvas, *width, *height, &frame, &payload[16..size])?;
+                }
+                previous = Some(frame);
+
+                let image =
+                    RgbaImage::from_raw(*width, *height, canvas.clone()).ok_or_else(|| {
+                        WebpAnimationError::Decode("Failed to create image from RGBA buffer".into())
+                    })?;
+                emitted = true;
+                if !emit(WebpFrame {
+                    image: DynamicImage::ImageRgba8(image),
+                    duration: frame.duration,
+                }) {
+                    return Ok(());
+                }
+            }
+            // The alpha channel of a still image precedes its VP8 chunk.
+            b"ALPH" => {
+                alpha = [&chunk[..], &payload].concat();
+            }
+            b"VP8 " | b"VP8L" => {
+                // A still image: rebuild a minimal file and decode it whole.
+                let body = [&b"WEBP"[..], &alpha, &chunk, &payload].concat();
+                let file = [&b"RIFF"[..], &(body.len() as u32).to_le_bytes(), &body].concat();
+                for frame in formats::decode_bytes(&file, ColorMode::Rgba)? {
+                    if !emit(frame) {
+                        return Ok(());
+                    }
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+
+    if emitted {
+        Ok(())
+    } else {
+        Err(WebpAnimationError::NoFrames)
+    }
+}
+
+/// Parse the 16-byte header at the start of an `ANMF` chunk.
+fn read_frame_header(payload: &[u8]) -> FrameHeader {
+    let flags = payload[15];
+    FrameHeader {
+        x: read_u24(&payload[0..]) * 2,
+        y: read_u24(&payload[3..]) * 2,
+        width: read_u24(&payload[6..]) + 1,
+        height: read_u24(&payload[9..]) + 1,
+        duration: Duration::from_millis(read_u24(&payload[12..]) as u64),
+        dispose_to_background: flags & 0b01 != 0,
+        blend: flags & 0b10 == 0,
+        // Frames arrive in order, so every frame builds on the canvas.
+        key_frame: false,
+    }
+}
+
+/// Read a 24-bit little-endian integer.
+fn read_u24(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
@@ -0,0 +1,568 @@
+use crate::clock::{Clock, Instant, SystemClock};
+use crate::data::MemoryReport;
+use crate::demux::WebpDemuxer;
+use crate::error::Result;
+use crate::frame::WebpFrame;
+use crate::settings::{AnimationSettings, Prefetch};
+use crate::texture::AnimatedTexture;
+use nannou::image::{DynamicImage, RgbaImage};
+use nannou::prelude::*;
+use nannou::wgpu::{self, DeviceQueuePair, Texture};
+use std::fmt;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Number of frames kept decoded by [`StreamingPlayer::from_file`].
+const DEFAULT_WINDOW: usize = 8;
+
+/// Where the decoding thread continues from.
+#[derive(Clone, Copy, Debug)]
+struct Plan {
+    /// Incremented whenever the plan changes, so frames decoded for an older
+    /// plan can be told apart and dropped.
+    generation: u64,
+    /// Index of the first frame to decode, or `None` to wait for a new plan.
+    start: Option<usize>,
+    /// Whether decoding wraps around to the first frame after the last.
+    looping: bool,
+    /// Set when the player is dropped; the thread exits.
+    closed: bool,
+}
+
+/// State shared between a [`StreamingPlayer`] and its decoding thread.
+#[derive(Debug)]
+struct Shared {
+    /// The current plan.
+    plan: Mutex<Plan>,
+    /// Signalled when the plan changes.
+    changed: Condvar,
+}
+
+/// A decoded frame with its index and the generation of the plan it was
+/// decoded for.
+type Decoded = (u64, usize, WebpFrame);
+
+/// Plays very long animations while keeping only a few frames decoded.
+///
+/// [`WebpAnimationPlayer`](crate::WebpAnimationPlayer) decodes every frame up
+/// front, so a multi-minute animation can take gigabytes of memory. The
+/// streaming player instead decodes a window of frames ahead of the playhead
+/// on a background thread and drops each frame once it has been shown. CPU
+/// memory stays bounded by about `window` canvases and GPU memory by a single
+/// texture, however long the animation is.
+///
+/// Playback only runs forward. Seeking, or switching looping on at the last
+/// frame, restarts decoding from the nearest key frame. If the decoding thread
+/// falls behind, the current frame stays on screen until the next one is ready.
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use nannou_webp_animation::StreamingPlayer;
+/// # fn model(app: &App) -> nannou_webp_animation::Result<()> {
+/// let mut animation = StreamingPlayer::from_file_with_window("assets/feature.webp", 16, app)?;
+/// animation.update();
+/// app.draw().texture(animation.texture());
+/// # Ok(())
+/// # }
+/// ```
+pub struct StreamingPlayer {
+    /// Read by the decoding thread and by seeks on the calling thread.
+    demuxer: Arc<WebpDemuxer>,
+    /// Shared with the decoding thread.
+    shared: Arc<Shared>,
+    /// Frames decoded ahead of the playhead, at most `window` at a time.
+    decoded: Receiver<Decoded>,
+    /// Generation of the current plan; frames of older plans are dropped.
+    generation: u64,
+    /// Number of frames kept decoded ahead of the playhead.
+    window: usize,
+    /// Display duration of every frame.
+    durations: Vec<Duration>,
+    /// The frame being shown.
+    current_frame: WebpFrame,
+    /// Index of the frame being shown.
+    current_frame_index: usize,
+    /// Texture holding the current frame, rewritten on every frame change.
+    texture: Texture,
+    /// Device and queue used to upload frames.
+    device_queue_pair: Arc<DeviceQueuePair>,
+    /// Time when the current frame started.
+    last_frame_time: Instant,
+    /// Source of `last_frame_time` and the current time.
+    clock: Arc<dyn Clock>,
+    /// Unscaled time spent on the current frame when playback was paused, or
+    /// `None` while playing.
+    paused: Option<Duration>,
+    /// Indicates whether the animation should loop when it reaches the end.
+    is_looping: bool,
+    /// Playback speed multiplier applied to the elapsed time.
+    speed: f32,
+}
+
+impl StreamingPlayer {
+    /// Opens the WebP file at `path` for streaming playback with a window of
+    /// eight decoded frames.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or its first frame cannot
+    /// be decoded.
+    pub fn from_file<P: AsRef<Path>>(path: P, app: &App) -> Result<Self> {
+        Self::from_file_with_window(path, DEFAULT_WINDOW, app)
+    }
+
+    /// Opens the WebP file at `path` for streaming playback, keeping at most
+    /// `window` frames decoded ahead of the playhead.
+    ///
+    /// Each decoded frame takes `width * height * 4` bytes; a larger window
+    /// absorbs longer decoding hiccups.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `window`: Number of frames decoded ahead, at least one.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or its first frame cannot
+    /// be decoded.
+    pub fn from_file_with_window<P: AsRef<Path>>(
+        path: P,
+        window: usize,
+        app: &App,
+    ) -> Result<Self> {
+        Self::from_file_with_prefetch(path, Prefetch::Frames(window), app)
+    }
+
+    /// Opens the WebP file at `path` for streaming playback, keeping the
+    /// frames within `distance` of the playhead decoded ahead.
+    ///
+    /// A [`Prefetch::Time`] distance is turned into the number of frames it
+    /// spans where the frames are shortest, so the window covers that much
+    /// playback everywhere in the file.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: Path to the WebP file containing the animation.
+    /// - `distance`: How far ahead frames are decoded; at least one frame.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or its first frame cannot
+    /// be decoded.
+    pub fn from_file_with_prefetch<P: AsRef<Path>>(
+        path: P,
+        distance: impl Into<Prefetch>,
+        app: &App,
+    ) -> Result<Self> {
+        let demuxer = Arc::new(WebpDemuxer::from_file(path)?);
+        let durations: Vec<Duration> = (0..demuxer.frame_count())
+            .filter_map(|index| demuxer.frame_duration(index))
+            .collect();
+        let window = window_size(distance.into(), &durations);
+        let current_frame = demuxer.decode_frame(0)?;
+
+        let device_queue_pair = app.main_window().device_queue_pair().clone();
+        let texture = Texture::from_image(
+            (device_queue_pair.device(), device_queue_pair.queue()),
+            &current_frame.image,
+        );
+
+        let shared = Arc::new(Shared {
+            plan: Mutex::new(Plan {
+                generation: 0,
+                start: None,
+                looping: false,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+        });
+        let (sender, decoded) = mpsc::sync_channel(window);
+        let (thread_demuxer, thread_shared) = (demuxer.clone(), shared.clone());
+        thread::spawn(move || decode_ahead(&thread_demuxer, &thread_shared, &sender));
+
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let mut animation = Self {
+            demuxer,
+            shared,
+            decoded,
+            generation: 0,
+            window,
+            durations,
+            current_frame,
+            current_frame_index: 0,
+            texture,
+            device_queue_pair,
+            last_frame_time: clock.now(),
+            clock,
+            paused: None,
+            is_looping: AnimationSettings::global().looping,
+            speed: 1.0,
+        };
+        animation.replan(animation.following(0));
+        Ok(animation)
+    }
+
+    /// Advances to the next frame once the current one has been shown long
+    /// enough and its successor has been decoded. Call once per update.
+    pub fn update(&mut self) {
+        if self.paused.is_some() || self.following(self.current_frame_index).is_none() {
+            return;
+        }
+        if self.elapsed() < self.durations[self.current_frame_index] {
+            return;
+        }
+        while let Ok((generation, index, frame)) = self.decoded.try_recv() {
+            if generation == self.generation {
+                self.show(index, frame);
+                return;
+            }
+        }
+    }
+
+    /// Returns the texture of the current frame.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Returns the frame currently being shown.
+    pub fn current_frame(&self) -> &WebpFrame {
+        &self.current_frame
+    }
+
+    /// Returns the index of the frame currently being shown.
+    pub fn current_frame_index(&self) -> usize {
+        self.current_frame_index
+    }
+
+    /// Returns the number of frames in the animation.
+    pub fn frame_count(&self) -> usize {
+        self.durations.len()
+    }
+
+    /// Returns the total duration of one playthrough at normal speed.
+    pub fn duration(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+
+    /// Returns the width (in pixels) of the animation canvas.
+    pub fn width(&self) -> u32 {
+        self.demuxer.width()
+    }
+
+    /// Returns the height (in pixels) of the animation canvas.
+    pub fn height(&self) -> u32 {
+        self.demuxer.height()
+    }
+
+    /// Returns the number of frames kept decoded ahead of the playhead.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the memory this player holds at most: the current frame, a
+    /// full window of decoded frames and the canvas of the decoding thread on
+    /// the CPU, and the single texture on the GPU.
+    pub fn memory_usage(&self) -> MemoryReport {
+        let canvas = self.width() as u64 * self.height() as u64 * 4;
+        MemoryReport {
+            cpu_bytes: (self.window as u64 + 2) * canvas,
+            gpu_bytes: canvas,
+            frame_count: self.frame_count(),
+        }
+    }
+
+    /// Jumps to the start of the frame at `index`, clamped to the last frame.
+    ///
+    /// The frame is decoded on the calling thread, compositing forward from
+    /// the nearest key frame, and decoding ahead restarts after it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame cannot be decoded; the playhead then
+    /// stays where it was.
+    pub fn seek_frame(&mut self, index: usize) -> Result<()> {
+        let index = index.min(self.durations.len() - 1);
+        let frame = self.demuxer.decode_frame(index)?;
+        self.replan(self.following(index));
+        self.show(index, frame);
+        Ok(())
+    }
+
+    /// Sets whether the animation should loop after reaching the final frame.
+    pub fn set_looping(&mut self, looping: bool) {
+        if looping != self.is_looping {
+            self.is_looping = looping;
+            self.replan(self.following(self.current_frame_index));
+        }
+    }
+
+    /// Returns whether the animation loops after reaching the final frame.
+    pub fn is_looping(&self) -> bool {
+        self.is_looping
+    }
+
+    /// Sets the playback speed multiplier.
+    ///
+    /// Negative or non-finite values are treated as `0.0`, which pauses playback.
+    pub fn set_speed(&mut self, speed: f32) {
+        let elapsed = self.elapsed();
+        self.speed = if speed.is_finite() {
+            speed.max(0.0)
+        } else {
+            0.0
+        };
+        if self.speed > 0.0 {
+            self.set_elapsed(elapsed.div_f32(self.speed));
+        }
+    }
+
+    /// Returns the playback speed multiplier.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the clock frame durations are measured against; see [`Clock`].
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        let elapsed = self.since_last_frame();
+        self.clock = Arc::new(clock);
+        self.set_elapsed(elapsed);
+    }
+
+    /// Freezes the playhead until [`play`](Self::play) is called.
+    pub fn pause(&mut self) {
+        if self.paused.is_none() {
+            self.paused = Some(self.since_last_frame());
+        }
+    }
+
+    /// Resumes playback after [`pause`](Self::pause) from where it stopped.
+    pub fn play(&mut self) {
+        if let Some(elapsed) = self.paused.take() {
+            let now = self.clock.now();
+            self.last_frame_time = now.checked_sub(elapsed).unwrap_or(now);
+        }
+    }
+
+    /// Returns `true` unless playback is paused.
+    pub fn is_playing(&self) -> bool {
+        self.paused.is_none()
+    }
+
+    /// Returns the index of the frame after `index`, or `None` at the end of a
+    /// non-looping animation.
+    fn following(&self, index: usize) -> Option<usize> {
+        next_index(index, self.durations.len(), self.is_looping)
+    }
+
+    /// Restarts decoding ahead at `start` and drops the frames decoded so far.
+    fn replan(&mut self, start: Option<usize>) {
+        let mut plan = self.shared.plan.lock().unwrap();
+        plan.generation += 1;
+        plan.start = start;
+        plan.looping = self.is_looping;
+        self.generation = plan.generation;
+        drop(plan);
+        self.shared.changed.notify_all();
+        // Make room in the window; frames still in flight are dropped by
+        // `update` because of their old generation.
+        self.decoded.try_iter().for_each(drop);
+    }
+
+    /// Shows `frame` and restarts its display time.
+    fn show(&mut self, index: usize, frame: WebpFrame) {
+        write_frame(&self.device_queue_pair, &self.texture, &frame);
+        self.current_frame = frame;
+        self.current_frame_index = index;
+        self.set_elapsed(Duration::ZERO);
+    }
+
+    /// Returns the time spent on the current frame, scaled by the playback speed.
+    fn elapsed(&self) -> Duration {
+        let elapsed = self.paused.unwrap_or_else(|| self.since_last_frame());
+        elapsed.mul_f32(self.speed)
+    }
+
+    /// Returns the unscaled time since the current frame started.
+    fn since_last_frame(&self) -> Duration {
+        self.clock
+            .now()
+            .saturating_duration_since(self.last_frame_time)
+    }
+
+    /// Sets the unscaled time spent on the current frame.
+    fn set_elapsed(&mut self, elapsed: Duration) {
+        match &mut self.paused {
+            Some(paused) => *paused = elapsed,
+            None => {
+                let now = self.clock.now();
+                self.last_frame_time = now.checked_sub(elapsed).unwrap_or(now);
+            }
+        }
+    }
+}
+
+impl AnimatedTexture for StreamingPlayer {
+    fn update(&mut self) {
+        StreamingPlayer::update(self)
+    }
+
+    fn texture(&self) -> &Texture {
+        StreamingPlayer::texture(self)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width(), self.height())
+    }
+
+    fn frame_count(&self) -> usize {
+        StreamingPlayer::frame_count(self)
+    }
+
+    fn current_frame_index(&self) -> usize {
+        self.current_frame_index
+    }
+
+    fn frame_at(&self, index: usize) -> Option<&WebpFrame> {
+        (index == self.current_frame_index).then_some(&self.current_frame)
+    }
+
+    fn duration(&self) -> Duration {
+        StreamingPlayer::duration(self)
+    }
+}
+
+impl fmt::Debug for StreamingPlayer {
+    /// Summarizes the animation and playhead instead of dumping frames and GPU handles.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingPlayer")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("frame_count", &self.frame_count())
+            .field("current_frame_index", &self.current_frame_index)
+            .field("window", &self.window)
+            .field("is_playing", &self.is_playing())
+            .field("is_looping", &self.is_looping)
+            .field("speed", &self.speed)
+            .finish()
+    }
+}
+
+impl Drop for StreamingPlayer {
+    fn drop(&mut self) {
+        self.shared.plan.lock().unwrap().closed = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+/// Returns the number of frames to keep decoded for `distance`: the most
+/// frames it spans from any frame of the animation, and at least one.
+fn window_size(distance: Prefetch, durations: &[Duration]) -> usize {
+    let len = durations.len();
+    let most = match distance {
+        Prefetch::Frames(frames) => frames,
+        Prefetch::Time(_) => (0..len)
+            .map(|current| {
+                let mut offset = Duration::ZERO;
+                (1..len)
+                    .take_while(|&ahead| {
+                        let included = distance.includes(ahead, offset);
+                        offset += durations[(current + ahead) % len];
+                        included
+                    })
+                    .count()
+            })
+            .max()
+            .unwrap_or(0),
+    };
+    most.max(1)
+}
+
+/// Returns the index of the frame after `index` in an animation of `len`
+/// frames, or `None` at the end of a non-looping animation.
+fn next_index(index: usize, len: usize, looping: bool) -> Option<usize> {
+    match index + 1 {
+        next if next < len => Some(next),
+        _ if looping => Some(0),
+        _ => None,
+    }
+}
+
+/// Body of the decoding thread: decodes frames in playback order, blocking
+/// while the window is full, until the player is dropped.
+fn decode_ahead(demuxer: &WebpDemuxer, shared: &Shared, sender: &SyncSender<Decoded>) {
+    let mut canvas = Vec::new();
+    let mut previous = None;
+    let (mut generation, mut looping, mut index) = (0, false, None);
+    loop {
+        {
+            let mut plan = shared.plan.lock().unwrap();
+            // Wait for a new plan once the current one has run out of frames.
+            while !plan.closed && plan.generation == generation && index.is_none() {
+                plan = shared.changed.wait(plan).unwrap();
+            }
+            if plan.closed {
+                return;
+            }
+            if plan.generation != generation {
+                (generation, looping, index) = (plan.generation, plan.looping, plan.start);
+            }
+        }
+        let Some(current) = index else {
+            continue;
+        };
+
+        // `composite` only draws the new frame when `canvas` holds the one
+        // before it, so playing straight through decodes each frame once.
+        let image = demuxer
+            .composite(&mut canvas, previous, current)
+            .ok()
+            .and_then(|()| RgbaImage::from_raw(demuxer.width(), demuxer.height(), canvas.clone()));
+        let Some(image) = image else {
+            // A broken frame stalls playback on the frame before it until the
+            // player seeks elsewhere.
+            (previous, index) = (None, None);
+            continue;
+        };
+        previous = Some(current);
+        let frame = WebpFrame {
+            image: DynamicImage::ImageRgba8(image),
+            duration: demuxer.frame_duration(current).unwrap_or_default(),
+        };
+        if sender.send((generation, current, frame)).is_err() {
+            return;
+        }
+        index = next_index(current, demuxer.frame_count(), looping);
+    }
+}
+
+/// Writes the pixels of `frame` into `texture`, which has the canvas size.
+fn write_frame(device_queue_pair: &DeviceQueuePair, texture: &Texture, frame: &WebpFrame) {
+    let [width, height] = texture.size();
+    device_queue_pair.queue().write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        // Frames are always decoded as RGBA8.
+        frame.image.as_bytes(),
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width * 4),
+            rows_per_image: Some(height),
+        },
+        texture.extent(),
+    );
+}
@@ -0,0 +1,9 @@
+//! Raw FFI bindings to libwebp, libwebpdemux and libwebpmux.
+//!
+//! These are the same bindgen-generated items the crate is built on, so
+//! functions it does not wrap yet can be called without a separate `-sys`
+//! crate. Everything here is `unsafe` to call and follows the C API of the
+//! linked libwebp; see its headers for the contracts. The encoder and mux
+//! items are only present with the `encode` feature.
+
+pub use crate::bindings::*;
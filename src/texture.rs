@@ -0,0 +1,73 @@
+use crate::animation::WebpAnimationPlayer;
+use crate::frame::WebpFrame;
+use nannou::wgpu::Texture;
+use std::time::Duration;
+
+/// A texture that changes over time, independent of where its frames come from.
+///
+/// Sketches and libraries can accept any animated texture instead of a concrete
+/// player type:
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # use nannou_webp_animation::AnimatedTexture;
+/// fn draw_animated<A: AnimatedTexture>(draw: &Draw, animation: &A, position: Point2) {
+///     let (w, h) = animation.size();
+///     draw.texture(animation.texture())
+///         .xy(position)
+///         .w_h(w as f32, h as f32);
+/// }
+/// ```
+pub trait AnimatedTexture {
+    /// Advances playback according to the elapsed time. Call once per update.
+    fn update(&mut self);
+
+    /// Returns the texture to draw for the current playhead position.
+    fn texture(&self) -> &Texture;
+
+    /// Returns the canvas width and height in pixels.
+    fn size(&self) -> (u32, u32);
+
+    /// Returns the number of frames.
+    fn frame_count(&self) -> usize;
+
+    /// Returns the index of the frame currently being shown.
+    fn current_frame_index(&self) -> usize;
+
+    /// Returns the decoded frame at `index`, or `None` if it is out of range or
+    /// not kept in CPU memory.
+    fn frame_at(&self, index: usize) -> Option<&WebpFrame>;
+
+    /// Returns the time one playthrough takes at normal speed.
+    fn duration(&self) -> Duration;
+}
+
+impl AnimatedTexture for WebpAnimationPlayer {
+    fn update(&mut self) {
+        WebpAnimationPlayer::update(self)
+    }
+
+    fn texture(&self) -> &Texture {
+        WebpAnimationPlayer::texture(self)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width(), self.height())
+    }
+
+    fn frame_count(&self) -> usize {
+        WebpAnimationPlayer::frame_count(self)
+    }
+
+    fn current_frame_index(&self) -> usize {
+        WebpAnimationPlayer::current_frame_index(self)
+    }
+
+    fn frame_at(&self, index: usize) -> Option<&WebpFrame> {
+        WebpAnimationPlayer::frame_at(self, index)
+    }
+
+    fn duration(&self) -> Duration {
+        WebpAnimationPlayer::duration(self)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::frame::WebpFrame;
+use nannou::image::GenericImageView;
+use nannou::wgpu::{self, DeviceQueuePair, Texture};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+/// The RGBA8 pixels of one frame, ready to be written into a texture.
+struct StagedFrame {
+    /// Index of the frame in the animation.
+    index: usize,
+    /// Width of the frame in pixels.
+    width: u32,
+    /// Height of the frame in pixels.
+    height: u32,
+    /// Tightly packed RGBA8 rows.
+    pixels: Vec<u8>,
+}
+
+impl StagedFrame {
+    /// Creates a texture like `Texture::from_image` and writes the pixels into it.
+    fn upload(&self, device_queue_pair: &DeviceQueuePair) -> Texture {
+        let texture = wgpu::TextureBuilder::new()
+            .size([self.width, self.height])
+            .format(wgpu::TextureFormat::Rgba8UnormSrgb)
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+            .build(device_queue_pair.device());
+
+        device_queue_pair.queue().write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &self.pixels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(self.width * 4),
+                rows_per_image: Some(self.height),
+            },
+            texture.extent(),
+        );
+
+        texture
+    }
+}
+
+/// Prepares the textures of upcoming frames away from the draw path.
+///
+/// A staging thread converts requested frames to RGBA8, the expensive part
+/// of an upload; [`upload_staged`](Self::upload_staged) then only copies the
+/// finished pixels to the GPU. It runs on the main thread because the player
+/// owns the textures and submits through the window's queue. The thread
+/// exits when the uploader is dropped.
+pub(crate) struct TextureUploader {
+    /// Frame indices to stage, in request order.
+    requests: Sender<usize>,
+    /// Frames the staging thread has finished.
+    staged: Receiver<StagedFrame>,
+    /// Whether each frame has been requested, so it is staged only once.
+    requested: Vec<bool>,
+}
+
+impl TextureUploader {
+    /// Starts a staging thread for `frames`.
+    pub(crate) fn new(frames: Arc<Vec<WebpFrame>>) -> Self {
+        let (requests, pending) = mpsc::channel::<usize>();
+        let (finished, staged) = mpsc::channel();
+        let requested = vec![false; frames.len()];
+        thread::spawn(move || {
+            for index in pending {
+                let image = &frames[index].image;
+                let (width, height) = image.dimensions();
+                let frame = StagedFrame {
+                    index,
+                    width,
+                    height,
+                    pixels: image.to_rgba8().into_raw(),
+                };
+                if finished.send(frame).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            staged,
+            requested,
+        }
+    }
+
+    /// Queues the frame at `index` for staging unless it was queued before.
+    pub(crate) fn request(&mut self, index: usize) {
+        if !std::mem::replace(&mut self.requested[index], true) {
+            let _ = self.requests.send(index);
+        }
+    }
+
+    /// Uploads the frames staged since the last call without waiting for
+    /// the ones still being converted, passing each texture to `store`.
+    pub(crate) fn upload_staged<F>(&self, device_queue_pair: &DeviceQueuePair, mut store: F)
+    where
+        F: FnMut(usize, Texture),
+    {
+        for frame in self.staged.try_iter() {
+            store(frame.index, frame.upload(device_queue_pair));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use nannou::image::{DynamicImage, ImageBuffer, Rgba};
+use image::{imageops, DynamicImage, GenericImageView, ImageBuffer, Rgba, RgbaImage};
 
 /// Creates a `DynamicImage` from raw RGBA data.
 ///
@@ -29,3 +29,136 @@ pub fn create_image_from_raw(width: u32, height: u32, rgba_data: Vec<u8>) -> Opt
     let buffer = ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, rgba_data)?;
     Some(DynamicImage::ImageRgba8(buffer))
 }
+
+/// Linearly blends two RGBA buffers of equal length into `out`.
+///
+/// # Parameters
+///
+/// - `from`: The RGBA data shown when `t` is `0.0`.
+/// - `to`: The RGBA data shown when `t` is `1.0`.
+/// - `t`: The blend weight of `to`, clamped to `0.0..=1.0`.
+/// - `out`: The destination buffer. Only `min(from.len(), to.len(), out.len())` bytes are written.
+///
+/// # Examples
+///
+/// ```rust
+/// # use nannou_webp_animation::utils::blend_rgba;
+/// let black = [0, 0, 0, 255];
+/// let white = [255, 255, 255, 255];
+/// let mut out = [0; 4];
+/// blend_rgba(&black, &white, 0.5, &mut out);
+/// assert_eq!(out, [128, 128, 128, 255]);
+/// ```
+pub fn blend_rgba(from: &[u8], to: &[u8], t: f32, out: &mut [u8]) {
+    let t = t.clamp(0.0, 1.0);
+    for ((dst, &a), &b) in out.iter_mut().zip(from).zip(to) {
+        *dst = (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    }
+}
+
+/// Centres `image` on a transparent canvas of the given size.
+///
+/// Images that already have the requested size are returned unchanged; larger
+/// images are clipped.
+///
+/// # Parameters
+///
+/// - `image`: The image to place on the canvas.
+/// - `width`: The width of the canvas in pixels.
+/// - `height`: The height of the canvas in pixels.
+pub fn letterbox(image: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    if image.dimensions() == (width, height) {
+        return image.clone();
+    }
+
+    let mut canvas = RgbaImage::new(width, height);
+    let x = width.saturating_sub(image.width()) / 2;
+    let y = height.saturating_sub(image.height()) / 2;
+    imageops::replace(&mut canvas, &image.to_rgba8(), x, y);
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// Error-diffusion or threshold pattern applied when reducing colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dithering {
+    /// Round every channel to the nearest level.
+    #[default]
+    None,
+    /// Offset channels with a 4x4 Bayer threshold matrix.
+    Ordered,
+    /// Diffuse the rounding error to neighbouring pixels (Floyd–Steinberg).
+    FloydSteinberg,
+}
+
+/// 4x4 Bayer threshold matrix used for ordered dithering.
+const BAYER_4X4: [[f32; 4]; 4] = [
+    [0.0, 8.0, 2.0, 10.0],
+    [12.0, 4.0, 14.0, 6.0],
+    [3.0, 11.0, 1.0, 9.0],
+    [15.0, 7.0, 13.0, 5.0],
+];
+
+/// Reduces each color channel of `image` to `levels` evenly spaced values.
+///
+/// This yields a palette of `levels³` colors. The alpha channel is left untouched.
+///
+/// # Parameters
+///
+/// - `image`: The image to quantize in place.
+/// - `levels`: The number of values per channel, clamped to at least 2.
+/// - `dithering`: How rounding errors are distributed.
+pub fn quantize(image: &mut RgbaImage, levels: u8, dithering: Dithering) {
+    let step = 255.0 / (levels.max(2) - 1) as f32;
+    let round = |v: f32| ((v / step).round() * step).clamp(0.0, 255.0);
+    let (width, height) = image.dimensions();
+
+    match dithering {
+        Dithering::None => {
+            for pixel in image.pixels_mut() {
+                for c in 0..3 {
+                    pixel[c] = round(pixel[c] as f32) as u8;
+                }
+            }
+        }
+        Dithering::Ordered => {
+            for (x, y, pixel) in image.enumerate_pixels_mut() {
+                let threshold = (BAYER_4X4[(y % 4) as usize][(x % 4) as usize] + 0.5) / 16.0 - 0.5;
+                for c in 0..3 {
+                    pixel[c] = round(pixel[c] as f32 + threshold * step) as u8;
+                }
+            }
+        }
+        Dithering::FloydSteinberg => {
+            let (w, h) = (width as usize, height as usize);
+            let mut values: Vec<f32> = image.as_raw().iter().map(|&v| v as f32).collect();
+            for y in 0..h {
+                for x in 0..w {
+                    for c in 0..3 {
+                        let i = (y * w + x) * 4 + c;
+                        let old = values[i];
+                        let new = round(old);
+                        values[i] = new;
+                        let error = old - new;
+
+                        let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+                            let nx = x as isize + dx;
+                            let ny = y + dy;
+                            if nx >= 0 && (nx as usize) < w && ny < h {
+                                values[(ny * w + nx as usize) * 4 + c] += error * weight;
+                            }
+                        };
+                        diffuse(1, 0, 7.0 / 16.0);
+                        diffuse(-1, 1, 3.0 / 16.0);
+                        diffuse(0, 1, 5.0 / 16.0);
+                        diffuse(1, 1, 1.0 / 16.0);
+                    }
+                }
+            }
+            for (dst, (i, &v)) in image.iter_mut().zip(values.iter().enumerate()) {
+                if i % 4 != 3 {
+                    *dst = v.clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+    }
+}
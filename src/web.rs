@@ -0,0 +1,70 @@
+#[cfg(feature = "nannou")]
+use crate::animation::WebpAnimationPlayer;
+use crate::data::WebpAnimationData;
+use crate::error::{Result, WebpAnimationError};
+#[cfg(feature = "nannou")]
+use nannou::App;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+/// Downloads the body of `url` with the browser's `fetch`.
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>> {
+    let window =
+        web_sys::window().ok_or_else(|| WebpAnimationError::Http("no global `window`".into()))?;
+    let response: Response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .and_then(JsCast::dyn_into)
+        .map_err(js_error)?;
+    if !response.ok() {
+        return Err(WebpAnimationError::Http(format!(
+            "{} responded with status {}",
+            url,
+            response.status()
+        )));
+    }
+
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// Converts an exception thrown by a browser API.
+fn js_error(value: JsValue) -> WebpAnimationError {
+    WebpAnimationError::Http(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+}
+
+impl WebpAnimationData {
+    /// Downloads the animation at `url` with the browser's `fetch` API and
+    /// decodes it.
+    ///
+    /// Only available on WebAssembly, where files cannot be read from disk.
+    /// The download does not block the page; decoding runs when it completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, the server does not respond with
+    /// a success status, or the body cannot be decoded.
+    pub async fn fetch(url: &str) -> Result<Self> {
+        Self::from_bytes(&fetch_bytes(url).await?)
+    }
+}
+
+#[cfg(feature = "nannou")]
+impl WebpAnimationPlayer {
+    /// Downloads and decodes the animation at `url`, then uploads the textures;
+    /// see [`WebpAnimationData::fetch`].
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The URL of the animation, relative to the page or absolute.
+    /// - `app`: Reference to the Nannou `App` instance, used for creating textures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the body cannot be decoded.
+    pub async fn fetch(url: &str, app: &App) -> Result<Self> {
+        Ok(WebpAnimationData::fetch(url).await?.into_player(app))
+    }
+}
@@ -1,2 +1,6 @@
 #include <webp/decode.h>
 #include <webp/demux.h>
+#ifndef NANNOU_WEBP_NO_ENCODE
+#include <webp/encode.h>
+#include <webp/mux.h>
+#endif